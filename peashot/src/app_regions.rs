@@ -0,0 +1,90 @@
+//! Preferred regions for specific applications
+//!
+//! When ferrishot launches, the focused window is detected. If its application has
+//! a region in the config, that region will be the initial selection.
+//!
+//! ```kdl
+//! app-regions {
+//!   app "code" "1800x1000+60+80"
+//! }
+//! ```
+
+use crate::lazy_rect::LazyRectangle;
+
+/// The `app-regions` node in the config
+#[derive(ferrishot_knus::Decode, Debug, Clone, Default)]
+pub struct AppRegions {
+    /// Each application with its region
+    #[ferrishot_knus(children(name = "app"))]
+    pub apps: Vec<AppRegion>,
+}
+
+/// Preferred region for a single application
+#[derive(ferrishot_knus::Decode, Debug, Clone)]
+pub struct AppRegion {
+    /// Name of the application, or its window class. Case-insensitive
+    #[ferrishot_knus(argument)]
+    pub app: String,
+    /// The region to select, in the same format as `--region`
+    #[ferrishot_knus(argument, str)]
+    pub region: LazyRectangle,
+}
+
+impl AppRegions {
+    /// Region for the application with the given name
+    pub fn get(&self, app_name: &str) -> Option<LazyRectangle> {
+        self.apps
+            .iter()
+            .find(|app| app.app.eq_ignore_ascii_case(app_name))
+            .map(|app| app.region)
+    }
+
+    /// Region of the application which is currently focused
+    ///
+    /// Has to be called before the window of ferrishot is created,
+    /// otherwise ferrishot itself will be the focused application
+    pub fn for_focused_app(&self) -> Option<LazyRectangle> {
+        if self.apps.is_empty() {
+            return None;
+        }
+
+        let app_name = focused_app_name()?;
+        log::info!("Focused application: {app_name}");
+
+        self.get(&app_name)
+    }
+}
+
+/// Name of the application with the focused window
+fn focused_app_name() -> Option<String> {
+    xcap::Window::all()
+        .inspect_err(|err| log::error!("Failed to get the list of windows: {err}"))
+        .ok()?
+        .into_iter()
+        .find(|window| window.is_focused().unwrap_or(false))?
+        .app_name()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr as _;
+
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn get_is_case_insensitive() {
+        let region = LazyRectangle::from_str("100x200+10+20").unwrap();
+        let app_regions = AppRegions {
+            apps: vec![AppRegion {
+                app: "Code".to_string(),
+                region,
+            }],
+        };
+
+        assert_eq!(app_regions.get("code"), Some(region));
+        assert_eq!(app_regions.get("CODE"), Some(region));
+        assert_eq!(app_regions.get("firefox"), None);
+    }
+}
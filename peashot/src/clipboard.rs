@@ -2,56 +2,173 @@
 //!
 //! - PNG image
 //! - Text
+//! - HTML, with a text alternative
+//! - HTML, with a text alternative and the encoded image it refers to
 //!
 //! This module includes a small daemon for Linux that runs in the background,
 //! providing clipboard access.
+//!
+//! On Wayland, the daemon talks to the compositor with the `ext-data-control` protocol
+//! (or `wlr-data-control` on compositors which only have that, like older sway and Hyprland).
+//! It falls back to `arboard` on compositors without either, like GNOME.
 
 /// An argument that can be passed into the program to signal that it should daemonize itself. This
 /// can be anything as long as it is unlikely to be passed in by the user by mistake.
 #[cfg(target_os = "linux")]
 pub const CLIPBOARD_DAEMON_ID: &str = "__ferrishot_clipboard_daemon";
 
-use std::{fs::File, io::Write as _};
+use std::borrow::Cow;
 
 /// Error with the clipboard
+///
+/// The messages say what the user can do about the error on their platform
 #[derive(thiserror::Error, miette::Diagnostic, Debug)]
 pub enum ClipboardError {
+    /// Another application holds the clipboard
+    #[cfg_attr(
+        target_os = "windows",
+        error(
+            "Another application is holding the clipboard open. Close clipboard managers or remote desktop tools and try again"
+        )
+    )]
+    #[cfg_attr(
+        not(target_os = "windows"),
+        error("Another application is using the clipboard, try again")
+    )]
+    Occupied,
+    /// There is no clipboard which can be used
+    #[cfg_attr(
+        target_os = "linux",
+        error(
+            "No clipboard was found. On Wayland, the compositor must support the ext-data-control or wlr-data-control protocol. Otherwise an X11 server is needed"
+        )
+    )]
+    #[cfg_attr(
+        target_os = "macos",
+        error("The clipboard cannot be accessed. Check that ferrishot is not sandboxed")
+    )]
+    #[cfg_attr(
+        not(any(target_os = "linux", target_os = "macos")),
+        error("The clipboard cannot be accessed in this session")
+    )]
+    NotSupported,
+    /// The content could not be converted into a format which the clipboard stores
+    #[error("The clipboard does not accept this content, save it to a file instead")]
+    Conversion,
+    /// After copying, the clipboard holds something else
+    #[error(
+        "The clipboard holds something else right after copying. A clipboard manager may have replaced it"
+    )]
+    NotVerified,
     /// Arboard Error
     #[error(transparent)]
-    Arboard(#[from] arboard::Error),
+    Arboard(arboard::Error),
     /// IO Error
     #[error(transparent)]
     Io(#[from] std::io::Error),
 }
 
-/// Set the text content of the clipboard
-pub fn set_text(text: &str) -> Result<(), ClipboardError> {
-    #[cfg(target_os = "linux")]
-    {
-        use std::process;
-        process::Command::new(std::env::current_exe()?)
-            .arg(CLIPBOARD_DAEMON_ID)
-            .arg("text")
-            .arg(text)
-            .stdin(process::Stdio::null())
-            .stdout(process::Stdio::null())
-            .stderr(process::Stdio::null())
-            .current_dir("/")
-            .spawn()?;
+/// The clipboard daemon could not be spawned, or it failed
+#[cfg(target_os = "linux")]
+#[derive(thiserror::Error, miette::Diagnostic, Debug)]
+pub enum DaemonError {
+    /// The daemon could not be spawned
+    #[error("could not spawn it: {0}")]
+    Spawn(#[from] std::io::Error),
+    /// The daemon exited with an error
+    #[error("{status}: {stderr}")]
+    Failed {
+        /// How the daemon exited
+        status: std::process::ExitStatus,
+        /// What the daemon printed before exiting
+        stderr: String,
+    },
+}
+
+impl From<arboard::Error> for ClipboardError {
+    fn from(err: arboard::Error) -> Self {
+        match err {
+            arboard::Error::ClipboardOccupied => Self::Occupied,
+            arboard::Error::ClipboardNotSupported => Self::NotSupported,
+            arboard::Error::ConversionFailure => Self::Conversion,
+            err => Self::Arboard(err),
+        }
     }
-    #[cfg(not(target_os = "linux"))]
-    {
-        arboard::Clipboard::new()?.set_text(text)?;
+}
+
+/// Which backend is used to provide the clipboard
+pub fn backend() -> String {
+    let provider = match crate::image::display_server() {
+        "wayland" => "wayland data-control, with wl-clipboard-rs",
+        "x11" => "x11",
+        "windows" => "win32",
+        "quartz" => "NSPasteboard",
+        _ => "unknown",
+    };
+
+    if cfg!(target_os = "linux") {
+        format!("arboard ({provider}, via daemon)")
+    } else {
+        format!("arboard ({provider})")
     }
+}
+
+/// Content to place onto the clipboard, in a single transaction
+///
+/// The clipboard can hold several representations of the same content. The application
+/// which pastes picks the one it prefers: a rich text editor takes the HTML, while
+/// a terminal takes the plain text.
+///
+/// Only offers which are supported on every platform can be made
+#[derive(Debug)]
+pub enum Offer<'a> {
+    /// Plain text, `text/plain`
+    Text(Cow<'a, str>),
+    /// Rich text, `text/html`, along with a plain text alternative
+    Html {
+        /// The HTML
+        html: Cow<'a, str>,
+        /// Plain text, for applications which can't paste HTML
+        alt_text: Cow<'a, str>,
+    },
+    /// An image, `image/png` on Linux
+    Image(arboard::ImageData<'a>),
+    /// Rich text and plain text which refer to an image, along with the image itself,
+    /// encoded as PNG or JPEG. Image editors paste the image
+    ///
+    /// Only the data-control protocol of Wayland can offer all 3 at once. Elsewhere,
+    /// the image is left out and this is the same as [`Offer::Html`]
+    HtmlWithImage {
+        /// The HTML
+        html: Cow<'a, str>,
+        /// Plain text, for applications which can't paste HTML
+        alt_text: Cow<'a, str>,
+        /// The encoded image
+        image: Cow<'a, [u8]>,
+    },
+}
 
+/// Check whether the clipboard can be used at all, which is not the case in some
+/// Wayland sessions and containers
+///
+/// Checking once at startup lets the app offer saving or uploading instead,
+/// rather than failing only once something is copied
+pub fn check() -> Result<(), ClipboardError> {
+    arboard::Clipboard::new()?;
     Ok(())
 }
 
+/// Set the text content of the clipboard
+pub fn set_text(text: &str) -> Result<(), ClipboardError> {
+    set(Offer::Text(text.into()))
+}
+
 /// Set the image content of the clipboard
-///
-/// # Returns
-///
-/// Temporary file of the saved image
+pub fn set_image(image_data: arboard::ImageData) -> Result<(), ClipboardError> {
+    set(Offer::Image(image_data))
+}
+
+/// Place the `offer` onto the clipboard
 #[cfg_attr(
     target_os = "linux",
     expect(
@@ -59,32 +176,117 @@ pub fn set_text(text: &str) -> Result<(), ClipboardError> {
         reason = "on non-linux it is passed by value"
     )
 )]
-pub fn set_image(image_data: arboard::ImageData) -> Result<std::path::PathBuf, ClipboardError> {
-    let clipboard_buffer_path = tempfile::Builder::new().keep(true).tempfile()?;
-    let mut clipboard_buffer_file = File::create(&clipboard_buffer_path)?;
-    clipboard_buffer_file.write_all(&image_data.bytes)?;
-
+pub fn set(offer: Offer) -> Result<(), ClipboardError> {
     #[cfg(target_os = "linux")]
     {
         use std::process;
-        process::Command::new(std::env::current_exe()?)
-            .arg(CLIPBOARD_DAEMON_ID)
-            .arg("image")
-            .arg(image_data.width.to_string())
-            .arg(image_data.height.to_string())
-            .arg(clipboard_buffer_path.path())
+
+        let mut daemon = process::Command::new(std::env::current_exe()?);
+        daemon.arg(CLIPBOARD_DAEMON_ID);
+
+        match &offer {
+            Offer::Text(text) => {
+                daemon.arg("text").arg(&**text);
+            }
+            Offer::Html { html, alt_text } => {
+                daemon.arg("html").arg(&**html).arg(&**alt_text);
+            }
+            Offer::HtmlWithImage {
+                html,
+                alt_text,
+                image,
+            } => {
+                let image_path = crate::temp::write("clipboard-image", image)?.hand_off()?;
+
+                daemon
+                    .arg("html")
+                    .arg(&**html)
+                    .arg(&**alt_text)
+                    .arg(image_path);
+            }
+            Offer::Image(image_data) => {
+                // the daemon removes the file as soon as it has read it, as it may keep
+                // running for a long time until something else is copied
+                let clipboard_buffer_path =
+                    crate::temp::write("clipboard-image", &image_data.bytes)?.hand_off()?;
+
+                daemon
+                    .arg("image")
+                    .arg(image_data.width.to_string())
+                    .arg(image_data.height.to_string())
+                    .arg(clipboard_buffer_path);
+            }
+        }
+
+        daemon
             .stdin(process::Stdio::null())
             .stdout(process::Stdio::null())
             .stderr(process::Stdio::inherit())
             .current_dir("/")
             .spawn()?;
     }
+    // There is no daemon, so read the clipboard back to make sure the copy worked,
+    // rather than finding out only when pasting. Images can only be read back on Windows
     #[cfg(not(target_os = "linux"))]
     {
-        arboard::Clipboard::new()?.set_image(image_data)?;
+        let mut clipboard = arboard::Clipboard::new()?;
+
+        let is_verified = match offer {
+            Offer::Text(text) => {
+                clipboard.set_text(text.clone())?;
+                clipboard.get_text().is_ok_and(|copied| copied == text)
+            }
+            Offer::Html { html, alt_text } | Offer::HtmlWithImage { html, alt_text, .. } => {
+                clipboard.set_html(html, Some(alt_text.clone()))?;
+                clipboard.get_text().is_ok_and(|copied| copied == alt_text)
+            }
+            // Windows stores the image as a PNG, which reads back the same pixels
+            #[cfg(target_os = "windows")]
+            Offer::Image(image_data) => {
+                let expected = image_data.to_owned_img();
+                clipboard.set_image(image_data)?;
+                clipboard.get_image().is_ok_and(|copied| {
+                    (copied.width, copied.height, copied.bytes)
+                        == (expected.width, expected.height, expected.bytes)
+                })
+            }
+            // macOS converts the image into its own color space, so the pixels read back
+            // are different and can't tell whether the copy worked
+            #[cfg(not(target_os = "windows"))]
+            Offer::Image(image_data) => {
+                clipboard.set_image(image_data)?;
+                true
+            }
+        };
+
+        if !is_verified {
+            return Err(ClipboardError::NotVerified);
+        }
     }
 
-    Ok(clipboard_buffer_path.path().to_path_buf())
+    Ok(())
+}
+
+/// Spawn the clipboard daemon, which opens the clipboard and exits right away without
+/// changing what it holds
+///
+/// This is how `ferrishot doctor` checks that the daemon works
+#[cfg(target_os = "linux")]
+pub fn probe_daemon() -> Result<(), DaemonError> {
+    let output = std::process::Command::new(std::env::current_exe()?)
+        .arg(CLIPBOARD_DAEMON_ID)
+        .arg("probe")
+        .stdin(std::process::Stdio::null())
+        .output()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(DaemonError::Failed {
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
+    }
 }
 
 /// Runs a process in the background that provides clipboard access,
@@ -103,7 +305,7 @@ pub fn set_image(image_data: arboard::ImageData) -> Result<std::path::PathBuf, C
 /// We expect that the daemon receives 4 arguments:
 ///
 /// 1. ID of the daemon
-/// 2. copy type: "image" or "text"
+/// 2. copy type: "image", "text", "html" or "probe"
 ///
 /// if copy type is "image" we expect:
 ///   3. width of image
@@ -113,11 +315,20 @@ pub fn set_image(image_data: arboard::ImageData) -> Result<std::path::PathBuf, C
 ///   The image must be of valid width, height and byte amount
 /// if copy type is "text" we expect:
 ///   3. text content which should be copied to the clipboard
+/// if copy type is "html" we expect:
+///   3. html content which should be copied to the clipboard
+///   4. text alternative for the html
+///   5. optionally, path to the encoded image which the html refers to
+/// if copy type is "probe" we expect nothing else. The clipboard is only opened,
+/// to check that it can be
 #[cfg(target_os = "linux")]
 pub fn run_clipboard_daemon() -> Result<(), arboard::Error> {
     use arboard::SetExtLinux as _;
     use pretty_assertions::assert_eq;
     use std::fs;
+    use wl_clipboard_rs::copy::{MimeSource, MimeType, Source};
+
+    let is_wayland = crate::image::display_server() == "wayland";
 
     log::info!(
         "Spawned clipboard daemon with arguments: {:?}",
@@ -147,6 +358,9 @@ pub fn run_clipboard_daemon() -> Result<(), arboard::Error> {
                 .expect("valid image height");
             let path = args.next().expect("image path");
             let bytes: std::borrow::Cow<[u8]> = fs::read(&path).expect("image contents").into();
+            if let Err(err) = fs::remove_file(&path) {
+                log::warn!("Failed to remove the image file {path}: {err}");
+            }
 
             assert_eq!(args.next(), None, "unexpected extra args");
             assert_eq!(
@@ -155,23 +369,124 @@ pub fn run_clipboard_daemon() -> Result<(), arboard::Error> {
                 "every 4 bytes in `bytes` represents a single RGBA pixel"
             );
 
-            arboard::Clipboard::new()?
-                .set()
-                .wait()
-                .image(arboard::ImageData {
-                    width,
-                    height,
-                    bytes,
-                })?;
+            let is_copied = is_wayland
+                && encode_png(width, height, &bytes).is_some_and(|png| {
+                    copy_on_wayland(vec![MimeSource {
+                        source: Source::Bytes(png.into()),
+                        mime_type: MimeType::Specific("image/png".to_string()),
+                    }])
+                });
 
-            fs::remove_file(path).expect("failed to remove file");
+            if !is_copied {
+                arboard::Clipboard::new()?
+                    .set()
+                    .wait()
+                    .image(arboard::ImageData {
+                        width,
+                        height,
+                        bytes,
+                    })?;
+            }
         }
         "text" => {
             let text = args.next().expect("text");
             assert_eq!(args.next(), None, "unexpected extra args");
-            arboard::Clipboard::new()?.set().wait().text(text)?;
+
+            let is_copied = is_wayland
+                && copy_on_wayland(vec![MimeSource {
+                    source: Source::Bytes(text.as_bytes().into()),
+                    mime_type: MimeType::Text,
+                }]);
+
+            if !is_copied {
+                arboard::Clipboard::new()?.set().wait().text(text)?;
+            }
         }
-        _ => panic!("invalid copy type, expected `image` or `text`"),
+        "html" => {
+            let html = args.next().expect("html");
+            let alt_text = args.next().expect("alt text");
+            let image = args.next().map(|path| {
+                let bytes = fs::read(&path).expect("image contents");
+                if let Err(err) = fs::remove_file(&path) {
+                    log::warn!("Failed to remove the image file {path}: {err}");
+                }
+                bytes
+            });
+            assert_eq!(args.next(), None, "unexpected extra args");
+
+            let mut sources = vec![
+                MimeSource {
+                    source: Source::Bytes(html.as_bytes().into()),
+                    mime_type: MimeType::Specific("text/html".to_string()),
+                },
+                MimeSource {
+                    source: Source::Bytes(alt_text.as_bytes().into()),
+                    mime_type: MimeType::Text,
+                },
+            ];
+            if let Some(image) = image {
+                match image::guess_format(&image) {
+                    Ok(format) => sources.push(MimeSource {
+                        source: Source::Bytes(image.into()),
+                        mime_type: MimeType::Specific(format.to_mime_type().to_string()),
+                    }),
+                    Err(err) => log::warn!("Leaving out the image, its format is unknown: {err}"),
+                }
+            }
+
+            // arboard can't offer the image along with the html
+            let is_copied = is_wayland && copy_on_wayland(sources);
+
+            if !is_copied {
+                arboard::Clipboard::new()?
+                    .set()
+                    .wait()
+                    .html(html, Some(alt_text))?;
+            }
+        }
+        "probe" => {
+            assert_eq!(args.next(), None, "unexpected extra args");
+
+            arboard::Clipboard::new()?;
+        }
+        _ => panic!("invalid copy type, expected `image`, `text`, `html` or `probe`"),
     }
     Ok(())
 }
+
+/// Offer the `sources` with the data-control protocol of the Wayland compositor,
+/// and keep serving them until something else is copied
+///
+/// Returns `false` if the compositor has no data-control protocol, so something
+/// else has to copy them
+#[cfg(target_os = "linux")]
+fn copy_on_wayland(sources: Vec<wl_clipboard_rs::copy::MimeSource>) -> bool {
+    let mut options = wl_clipboard_rs::copy::Options::new();
+    options.foreground(true);
+
+    match options.copy_multi(sources) {
+        Ok(()) => true,
+        Err(err) => {
+            log::warn!("Failed to copy with wayland data-control, falling back to arboard: {err}");
+            false
+        }
+    }
+}
+
+/// Encode RGBA `bytes` of an image as PNG, which is how images are offered on Wayland
+#[cfg(target_os = "linux")]
+fn encode_png(width: usize, height: usize, bytes: &[u8]) -> Option<Vec<u8>> {
+    let image = image::RgbaImage::from_raw(
+        u32::try_from(width).ok()?,
+        u32::try_from(height).ok()?,
+        bytes.to_vec(),
+    )?;
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|err| log::warn!("Failed to encode the copied image as PNG: {err}"))
+        .ok()?;
+
+    Some(png)
+}
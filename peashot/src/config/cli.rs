@@ -21,9 +21,13 @@ const STYLES: clap::builder::Styles = clap::builder::Styles::styled()
 
 /// Ferrishot is a powerful screenshot app written in Rust
 #[derive(Parser, Debug)]
-#[command(version, styles = STYLES, long_about = None)]
+#[command(version, disable_version_flag = true, styles = STYLES, long_about = None)]
 #[expect(clippy::struct_excessive_bools, reason = "normal for CLIs")]
 pub struct Cli {
+    /// Subcommand to run instead of taking a screenshot
+    #[command(subcommand)]
+    pub command: Option<Subcommand>,
+
     /// Instead of taking a screenshot of the desktop, open this image instead
     //
     // NOTE: Currently disabled because if the screenshot is not the same size as the desktop,
@@ -50,6 +54,8 @@ pub struct Cli {
     /// - 0.2 for `width` means it region takes up 20% of the width of the image.
     /// - 0.5 for `y` means the top-left corner will be at the vertical center
     ///
+    /// `width`, `height` and `x` can also be arithmetic, like `1920/2x1080/2+0+0`
+    ///
     /// The format can also end with 1 or 2 percentages, which shifts the region relative to the region's size
     /// - If `width` is `250`, end region with `+30%` to move right by 75px or `-40%` to move left by 100px
     /// - Supplying 2 percentage at the end like `+30%-10%`, the 1st affects x-offset and the 2nd affects y-offset
@@ -85,6 +91,14 @@ pub struct Cli {
     #[arg(short, long, value_name = "ACTION")]
     pub accept_on_select: Option<crate::image::action::Command>,
 
+    /// Show the screenshot with a laser pointer, for pointing at things while sharing the screen
+    ///
+    /// Scroll to zoom, drag with the left or middle mouse button to pan and press `0` to reset.
+    /// Nothing can be captured.
+    /// Present an existing image with `ferrishot --present edit <IMAGE>`
+    #[arg(long, conflicts_with_all = ["accept_on_select", "region", "last_region"])]
+    pub present: bool,
+
     /// Wait this long before launch
     #[arg(
         short,
@@ -95,6 +109,15 @@ pub struct Cli {
     )]
     pub delay: Option<Duration>,
 
+    /// Exit if nothing was done for this many seconds
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        long_help = "Exit if no key was pressed and the mouse was not used for this many seconds, for when ferrishot is started by a script and nobody is there. Overrides the `idle-timeout` option. `0` disables it",
+        value_hint = ValueHint::Other
+    )]
+    pub timeout: Option<u32>,
+
     /// Save image to path
     #[arg(
         short,
@@ -105,6 +128,66 @@ pub struct Cli {
     )]
     pub save_path: Option<PathBuf>,
 
+    /// Renderer used to draw ferrishot
+    #[arg(
+        long,
+        value_name = "RENDERER",
+        default_value = "auto",
+        long_help = "Renderer used to draw ferrishot. Use `tiny-skia` if ferrishot does not start because of broken GPU drivers"
+    )]
+    pub renderer: Renderer,
+
+    /// Backend used to take the screenshot
+    #[arg(
+        long,
+        value_name = "BACKEND",
+        default_value = "xcap",
+        long_help = "Backend used to take the screenshot. `fake` draws a test pattern instead of capturing the screen, for trying out ferrishot where the screen can't be captured"
+    )]
+    pub capture_backend: crate::image::CaptureBackendKind,
+
+    /// Monitor to capture: its index, its name or `all`
+    #[arg(
+        long,
+        value_name = "MONITOR",
+        default_value = "current",
+        long_help = "Monitor to capture: its index starting from 0, its name, or `all`. With `all`, the monitors are stitched into a single screenshot, so the selection can cross from one monitor to another. `ferrishot --version --json` lists the monitors. By default, the monitor under the mouse is captured"
+    )]
+    pub monitor: crate::image::MonitorChoice,
+
+    //
+    // --- Window ---
+    //
+    /// Title of the window
+    #[arg(
+        help_heading = "Window",
+        long,
+        value_name = "TITLE",
+        default_value = "ferrishot",
+        value_hint = ValueHint::Other
+    )]
+    pub window_title: String,
+
+    /// Application ID of the window
+    #[arg(
+        help_heading = "Window",
+        long,
+        value_name = "ID",
+        default_value = "ferrishot",
+        long_help = "Application ID of the window. It is the app ID on Wayland and `WM_CLASS` on X11, which window manager rules use to match windows, for example to make ferrishot float or disable its animations",
+        value_hint = ValueHint::Other
+    )]
+    pub app_id: String,
+
+    /// Use this image as the icon of the window
+    #[arg(
+        help_heading = "Window",
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath
+    )]
+    pub window_icon: Option<PathBuf>,
+
     //
     // --- Config ---
     //
@@ -125,7 +208,8 @@ pub struct Cli {
         long,
         value_name = "FILE.KDL",
         default_value_t = DEFAULT_CONFIG_FILE_PATH.to_string_lossy().to_string(),
-        value_hint = ValueHint::FilePath
+        value_hint = ValueHint::FilePath,
+        global = true
     )]
     pub config_file: String,
 
@@ -142,9 +226,34 @@ pub struct Cli {
     pub silent: bool,
 
     /// Print in JSON format
-    #[arg(help_heading = "Output", short, long, conflicts_with = "silent")]
+    #[arg(
+        help_heading = "Output",
+        short,
+        long,
+        conflicts_with = "silent",
+        global = true
+    )]
     pub json: bool,
 
+    /// Write the accepted region to this file
+    #[arg(
+        help_heading = "Output",
+        long,
+        value_name = "PATH",
+        long_help = "Write the accepted region to this file on exit, in the format of `--region`. Use `/dev/fd/<N>` to write to a file descriptor, so scripts can chain ferrishot's region picker into other tools",
+        value_hint = ValueHint::FilePath
+    )]
+    pub write_region_file: Option<PathBuf>,
+
+    /// Print version
+    #[arg(
+        help_heading = "Output",
+        short = 'V',
+        long,
+        long_help = "Print version. With `--json`, also print information about the build and the environment, useful for bug reports"
+    )]
+    pub version: bool,
+
     //
     // --- Debug ---
     //
@@ -181,6 +290,16 @@ pub struct Cli {
     )]
     pub log_file: String,
 
+    /// Format of the log lines
+    #[arg(
+        help_heading = "Debug",
+        long,
+        value_name = "FORMAT",
+        default_value = "text",
+        hide = !cfg!(feature = "debug")
+    )]
+    pub log_format: crate::logging::LogFormat,
+
     /// Filter for specific Rust module or crate, instead of showing logs from all crates
     #[arg(
         help_heading = "Debug",
@@ -198,6 +317,179 @@ pub struct Cli {
         hide = !cfg!(feature = "debug")
     )]
     pub debug: bool,
+
+    /// Record input events and the screenshot to this file
+    #[arg(
+        help_heading = "Debug",
+        long,
+        value_name = "FILE",
+        long_help = "Record input events and the screenshot to this file. Attach it to a bug report, so the bug can be reproduced with `--replay`",
+        value_hint = ValueHint::FilePath,
+        hide = !cfg!(feature = "debug")
+    )]
+    pub record: Option<PathBuf>,
+
+    /// Replay input events recorded with `--record`
+    #[arg(
+        help_heading = "Debug",
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["record", "region", "last_region", "file"],
+        value_hint = ValueHint::FilePath,
+        hide = !cfg!(feature = "debug")
+    )]
+    pub replay: Option<PathBuf>,
+}
+
+impl Cli {
+    /// Move the options of the subcommand into the options which the rest of ferrishot reads,
+    /// so for example `ferrishot capture --region full --to clipboard` is the same as
+    /// `ferrishot --region full --accept-on-select copy-to-clipboard`
+    pub fn apply_subcommand(&mut self) {
+        if let Some(Subcommand::Capture(capture)) = &self.command {
+            self.region = capture.region;
+            self.last_region = capture.last_region;
+            self.accept_on_select = Some(capture.to.action());
+        }
+    }
+}
+
+/// Subcommands of ferrishot
+///
+/// Without a subcommand, ferrishot opens the app just like `ferrishot gui`
+#[derive(clap::Subcommand, Debug)]
+pub enum Subcommand {
+    /// Open the app to take a screenshot. This is the same as not passing a subcommand
+    Gui,
+    /// Capture a region of the screen without opening a window
+    Capture(Capture),
+    /// Open an existing image to crop it, instead of taking a screenshot
+    Edit {
+        /// The image to open
+        #[arg(value_hint = ValueHint::FilePath)]
+        image: PathBuf,
+    },
+    /// Upload an existing image and print the link, without taking a screenshot
+    Upload {
+        /// The image to upload
+        #[arg(value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+        /// Upload to this service. By default, the first service to accept the image is used
+        #[arg(long, value_name = "SERVICE")]
+        service: Option<crate::image::upload::ImageUploadService>,
+    },
+    /// Run commands in a running ferrishot, which has `control-socket #true` in its config
+    ///
+    /// Each command is written just like inside of `on-start`, for example
+    /// `ferrishot ctl 'select-region "100x100+0+0"' copy-to-clipboard`
+    Ctl {
+        /// Commands to run, in order
+        #[arg(required = true, value_name = "COMMAND", value_hint = ValueHint::Other)]
+        commands: Vec<String>,
+    },
+    /// Manage the config file
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Inspect the keybindings
+    #[command(subcommand)]
+    Keys(KeysCommand),
+    /// Check whether ferrishot is able to work in this environment
+    ///
+    /// Checks taking screenshots, clipboard access, permissions of the directories
+    /// that ferrishot writes to and connectivity to the image upload services.
+    Doctor,
+}
+
+/// Options of `ferrishot capture`
+#[derive(clap::Args, Debug)]
+pub struct Capture {
+    /// Region to capture, in the same format as `--region`
+    #[arg(
+        short,
+        long,
+        value_name = "WxH+X+Y",
+        required_unless_present = "last_region",
+        value_hint = ValueHint::Other
+    )]
+    pub region: Option<LazyRectangle>,
+
+    /// Capture the last region
+    #[arg(short, long, conflicts_with = "region")]
+    pub last_region: bool,
+
+    /// Where the screenshot goes
+    #[arg(short, long, value_name = "TARGET", default_value = "clipboard")]
+    pub to: CaptureTarget,
+}
+
+/// Where `ferrishot capture` puts the screenshot
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureTarget {
+    /// Copy the screenshot to the clipboard
+    Clipboard,
+    /// Save the screenshot to `--save-path`, or to a file picked in a dialog
+    File,
+    /// Save the screenshot as a PDF with a single page
+    Pdf,
+    /// Upload the screenshot and print the link
+    Upload,
+}
+
+impl CaptureTarget {
+    /// Action which puts the screenshot there
+    pub const fn action(self) -> crate::image::action::Command {
+        use crate::image::action::Command;
+
+        match self {
+            Self::Clipboard => Command::CopyToClipboard,
+            Self::File => Command::SaveScreenshot,
+            Self::Pdf => Command::SaveAsPdf,
+            Self::Upload => Command::UploadScreenshot,
+        }
+    }
+}
+
+/// Subcommands of `ferrishot config`
+#[derive(clap::Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigCommand {
+    /// Write the default config to the config file. This is the same as `--dump-default-config`
+    Dump,
+    /// Print the path of the config file
+    Path,
+    /// Check the config file for errors, without opening the app
+    Check,
+}
+
+/// Subcommands of `ferrishot keys`
+#[derive(clap::Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeysCommand {
+    /// Print every keybinding in effect: the defaults, the keymap preset and the config
+    /// file merged together. With `--json`, print them in JSON
+    List,
+}
+
+/// Renderer used to draw ferrishot
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Renderer {
+    /// Use the GPU, falling back to the CPU if the GPU can't be used
+    #[default]
+    Auto,
+    /// Use the GPU
+    Wgpu,
+    /// Use the CPU. Slower, but works everywhere
+    TinySkia,
+}
+
+impl Renderer {
+    /// Name of the backend that Iced should use. `None` lets Iced try each
+    /// backend in turn, until one of them initializes
+    pub const fn backend(self) -> Option<&'static str> {
+        match self {
+            Self::Auto => None,
+            Self::Wgpu => Some("wgpu"),
+            Self::TinySkia => Some("tiny-skia"),
+        }
+    }
 }
 
 /// Represents the default location of the config file
@@ -221,3 +513,40 @@ pub static DEFAULT_LOG_FILE_PATH: LazyLock<PathBuf> = LazyLock::new(|| {
         |strategy| strategy.cache_dir().join("ferrishot.log"),
     )
 });
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser as _;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn capture_subcommand() {
+        let mut cli = Cli::try_parse_from(["ferrishot", "capture", "--region", "full"]).unwrap();
+        cli.apply_subcommand();
+        assert!(cli.region.is_some());
+        assert_eq!(
+            cli.accept_on_select,
+            Some(crate::image::action::Command::CopyToClipboard)
+        );
+
+        let mut cli =
+            Cli::try_parse_from(["ferrishot", "capture", "--last-region", "--to", "upload"])
+                .unwrap();
+        cli.apply_subcommand();
+        assert!(cli.last_region);
+        assert_eq!(
+            cli.accept_on_select,
+            Some(crate::image::action::Command::UploadScreenshot)
+        );
+
+        // there is nothing to capture without a region
+        assert!(Cli::try_parse_from(["ferrishot", "capture"]).is_err());
+
+        // without a subcommand, nothing changes
+        let mut cli = Cli::try_parse_from(["ferrishot", "--region", "full"]).unwrap();
+        cli.apply_subcommand();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.accept_on_select, None);
+    }
+}
@@ -82,6 +82,8 @@ macro_rules! declare_commands {
                 keys: $crate::config::key_map::KeySequence,
                 #[ferrishot_knus(default, property(name = "mod"), str)]
                 mods: $crate::config::key_map::KeyMods,
+                #[ferrishot_knus(default, property(name = "region"), str)]
+                region: Option<$crate::lazy_rect::LazyRectangle>,
             }
         )*
 
@@ -104,6 +106,48 @@ macro_rules! declare_commands {
             )*
         }
 
+        impl $Command {
+            /// Names of the commands, in `PascalCase`
+            pub const NAMES: &[&str] = &[$(stringify!($Keymappable_Command)),*];
+
+            /// Attributes of the commands, including their doc comments, in the same order as `NAMES`
+            pub const ATTRS: &[&[&str]] = &[$(&[$(stringify!($Keymappable_Command_Attr)),*]),*];
+
+            /// Name of the command, in `PascalCase`
+            pub const fn name(&self) -> &'static str {
+                match self {
+                    $(
+                        Self::$Keymappable_Command { .. } => stringify!($Keymappable_Command),
+                    )*
+                }
+            }
+        }
+
+        /// The name of the command as written in the config file, and its arguments,
+        /// like `move direction=Left amount=1`
+        impl ::std::fmt::Display for $Command {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    $(
+                        Self::$Keymappable_Command $({ $($Command_Argument),* })? => {
+                            f.write_str(&$crate::config::commands::kebab_case(
+                                stringify!($Keymappable_Command),
+                            ))?;
+                            $($(
+                                write!(
+                                    f,
+                                    " {}={:?}",
+                                    stringify!($Command_Argument),
+                                    $Command_Argument
+                                )?;
+                            )*)?
+                            Ok(())
+                        }
+                    )*
+                }
+            }
+        }
+
         /// Parses the corresponding commands in the KDL file.
         ///
         /// See [`KeymappableCommand`](crate::config::commands::KeymappableCommand) for more info.
@@ -114,12 +158,65 @@ macro_rules! declare_commands {
             )*
         }
 
+        /// The same commands, without keys. Used for `on-start`
+        ///
+        /// See [`StartupCommand`](crate::config::commands::StartupCommand) for more info.
+        pub mod on_start {
+            #[allow(clippy::wildcard_imports, reason = "arguments use types of the parent module")]
+            use super::*;
+
+            $(
+                $(#[$Keymappable_Command_Attr])*
+                #[derive(ferrishot_knus::Decode, Debug, Clone)]
+                pub struct $Keymappable_Command {
+                    $($(
+                        $(#[$Command_Argument_Attr])*
+                        $(#[ferrishot_knus(default = $Command_Argument_Default)])?
+                        #[ferrishot_knus(argument)]
+                        $Command_Argument: $Command_Argument_Ty,
+                    )+)?
+                }
+            )*
+
+            /// Parses the corresponding commands inside of `on-start` in the KDL file.
+            #[derive(ferrishot_knus::Decode, Debug, Clone)]
+            pub enum StartupCommand {
+                $(
+                    $Keymappable_Command($Keymappable_Command),
+                )*
+            }
+
+            impl StartupCommand {
+                /// The `Command` to run
+                pub fn into_command(self) -> $Command {
+                    match self {
+                        $(
+                            Self::$Keymappable_Command($Keymappable_Command {
+                                $(
+                                    $($Command_Argument,)*
+                                )?
+                            }) => {
+                                $Command::$Keymappable_Command$({
+                                    $($Command_Argument),*
+                                })?
+                            },
+                        )*
+                    }
+                }
+            }
+        }
+
         impl KeymappableCommand {
             /// # Returns
             ///
-            /// The keys necessary to trigger the `Command`, as well as the `Command` itself.
+            /// The keys necessary to trigger the `Command`, the `Command` itself and the
+            /// region to select first when nothing is selected.
             /// This is a key-value pair which will be stored in the `KeyMap`.
-            pub fn action(self) -> (($crate::config::key_map::KeySequence, $crate::config::key_map::KeyMods), Command) {
+            pub fn action(self) -> (
+                ($crate::config::key_map::KeySequence, $crate::config::key_map::KeyMods),
+                Command,
+                Option<$crate::lazy_rect::LazyRectangle>,
+            ) {
                 match self {
                     $(
                         Self::$Keymappable_Command($Keymappable_Command {
@@ -127,13 +224,15 @@ macro_rules! declare_commands {
                                 $($Command_Argument,)*
                             )?
                             keys,
-                            mods
+                            mods,
+                            region,
                         }) => {
                             (
                                 (keys, mods),
                                 Command::$Keymappable_Command$({
                                     $($Command_Argument),*
-                                })?
+                                })?,
+                                region,
                             )
                         },
                     )*
@@ -177,6 +276,50 @@ macro_rules! declare_global_commands {
             }
         }
 
+        impl ::std::fmt::Display for $CommandIdent {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                match self {
+                    $(
+                        Self::$EnumVariant(cmd) => cmd.fmt(f),
+                    )*
+                }
+            }
+        }
+
+        impl $CommandIdent {
+            /// Names of all of the commands, as they are written in the config file,
+            /// like `select-region`
+            pub fn names() -> Vec<String> {
+                [$($($InnerCommand)::+::Command::NAMES),*]
+                    .into_iter()
+                    .flatten()
+                    .map(|name| kebab_case(name))
+                    .collect()
+            }
+
+            /// Every command as it is written in the config file, and what it does
+            pub fn docs() -> Vec<(String, String)> {
+                [$(
+                    $($InnerCommand)::+::Command::NAMES
+                        .iter()
+                        .zip($($InnerCommand)::+::Command::ATTRS)
+                ),*]
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, attrs)| (kebab_case(name), doc_text(attrs)))
+                    .collect()
+            }
+
+            /// Name of the command, as it is written in the config file
+            pub fn name(&self) -> String {
+                match self {
+                    $(
+                        Self::$EnumVariant(cmd) => kebab_case(cmd.name()),
+                    )*
+                }
+            }
+        }
+
         $(#[$EnumAttr])*
         pub enum $EnumIdent {
             $(
@@ -186,14 +329,47 @@ macro_rules! declare_global_commands {
             )*
         }
 
+        /// A command inside of `on-start`, which runs once when ferrishot starts
+        ///
+        /// ```kdl
+        /// on-start {
+        ///   // contains all of the possible `StartupCommand` variants, without `key` and `mod`
+        /// }
+        /// ```
+        #[derive(Debug, Clone, ferrishot_knus::Decode)]
+        pub enum StartupCommand {
+            $(
+                $(#[doc = $VariantDoc])*
+                #[ferrishot_knus(transparent)]
+                $EnumVariant($($InnerCommand)::+::on_start::StartupCommand),
+            )*
+        }
+
+        impl StartupCommand {
+            /// The `Command` to run
+            pub fn into_command(self) -> $CommandIdent {
+                match self {
+                    $(
+                        Self::$EnumVariant(cmd) => $CommandIdent::$EnumVariant(cmd.into_command()),
+                    )*
+                }
+            }
+        }
+
         impl $EnumIdent {
-            /// Key sequence required for this command
-            pub fn action(self) -> ((KeySequence, KeyMods), Command) {
+            /// Key sequence required for this command, and what it does
+            pub fn action(self) -> ((KeySequence, KeyMods), $crate::config::key_map::Binding) {
                 match self {
                     $(
                         Self::$EnumVariant(cmd) => {
-                            let (keys, cmd) = cmd.action();
-                            (keys, $CommandIdent::$EnumVariant(cmd))
+                            let (keys, cmd, region) = cmd.action();
+                            (
+                                keys,
+                                $crate::config::key_map::Binding {
+                                    command: $CommandIdent::$EnumVariant(cmd),
+                                    region,
+                                },
+                            )
                         },
                     )*
                 }
@@ -202,6 +378,76 @@ macro_rules! declare_global_commands {
     };
 }
 
+/// Convert a `PascalCase` identifier into `kebab-case`, which is how the
+/// config file names the commands
+pub fn kebab_case(ident: &str) -> String {
+    let mut kebab = String::with_capacity(ident.len() + 4);
+
+    for (i, ch) in ident.chars().enumerate() {
+        if ch.is_ascii_uppercase() && i != 0 {
+            kebab.push('-');
+        }
+        kebab.push(ch.to_ascii_lowercase());
+    }
+
+    kebab
+}
+
+/// Join the lines of the doc comment found in the `stringify!`-ed attributes into a single line
+///
+/// Attributes other than `#[doc = "..."]` are ignored.
+pub fn doc_text(attrs: &[&str]) -> String {
+    attrs
+        .iter()
+        .filter_map(|attr| doc_comment(attr))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Contents of a `stringify!`-ed `doc = "..."` attribute
+fn doc_comment(attr: &str) -> Option<String> {
+    let literal = attr
+        .strip_prefix("doc")?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim();
+
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return raw
+            .get(hashes + 1..raw.len().checked_sub(hashes + 1)?)
+            .map(ToString::to_string);
+    }
+
+    Some(unescape(literal.strip_prefix('"')?.strip_suffix('"')?))
+}
+
+/// Undo the escaping of a string literal's contents
+fn unescape(literal: &str) -> String {
+    let mut unescaped = String::with_capacity(literal.len());
+    let mut chars = literal.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            unescaped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('t') => unescaped.push('\t'),
+            Some(escaped) => unescaped.push(escaped),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
 declare_global_commands! {
     /// The `Command` is triggered by a series of key presses.
     ///
@@ -228,6 +474,8 @@ declare_global_commands! {
     enum KeymappableCommand {
         /// Image Upload
         ImageUpload(crate::image::action),
+        /// Stamp
+        Stamp(crate::image::stamp),
         /// App
         App(ui::app),
         /// Debug overlay
@@ -238,5 +486,83 @@ declare_global_commands! {
         Letters(ui::popup::letters),
         /// Selection
         Selection(ui::selection),
+        /// Guides
+        Guides(ui::guides),
+        /// Palette
+        Palette(ui::popup::palette),
+        /// Help
+        Help(ui::popup::help),
+        /// Histogram
+        Histogram(ui::histogram),
+        /// Magnifier
+        Magnifier(ui::magnifier),
+        /// Crop suggestion
+        CropSuggestion(ui::crop_suggestion),
+        /// Errors
+        Errors(ui::errors),
+        /// Mode
+        Mode(ui::mode),
+        /// Annotations
+        Annotations(ui::annotations),
+        /// History
+        History(ui::history),
+        /// Frames
+        Frames(ui::frames),
+    }
+}
+
+/// Commands to run in order when ferrishot starts, before any input
+///
+/// ```kdl
+/// on-start {
+///   select-region "0.5x0.5+0.25+0.25"
+///   open-keybindings-cheatsheet
+/// }
+/// ```
+#[derive(ferrishot_knus::Decode, Debug, Clone, Default)]
+pub struct OnStart {
+    /// The commands
+    #[ferrishot_knus(children)]
+    pub commands: Vec<StartupCommand>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn command_names() {
+        let names = crate::Command::names();
+
+        for name in [
+            "select-region",
+            "copy-errors",
+            "halve-width",
+            "open-keybindings-cheatsheet",
+        ] {
+            assert!(names.iter().any(|n| n == name), "missing command `{name}`");
+        }
+    }
+
+    #[test]
+    fn command_docs() {
+        assert!(crate::Command::docs().contains(&(
+            "save-as-pdf".to_string(),
+            "Save image to a PDF file with a single page".to_string()
+        )));
+    }
+
+    #[test]
+    fn doc_text_skips_other_attributes() {
+        assert_eq!(
+            doc_text(&[
+                r#"doc = " Copy the \"selection\""#,
+                "derive(Debug)",
+                r#"doc = " to the clipboard""#,
+                r##"doc = r#" with "quotes""#"##,
+            ]),
+            r#"Copy the "selection" to the clipboard with "quotes""#
+        );
     }
 }
@@ -1,20 +1,37 @@
 //! Parse user keybindings
 
 use crate::config::named_key::Named;
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::HashMap, fmt, str::FromStr, time::Duration};
 
 use iced::{
     advanced::debug::core::SmolStr,
     keyboard::{Modifiers, key::Key as IcedKey},
 };
 
+use crate::{lazy_rect::LazyRectangle, ui::mode::Mode};
+
 use super::commands::{Command, KeymappableCommand};
 
+/// What happens when a key sequence is pressed
+#[derive(Debug, Clone)]
+pub struct Binding {
+    /// The command to run
+    pub command: Command,
+    /// When nothing is selected, select this region before running the command.
+    /// For example, `save-screenshot key=p region="full"` saves the whole screen
+    pub region: Option<LazyRectangle>,
+}
+
 /// Represents the keybindings for ferrishot
 #[derive(Debug, Default)]
 pub struct KeyMap {
     /// Map of Key Pressed => Action when pressing that key
-    pub keys: HashMap<(KeySequence, KeyMods), Command>,
+    pub keys: HashMap<(KeySequence, KeyMods), Binding>,
+    /// Keys which are only active in a specific mode. They take
+    /// priority over the keys in `keys`
+    pub modes: HashMap<Mode, HashMap<(KeySequence, KeyMods), Binding>>,
+    /// Keys which the user's config binds more than once
+    pub conflicts: Vec<Conflict>,
 }
 
 impl KeyMap {
@@ -37,25 +54,196 @@ impl KeyMap {
         key: IcedKey,
         previous_key: Option<IcedKey>,
         mods: Modifiers,
-    ) -> Option<&Command> {
-        self.keys
-            .get(&(KeySequence((key, previous_key)), KeyMods(mods)))
+        mode: Mode,
+    ) -> Option<&Binding> {
+        let keys = (KeySequence((key, previous_key)), KeyMods(mods));
+
+        self.modes
+            .get(&mode)
+            .and_then(|mode_keys| mode_keys.get(&keys))
+            .or_else(|| self.keys.get(&keys))
+    }
+
+    /// Every keybinding, with the mode it is limited to and its keys as written in the config,
+    /// like `ctrl+alt+g<left>`
+    pub fn bindings(&self) -> impl Iterator<Item = (Option<Mode>, String, &Binding)> {
+        let global = self.keys.iter().map(|keys| (None, keys));
+        let modes = self
+            .modes
+            .iter()
+            .flat_map(|(mode, keys)| keys.iter().map(move |keys| (Some(*mode), keys)));
+
+        global
+            .chain(modes)
+            .map(|(mode, ((sequence, mods), binding))| {
+                let keys = if mods.0.is_empty() {
+                    sequence.to_string()
+                } else {
+                    format!("{mods}+{sequence}")
+                };
+                (mode, keys, binding)
+            })
+    }
+
+    /// Keys which run the `command` in any mode, as written in the config. When several
+    /// keys run it, the shortest of them
+    pub fn keys_of(&self, command: &Command) -> Option<String> {
+        // commands with the same name and arguments are the same
+        let command = command.to_string();
+
+        self.bindings()
+            .filter(|(mode, _, binding)| mode.is_none() && binding.command.to_string() == command)
+            .map(|(_, keys, _)| keys)
+            .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+    }
+
+    /// Every keybinding, sorted by mode and keys. As a table, or in JSON with `is_json`
+    ///
+    /// This is what `ferrishot keys list` prints
+    pub fn list(&self, is_json: bool) -> String {
+        let mut rows = self
+            .bindings()
+            .map(|(mode, keys, binding)| {
+                (
+                    mode.map(|mode| mode.label().to_lowercase()),
+                    keys,
+                    binding.command.to_string(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // keys active in every mode come first
+        rows.sort();
+
+        if is_json {
+            let rows = rows
+                .into_iter()
+                .map(|(mode, keys, command)| {
+                    serde_json::json!({
+                        "mode": mode,
+                        "keys": keys,
+                        "command": command,
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            return serde_json::to_string_pretty(&rows).expect("keybindings are always valid JSON")
+                + "\n";
+        }
+
+        let rows = rows
+            .into_iter()
+            .map(|(mode, keys, command)| (mode.unwrap_or_else(|| "any".to_string()), keys, command))
+            .collect::<Vec<_>>();
+        let mode_width = rows
+            .iter()
+            .map(|(mode, ..)| mode.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        let keys_width = rows
+            .iter()
+            .map(|(_, keys, _)| keys.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        std::iter::once((
+            "MODE".to_string(),
+            "KEYS".to_string(),
+            "COMMAND".to_string(),
+        ))
+        .chain(rows)
+        .map(|(mode, keys, command)| {
+            format!("{mode:<mode_width$}  {keys:<keys_width$}  {command}\n")
+        })
+        .collect()
     }
 }
 
 /// Keybindings for ferrishot
 #[derive(ferrishot_knus::Decode, Debug, Default)]
 pub struct Keys {
+    /// Keybindings which are only active in a specific mode
+    ///
+    /// ```kdl
+    /// keys {
+    ///   mode "annotate" {
+    ///     // ...
+    ///   }
+    /// }
+    /// ```
+    #[ferrishot_knus(children(name = "mode"))]
+    pub modes: Vec<ModeKeys>,
     /// A list of raw keybindings for ferrishot, directly as read from the config file
     #[ferrishot_knus(children)]
     pub keys: Vec<KeymappableCommand>,
 }
 
+/// Keybindings of a single mode
+#[derive(ferrishot_knus::Decode, Debug)]
+pub struct ModeKeys {
+    /// The mode in which the keybindings are active
+    #[ferrishot_knus(argument)]
+    pub mode: Mode,
+    /// Raw keybindings, as read from the config file
+    #[ferrishot_knus(children)]
+    pub keys: Vec<KeymappableCommand>,
+}
+
+impl Keys {
+    /// Add keybindings after the existing ones, so they take priority when both bind the same key
+    pub fn extend(&mut self, other: Self) {
+        self.keys.extend(other.keys);
+        self.modes.extend(other.modes);
+    }
+}
+
+/// Add the keybinding of the `command` to the `keys`
+fn bind(keys: &mut HashMap<(KeySequence, KeyMods), Binding>, command: KeymappableCommand) {
+    let (key, binding) = command.action();
+
+    // `unbind` removes the key, so it is free to start a sequence like `gg`
+    if matches!(
+        binding.command,
+        Command::App(crate::ui::app::Command::Unbind)
+    ) {
+        keys.remove(&key);
+    } else {
+        keys.insert(key, binding);
+    }
+}
+
 impl FromIterator<KeymappableCommand> for KeyMap {
     fn from_iter<T: IntoIterator<Item = KeymappableCommand>>(iter: T) -> Self {
+        let mut keys = HashMap::new();
+
+        for command in iter {
+            bind(&mut keys, command);
+        }
+
         Self {
-            keys: iter.into_iter().map(KeymappableCommand::action).collect(),
+            keys,
+            modes: HashMap::new(),
+            conflicts: Vec::new(),
+        }
+    }
+}
+
+impl From<Keys> for KeyMap {
+    fn from(value: Keys) -> Self {
+        let mut key_map = value.keys.into_iter().collect::<Self>();
+
+        // the same mode can be declared more than once, e.g. by the default
+        // and the user config
+        for ModeKeys { mode, keys } in value.modes {
+            let mode_keys = key_map.modes.entry(mode).or_default();
+
+            for command in keys {
+                bind(mode_keys, command);
+            }
         }
+
+        key_map
     }
 }
 
@@ -69,10 +257,48 @@ impl FromIterator<KeymappableCommand> for KeyMap {
 #[derive(Debug, Hash, PartialEq, PartialOrd, Ord, Eq, Clone)]
 pub struct KeySequence(pub (IcedKey, Option<IcedKey>));
 
+/// The keys as written in the config, like `g<left>`
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (first, second) = &self.0;
+
+        for key in std::iter::once(first).chain(second) {
+            match key {
+                IcedKey::Character(ch) => f.write_str(ch)?,
+                IcedKey::Named(named) => match Named::from_iced(*named) {
+                    Some(named) => write!(f, "<{}>", <&str>::from(named))?,
+                    None => write!(f, "<{named:?}>")?,
+                },
+                IcedKey::Unidentified => f.write_str("<unidentified>")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Modifier keys
 #[derive(Debug, Default, Clone, Hash, Eq, PartialEq)]
 pub struct KeyMods(pub Modifiers);
 
+/// The modifiers as written in the config, like `ctrl+alt`
+impl fmt::Display for KeyMods {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mods = [
+            (Modifiers::CTRL, "ctrl"),
+            (Modifiers::ALT, "alt"),
+            (Modifiers::LOGO, "super"),
+            (Modifiers::SHIFT, "shift"),
+        ]
+        .into_iter()
+        .filter(|(modifier, _)| self.0.contains(*modifier))
+        .map(|(_, name)| name)
+        .collect::<Vec<_>>();
+
+        f.write_str(&mods.join("+"))
+    }
+}
+
 impl FromStr for KeyMods {
     type Err = String;
 
@@ -181,6 +407,180 @@ impl FromStr for KeySequence {
     }
 }
 
+/// The same keys are bound twice in the user's config, in the same mode. Only the
+/// last of the bindings is used
+#[derive(thiserror::Error, miette::Diagnostic, Debug)]
+#[error("`{keys}` is bound more than once in {}, only the last binding is used", src.name())]
+#[diagnostic(
+    severity(Warning),
+    help("Remove one of the bindings, or `unbind` the key first to make it obvious")
+)]
+pub struct Conflict {
+    /// The keys, as written in the config
+    keys: String,
+    /// The user's config
+    #[source_code]
+    src: miette::NamedSource<String>,
+    /// The binding which is not used
+    #[label("bound here")]
+    first: miette::SourceSpan,
+    /// The binding which is used
+    #[label("and here")]
+    second: miette::SourceSpan,
+}
+
+/// Find the keys which are bound more than once in the `keys` of the user's `config`
+///
+/// Overriding a key of the default config or the keymap preset is not a conflict,
+/// because the user's keys are meant to take priority over them
+pub fn conflicts(file_name: &str, config: &str) -> Vec<Conflict> {
+    // the config was already parsed, this only needs the positions of the keybindings
+    let Ok(document) = config.parse::<kdl::KdlDocument>() else {
+        return Vec::new();
+    };
+
+    let mut bound = HashMap::new();
+    let mut conflicts = Vec::new();
+
+    let keys = document
+        .nodes()
+        .iter()
+        .filter(|node| node.name().value() == "keys")
+        .filter_map(kdl::KdlNode::children)
+        .flat_map(kdl::KdlDocument::nodes);
+
+    for node in keys {
+        let (mode, bindings) = if node.name().value() == "mode" {
+            (
+                node.entry(0)
+                    .and_then(|mode| mode.value().as_string())
+                    .map(str::to_string),
+                node.children()
+                    .map(kdl::KdlDocument::nodes)
+                    .unwrap_or_default(),
+            )
+        } else {
+            (None, std::slice::from_ref(node))
+        };
+
+        for binding in bindings {
+            let Some(key) = binding.get("key").and_then(kdl::KdlValue::as_string) else {
+                continue;
+            };
+            let mods = binding.get("mod").and_then(kdl::KdlValue::as_string);
+            let (Ok(sequence), Ok(key_mods)) = (
+                key.parse::<KeySequence>(),
+                mods.unwrap_or_default().parse::<KeyMods>(),
+            ) else {
+                continue;
+            };
+            let keys = (mode.clone(), sequence, key_mods);
+
+            // the key is free to be bound again
+            if binding.name().value() == "unbind" {
+                bound.remove(&keys);
+                continue;
+            }
+
+            if let Some(first) = bound.insert(keys, binding.span()) {
+                conflicts.push(Conflict {
+                    keys: mods.map_or_else(|| key.to_string(), |mods| format!("{mods}+{key}")),
+                    src: miette::NamedSource::new(file_name, config.to_string()),
+                    first,
+                    second: binding.span(),
+                });
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Keybindings which move and resize the selection, picked from the ones shipped with ferrishot
+///
+/// ```kdl
+/// keymap preset="arrows-only"
+/// ```
+#[derive(ferrishot_knus::Decode, Debug, Clone, Copy)]
+pub struct Keymap {
+    /// The keybindings to use
+    #[ferrishot_knus(default, property)]
+    pub preset: Preset,
+}
+
+/// Keybindings shipped with ferrishot. Each of them is a `keys` node, in `keymaps/`
+#[derive(ferrishot_knus::DecodeScalar, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// `hjkl`, the arrow keys and sequences starting with `g`, like `gg`
+    #[default]
+    Vim,
+    /// `ctrl+b`, `ctrl+f`, `ctrl+p`, `ctrl+n` and the arrow keys
+    Emacs,
+    /// The arrow keys, `home`, `end`, `page-up` and `page-down`, without any letters
+    ArrowsOnly,
+}
+
+/// File with the keybindings of a `Preset`
+#[derive(ferrishot_knus::Decode, Debug)]
+struct PresetKdl {
+    /// The keybindings
+    #[ferrishot_knus(child)]
+    keys: Keys,
+}
+
+impl Preset {
+    /// The keybindings of the preset
+    ///
+    /// # Errors
+    ///
+    /// The file of the preset is invalid
+    pub fn keys(self) -> Result<Keys, miette::Error> {
+        let (name, kdl) = match self {
+            Self::Vim => ("<vim-keymap>", include_str!("../../keymaps/vim.kdl")),
+            Self::Emacs => ("<emacs-keymap>", include_str!("../../keymaps/emacs.kdl")),
+            Self::ArrowsOnly => (
+                "<arrows-only-keymap>",
+                include_str!("../../keymaps/arrows-only.kdl"),
+            ),
+        };
+
+        Ok(ferrishot_knus::parse::<PresetKdl>(name, kdl)?.keys)
+    }
+}
+
+/// Speeds up commands which nudge the selection, such as `move left 1`, while their key is held
+///
+/// ```kdl
+/// key-acceleration delay=300 doubling=400 max=1
+/// ```
+#[derive(ferrishot_knus::Decode, Debug, Clone, Copy)]
+pub struct KeyAcceleration {
+    /// Milliseconds to hold the key before the selection speeds up
+    #[ferrishot_knus(default = 300, property)]
+    pub delay: u32,
+    /// After the `delay`, the step doubles every this many milliseconds
+    #[ferrishot_knus(default = 400, property)]
+    pub doubling: u32,
+    /// The step grows to at most this many times its size. `1` disables acceleration
+    #[ferrishot_knus(default = 1, property)]
+    pub max: u32,
+}
+
+impl KeyAcceleration {
+    /// How many times larger the step is after holding the key for `held`
+    pub fn factor(self, held: Duration) -> u32 {
+        let accelerating = held.as_millis().saturating_sub(u128::from(self.delay));
+
+        if accelerating == 0 || self.max <= 1 {
+            return 1;
+        }
+
+        let doublings = accelerating as f64 / f64::from(self.doubling.max(1));
+
+        2_f64.powf(doublings).min(f64::from(self.max)) as u32
+    }
+}
+
 #[cfg(test)]
 mod test {
     use iced::keyboard::key;
@@ -188,10 +588,41 @@ mod test {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn key_acceleration() {
+        let acceleration = KeyAcceleration {
+            delay: 300,
+            doubling: 400,
+            max: 32,
+        };
+        let factor = |millis| acceleration.factor(Duration::from_millis(millis));
+
+        assert_eq!(factor(0), 1);
+        assert_eq!(factor(300), 1);
+        assert_eq!(factor(700), 2);
+        assert_eq!(factor(1100), 4);
+        assert_eq!(factor(60_000), 32);
+
+        let disabled = KeyAcceleration {
+            max: 1,
+            ..acceleration
+        };
+        assert_eq!(disabled.factor(Duration::from_secs(60)), 1);
+    }
+
     fn ch(c: &str) -> IcedKey {
         IcedKey::Character(SmolStr::new(c))
     }
 
+    /// The keybindings of the `keys` node in `kdl`
+    fn key_map(kdl: &str) -> KeyMap {
+        KeyMap::from(
+            ferrishot_knus::parse::<PresetKdl>("<test>", kdl)
+                .unwrap()
+                .keys,
+        )
+    }
+
     #[track_caller]
     fn parse(input: &str, expected: Result<KeySequence, String>) {
         assert_eq!(
@@ -241,4 +672,207 @@ mod test {
             Err("Invalid key: <@>. Matching variant not found".to_string()),
         );
     }
+
+    #[test]
+    fn display_keys() {
+        assert_eq!(
+            "g<left>".parse::<KeySequence>().unwrap().to_string(),
+            "g<left>"
+        );
+        assert_eq!("<f12>".parse::<KeySequence>().unwrap().to_string(), "<f12>");
+        assert_eq!(
+            "ctrl+alt".parse::<KeyMods>().unwrap().to_string(),
+            "ctrl+alt"
+        );
+    }
+
+    #[test]
+    fn list_keys() {
+        use crate::ui::app::Command as AppCommand;
+
+        let key = || (KeySequence((ch("q"), None)), KeyMods::default());
+        let binding = |command| Binding {
+            command,
+            region: None,
+        };
+        let key_map = KeyMap {
+            keys: HashMap::from([(key(), binding(Command::App(AppCommand::Exit)))]),
+            modes: HashMap::from([(
+                Mode::Measure,
+                HashMap::from([(key(), binding(Command::App(AppCommand::NoOp)))]),
+            )]),
+            conflicts: Vec::new(),
+        };
+
+        assert_eq!(
+            key_map.list(false),
+            "MODE     KEYS  COMMAND\nany      q     exit\nmeasure  q     no-op\n"
+        );
+    }
+
+    #[test]
+    fn keybinding_region() {
+        let key_map = key_map(
+            r#"
+                keys {
+                  save-screenshot key=p region="full"
+                }
+            "#,
+        );
+        let binding = key_map
+            .get(ch("p"), None, Modifiers::empty(), Mode::Select)
+            .unwrap();
+
+        assert!(matches!(
+            binding.command,
+            Command::ImageUpload(crate::image::action::Command::SaveScreenshot)
+        ));
+        assert!(binding.region.is_some());
+    }
+
+    #[test]
+    fn unbind_and_conflicts() {
+        let kdl = r#"
+            keys {
+              toggle-debug-overlay key=<f12>
+              unbind key=<f12>
+              goto top-left key=gg
+              goto bottom-right key=gg
+              // a different mode is not a conflict
+              mode magnify {
+                set-mode select key=gg
+              }
+            }
+        "#;
+
+        assert!(
+            key_map(kdl)
+                .get(
+                    IcedKey::Named(key::Named::F12),
+                    None,
+                    Modifiers::empty(),
+                    Mode::Select
+                )
+                .is_none()
+        );
+        let conflicts = conflicts("ferrishot.kdl", kdl);
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].to_string().starts_with("`gg`"));
+    }
+
+    #[test]
+    fn unbind_then_rebind_is_not_a_conflict() {
+        let conflicts = conflicts(
+            "ferrishot.kdl",
+            r#"
+                keys {
+                  goto top-left key=gg
+                  unbind key=gg
+                  goto bottom-right key=gg
+                }
+            "#,
+        );
+
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn keys_of_command() {
+        let copy = Command::ImageUpload(crate::image::action::Command::CopyToClipboard);
+        let key_map = key_map(
+            r#"
+                keys {
+                  copy-to-clipboard key=c mod=ctrl
+                  copy-to-clipboard key=y
+                  // only keys active in every mode
+                  mode annotate {
+                    copy-to-clipboard key=x
+                  }
+                }
+            "#,
+        );
+
+        // the shortest of the keys
+        assert_eq!(key_map.keys_of(&copy).as_deref(), Some("y"));
+        assert_eq!(
+            key_map.keys_of(&Command::Selection(
+                crate::ui::selection::Command::SelectRegion {
+                    selection: LazyRectangle::FULL,
+                }
+            )),
+            None
+        );
+    }
+
+    #[test]
+    fn unbind_in_mode() {
+        use crate::ui::app::Command as AppCommand;
+
+        let kdl = r#"
+            keys {
+              exit key=q
+              mode annotate {
+                no-op key=q
+                unbind key=q
+                set-mode select key=x
+                set-mode magnify key=x
+              }
+            }
+        "#;
+        let key_map = key_map(kdl);
+
+        // the key of the mode is removed, so the key active in every mode is used
+        assert!(
+            !key_map.modes[&Mode::Annotate]
+                .contains_key(&(KeySequence((ch("q"), None)), KeyMods::default()))
+        );
+        assert!(matches!(
+            key_map
+                .get(ch("q"), None, Modifiers::empty(), Mode::Annotate)
+                .map(|binding| &binding.command),
+            Some(Command::App(AppCommand::Exit))
+        ));
+        // bound twice in the same mode
+        let conflicts = conflicts("ferrishot.kdl", kdl);
+        assert_eq!(conflicts.len(), 1);
+        assert!(conflicts[0].to_string().starts_with("`x`"));
+    }
+
+    #[test]
+    fn mode_keys_take_priority() {
+        use crate::ui::app::Command as AppCommand;
+
+        let key = || (KeySequence((ch("q"), None)), KeyMods::default());
+        let binding = |command| Binding {
+            command,
+            region: None,
+        };
+        let key_map = KeyMap {
+            keys: HashMap::from([(key(), binding(Command::App(AppCommand::Exit)))]),
+            modes: HashMap::from([(
+                Mode::Measure,
+                HashMap::from([(key(), binding(Command::App(AppCommand::NoOp)))]),
+            )]),
+            conflicts: Vec::new(),
+        };
+
+        let get = |mode| {
+            key_map
+                .get(ch("q"), None, Modifiers::empty(), mode)
+                .map(|binding| &binding.command)
+        };
+
+        assert!(matches!(
+            get(Mode::Select),
+            Some(Command::App(AppCommand::Exit))
+        ));
+        assert!(matches!(
+            get(Mode::Annotate),
+            Some(Command::App(AppCommand::Exit))
+        ));
+        assert!(matches!(
+            get(Mode::Measure),
+            Some(Command::App(AppCommand::NoOp))
+        ));
+    }
 }
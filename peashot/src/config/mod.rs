@@ -20,14 +20,14 @@ mod tests;
 pub mod cli;
 pub mod commands;
 pub mod key_map;
-mod named_key;
+pub mod named_key;
 mod options;
 mod theme;
 
 use crate::config::key_map::KeyMap;
 pub use crate::config::theme::{Color, Theme};
 
-pub use cli::Cli;
+pub use cli::{Cli, ConfigCommand, KeysCommand, Subcommand};
 use miette::miette;
 
 use std::fs;
@@ -50,18 +50,28 @@ impl Config {
     pub fn parse(user_config: &str) -> Result<Self, miette::Error> {
         let config_file_path = PathBuf::from(user_config);
 
-        let default_config =
+        let mut default_config =
             ferrishot_knus::parse::<DefaultKdlConfig>("<default-config>", DEFAULT_KDL_CONFIG_STR)?;
 
-        let user_config = ferrishot_knus::parse::<UserKdlConfig>(
-            &user_config,
-            // if there is no config file, act as if it's simply empty
-            &fs::read_to_string(&config_file_path).unwrap_or_default(),
-        )?;
+        // if there is no config file, act as if it's simply empty
+        let user_config_str = fs::read_to_string(&config_file_path).unwrap_or_default();
+        let user_config_kdl =
+            ferrishot_knus::parse::<UserKdlConfig>(&user_config, &user_config_str)?;
 
-        default_config
-            .merge_user_config(user_config)
+        // the keys of the preset take priority over the default keys,
+        // and the user's keys take priority over both
+        let preset = user_config_kdl
+            .keymap
+            .unwrap_or(default_config.keymap)
+            .preset;
+        default_config.keys.extend(preset.keys()?);
+
+        let mut config: Self = default_config
+            .merge_user_config(user_config_kdl)
             .try_into()
-            .map_err(|err| miette!("{err}"))
+            .map_err(|err| miette!("{err}"))?;
+        config.keys.conflicts = key_map::conflicts(user_config, &user_config_str);
+
+        Ok(config)
     }
 }
@@ -26,12 +26,20 @@ macro_rules! named_keys {
                     $(Self::$Key => iced::keyboard::key::Named::$Key),*
                 }
             }
+
+            /// Convert an Iced key to this key, if it can be written in the config
+            pub const fn from_iced(named: iced::keyboard::key::Named) -> Option<Self> {
+                match named {
+                    $(iced::keyboard::key::Named::$Key => Some(Self::$Key),)*
+                    _ => None,
+                }
+            }
         }
     };
 }
 
 named_keys! {
-    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, strum::EnumString, strum::EnumIter)]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, strum::EnumString, strum::EnumIter, strum::IntoStaticStr)]
     #[strum(serialize_all = "kebab-case")]
     #[expect(
         clippy::upper_case_acronyms,
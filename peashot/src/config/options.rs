@@ -10,6 +10,9 @@
 ///
 /// `UserKdlConfig` is merged into `DefaultKdlConfig` before being processed
 /// into a `Config`
+///
+/// Options inside of `[...]` are entire KDL nodes, which are replaced as a whole
+/// if the user specifies them. All other options are nodes with a single argument.
 #[macro_export]
 macro_rules! declare_config_options {
     (
@@ -19,6 +22,10 @@ macro_rules! declare_config_options {
             $keys:ident: $Keys:ty,
             $(#[$theme_doc:meta])*
             $theme:ident: $Theme:ty,
+            [$(
+                $(#[$node_doc:meta])*
+                $node:ident: $Node:ty
+            ),* $(,)?],
             $(
                 $(#[$doc:meta])*
                 $key:ident: $typ:ty
@@ -31,12 +38,35 @@ macro_rules! declare_config_options {
             pub $theme: $Theme,
             $(#[$keys_doc])*
             pub $keys: $Keys,
+            $(
+                $(#[$node_doc])*
+                pub $node: $Node,
+            )*
             $(
                 $(#[$doc])*
                 pub $key: $typ,
             )*
         }
 
+        impl $Config {
+            /// Every option as it is written in the config file, and what it does
+            pub fn docs() -> Vec<(String, String)> {
+                [
+                    $(
+                        (stringify!($node), &[$(stringify!($node_doc)),*][..]),
+                    )*
+                    $(
+                        (stringify!($key), &[$(stringify!($doc)),*][..]),
+                    )*
+                ]
+                .into_iter()
+                .map(|(name, attrs)| {
+                    (name.replace('_', "-"), $crate::config::commands::doc_text(attrs))
+                })
+                .collect()
+            }
+        }
+
         /// The default config as read from the default config file, included as a static string in the binary.
         /// All values are required and must be specified
         #[derive(ferrishot_knus::Decode, Debug)]
@@ -47,6 +77,11 @@ macro_rules! declare_config_options {
             /// The default theme of ferrishot
             #[ferrishot_knus(child)]
             pub $theme: super::theme::DefaultKdlTheme,
+            $(
+                $(#[$node_doc])*
+                #[ferrishot_knus(child)]
+                pub $node: $Node,
+            )*
             $(
                 $(#[$doc])*
                 #[ferrishot_knus(child, unwrap(argument))]
@@ -61,6 +96,10 @@ macro_rules! declare_config_options {
                 $(
                     self.$key = user_config.$key.unwrap_or(self.$key);
                 )*
+                // nodes are replaced as a whole
+                $(
+                    self.$node = user_config.$node.unwrap_or(self.$node);
+                )*
                 // merge keybindings
                 //
                 // If the same keybinding is defined in the default theme and
@@ -90,8 +129,7 @@ macro_rules! declare_config_options {
                 // the user keybinding takes priority.
                 self
                     .keys
-                    .keys
-                    .extend(user_config.keys.unwrap_or_default().keys);
+                    .extend(user_config.keys.unwrap_or_default());
 
                 if let Some(user_theme) = user_config.theme {
                     self.theme = self.theme.merge_user_theme(user_theme);
@@ -109,8 +147,11 @@ macro_rules! declare_config_options {
                     $(
                         $key: value.$key,
                     )*
+                    $(
+                        $node: value.$node,
+                    )*
                     theme: value.theme.try_into()?,
-                    keys: value.keys.keys.into_iter().collect::<$crate::config::KeyMap>(),
+                    keys: $crate::config::KeyMap::from(value.keys),
                 })
             }
         }
@@ -125,6 +166,11 @@ macro_rules! declare_config_options {
             /// User-defined colors
             #[ferrishot_knus(child)]
             pub theme: Option<super::theme::UserKdlTheme>,
+            $(
+                $(#[$node_doc])*
+                #[ferrishot_knus(child)]
+                pub $node: Option<$Node>,
+            )*
             $(
                 $(#[$doc])*
                 #[ferrishot_knus(child, unwrap(argument))]
@@ -142,6 +188,26 @@ crate::declare_config_options! {
         keys: super::key_map::KeyMap,
         /// Ferrishot's theme and colors
         theme: super::Theme,
+        // Options which are entire nodes, instead of a single value
+        [
+            /// System information stamped onto the captured image
+            stamp: crate::image::stamp::Stamp,
+            /// Preferred regions for specific applications
+            app_regions: crate::app_regions::AppRegions,
+            /// Commands to run when ferrishot starts
+            on_start: super::commands::OnStart,
+            /// Speed up nudging the selection while a key is held
+            key_acceleration: super::key_map::KeyAcceleration,
+            /// Keybindings which move and resize the selection
+            keymap: super::key_map::Keymap,
+            /// Sizes of the parts of the selection which are grabbed or clicked
+            handles: crate::ui::selection::Handles,
+            /// Ring of actions around the cursor, opened by pressing a mouse button
+            radial_menu: crate::ui::popup::radial_menu::Settings,
+            /// How long the messages in the corner of the screen are shown, how many at once
+            /// and how they fade
+            toasts: crate::ui::errors::Toasts,
+        ],
         /// Renders a size indicator in the bottom left corner.
         /// It shows the current height and width of the selection.
         ///
@@ -149,5 +215,95 @@ crate::declare_config_options! {
         size_indicator: bool,
         /// Render icons around the selection
         selection_icons: bool,
+        /// When exiting with a selection, ask for confirmation first
+        /// instead of exiting instantly
+        confirm_exit: bool,
+        /// How the window covering the screen is created
+        window_mode: crate::ui::app::WindowMode,
+        /// Show the size and position of the selection in the window title, for
+        /// status bars and screen readers
+        selection_in_title: bool,
+        /// Listen for commands sent with `ferrishot ctl`
+        control_socket: bool,
+        /// Before running an action on the image, show a preview of
+        /// exactly what will be captured
+        confirm_preview: bool,
+        /// When nothing is selected, copying, saving and uploading
+        /// use the full screen instead
+        full_screen_without_selection: bool,
+        /// Scrolling a line over the selection grows or shrinks it by this many
+        /// pixels around its center. `0` disables it
+        scroll_resize_step: u32,
+        /// Screenshots larger than this many bytes are not uploaded, so they can be
+        /// made smaller first. `0` allows any size
+        max_upload_size: u64,
+        /// When the screenshot is the same as the one saved or uploaded last time,
+        /// use its path or link instead of saving or uploading it again
+        skip_duplicate_captures: bool,
+        /// What to do when saving to a path that already exists
+        save_conflict: crate::image::action::SaveConflict,
+        /// Size of the page when saving as a PDF
+        pdf_page_size: crate::image::pdf::PageSize,
+        /// Color profile of the monitor, embedded into saved PNG, JPEG and WebP files.
+        /// `None` embeds no profile
+        icc_profile: Option<std::path::PathBuf>,
+        /// After saving, show a toast with a button to move the file to the trash
+        /// for this many seconds. `0` disables the toast
+        undo_save_timeout: u32,
+        /// Exit if nothing was done for this many seconds, for when ferrishot
+        /// is started by a script and nobody is there. `0` disables it
+        idle_timeout: u32,
+        /// With `--accept-on-select`, wait this many milliseconds before running
+        /// the action, so it can be cancelled. `0` runs it immediately
+        accept_on_select_delay: u32,
+        /// Keys of a sequence, like `gg`, must be pressed within this many
+        /// milliseconds of each other. `0` waits forever
+        key_sequence_timeout: u32,
+        /// Holding a key runs its command again and again, instead of only once
+        key_repeat: bool,
+        /// Action to run with the `accept` key, `Enter` by default
+        primary_action: crate::image::action::Command,
+        /// Action to run when double-clicking inside of the selection.
+        /// `None` disables double-click
+        double_click_action: Option<crate::image::action::Command>,
+        /// How the frame around the selection looks
+        selection_frame_style: crate::ui::selection::FrameStyle,
+        /// Safe area guide shown inside of the selection
+        guide: crate::ui::guides::Guide,
+        /// How many times the magnifier enlarges the screenshot, in the `magnify` mode
+        magnifier_zoom: u32,
+        /// While dragging, snap edges of the selection to a grid of this many pixels.
+        /// `0` disables snapping
+        snap_to_pixel: u32,
+        /// New selections smaller than this many pixels are considered accidental
+        min_selection_size: u32,
+        /// What to do with a new selection smaller than `min_selection_size`
+        tiny_selection: crate::ui::selection::TinySelection,
+        /// Corner of the selection which stays in place when halving or doubling its size,
+        /// until it is changed with `cycle-resize-anchor`
+        resize_anchor: crate::geometry::Corner,
+        /// How many of the recently used regions are outlined while nothing is selected,
+        /// up to 8. `0` hides them
+        recent_regions: u32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn option_docs() {
+        let docs = Config::docs();
+
+        assert!(docs.contains(&(
+            "min-selection-size".to_string(),
+            "New selections smaller than this many pixels are considered accidental".to_string()
+        )));
+        // multiple lines are joined
+        assert!(docs.contains(&(
+            "recent-regions".to_string(),
+            "How many of the recently used regions are outlined while nothing is selected, up to 8. `0` hides them".to_string()
+        )));
     }
 }
@@ -1,5 +1,14 @@
 use super::*;
 
+/// Parse `kdl` as the contents of the config file
+fn parse(kdl: &str) -> Config {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ferrishot.kdl");
+    std::fs::write(&path, kdl).unwrap();
+
+    Config::parse(path.to_str().unwrap()).unwrap()
+}
+
 mod kdl_config_backward_compatibility {
     #[test]
     fn v0_3() {
@@ -10,3 +19,46 @@ mod kdl_config_backward_compatibility {
         .expect("ferrishot v0.3: The first released version of the config must never break");
     }
 }
+
+#[test]
+fn keymap_presets() {
+    use iced::keyboard::{Key, Modifiers, key::Named};
+
+    let config = parse(
+        r#"
+            keymap preset="arrows-only"
+            keys {
+              goto center key=<home>
+            }
+        "#,
+    );
+    let get = |key| {
+        config
+            .keys
+            .get(key, None, Modifiers::empty(), crate::ui::mode::Mode::Select)
+            .map(|binding| &binding.command)
+    };
+
+    assert!(get(Key::Character("h".into())).is_none());
+    assert!(matches!(
+        get(Key::Named(Named::ArrowLeft)),
+        Some(crate::Command::Selection(
+            crate::ui::selection::Command::Move { .. }
+        ))
+    ));
+    // the user's keys take priority over the preset
+    assert!(matches!(
+        get(Key::Named(Named::Home)),
+        Some(crate::Command::Selection(
+            crate::ui::selection::Command::Goto { .. }
+        ))
+    ));
+
+    for preset in [
+        key_map::Preset::Vim,
+        key_map::Preset::Emacs,
+        key_map::Preset::ArrowsOnly,
+    ] {
+        preset.keys().unwrap();
+    }
+}
@@ -2,7 +2,8 @@
 //!
 //! All theme keys are stored in a flat format for ease of use.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::{LazyLock, Mutex};
 
 use ferrishot_knus::{DecodeScalar, ast::Literal, errors::DecodeError, traits::ErrorSpan};
 
@@ -83,13 +84,90 @@ pub struct Color {
     pub opacity: f32,
 }
 
+/// Family of the font of the text, like `monospace` or a font installed on the system
+///
+/// ```kdl
+/// theme {
+///   font "Fira Sans"
+/// }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FontFamily(pub iced::Font);
+
+impl From<FontFamily> for iced::Font {
+    fn from(font: FontFamily) -> Self {
+        font.0
+    }
+}
+
+impl<S: ErrorSpan> DecodeScalar<S> for FontFamily {
+    fn type_check(
+        _type_name: &Option<ferrishot_knus::span::Spanned<ferrishot_knus::ast::TypeName, S>>,
+        _ctx: &mut ferrishot_knus::decode::Context<S>,
+    ) {
+    }
+
+    fn raw_decode(
+        value: &ferrishot_knus::span::Spanned<Literal, S>,
+        ctx: &mut ferrishot_knus::decode::Context<S>,
+    ) -> Result<Self, DecodeError<S>> {
+        use iced::font::Family;
+
+        let Literal::String(family) = &**value else {
+            ctx.emit_error(DecodeError::scalar_kind(
+                ferrishot_knus::decode::Kind::String,
+                value,
+            ));
+            return Ok(Self(iced::Font::MONOSPACE));
+        };
+
+        let family = match &**family {
+            "monospace" => Family::Monospace,
+            "sans-serif" => Family::SansSerif,
+            "serif" => Family::Serif,
+            "cursive" => Family::Cursive,
+            "fantasy" => Family::Fantasy,
+            name => Family::Name(intern_font_name(name)),
+        };
+
+        Ok(Self(iced::Font::with_family(family)))
+    }
+}
+
+/// Names of the fonts used by the config, which live for as long as ferrishot does
+static FONT_NAMES: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(Mutex::default);
+
+/// The `name` of a font, which `iced` needs to live forever. Each name is only leaked
+/// once, no matter how often the config is read
+fn intern_font_name(name: &str) -> &'static str {
+    let mut names = FONT_NAMES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    if let Some(interned) = names.get(name) {
+        return interned;
+    }
+
+    let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+    names.insert(interned);
+
+    interned
+}
+
 /// Declare theme keys
 ///
 /// `UserKdlTheme` is merged into `DefaultKdlTheme` before being processed
 /// into a `Theme`
+///
+/// Keys inside of `[...]` are not colors, like sizes and fonts. They are nodes with a
+/// single argument of their type
 #[macro_export]
 macro_rules! declare_theme_options {
     (
+        [$(
+            $(#[$value_doc:meta])*
+            $value_key:ident: $Value:ty
+        ),* $(,)?],
         $(
             $(#[$doc:meta])*
             $key:ident
@@ -98,6 +176,10 @@ macro_rules! declare_theme_options {
         /// Theme and colors of ferrishot
         #[derive(Debug, Copy, Clone)]
         pub struct Theme {
+            $(
+                $(#[$value_doc])*
+                pub $value_key: $Value,
+            )*
             $(
                 $(#[$doc])*
                 pub $key: iced::Color,
@@ -110,6 +192,11 @@ macro_rules! declare_theme_options {
             /// Palette
             #[ferrishot_knus(child, unwrap(properties))]
             palette: HashMap<String, u32>,
+            $(
+                $(#[$value_doc])*
+                #[ferrishot_knus(child, unwrap(argument))]
+                pub $value_key: $Value,
+            )*
             $(
                 $(#[$doc])*
                 #[ferrishot_knus(child)]
@@ -124,6 +211,11 @@ macro_rules! declare_theme_options {
             /// Palette
             #[ferrishot_knus(child, unwrap(properties))]
             palette: Option<HashMap<String, u32>>,
+            $(
+                $(#[$value_doc])*
+                #[ferrishot_knus(child, unwrap(argument))]
+                pub $value_key: Option<$Value>,
+            )*
             $(
                 $(#[$doc])*
                 #[ferrishot_knus(child)]
@@ -140,6 +232,9 @@ macro_rules! declare_theme_options {
                     self.palette.extend(palette.into_iter());
                 }
                 // merge rest of the keys
+                $(
+                    self.$value_key = user_theme.$value_key.unwrap_or(self.$value_key);
+                )*
                 $(
                     self.$key = user_theme.$key.unwrap_or(self.$key);
                 )*
@@ -152,6 +247,9 @@ macro_rules! declare_theme_options {
 
             fn try_from(value: DefaultKdlTheme) -> Result<Self, Self::Error> {
                 Ok(Self {
+                    $(
+                        $value_key: value.$value_key,
+                    )*
                     $(
                         $key: {
                             let hex = match value.$key.color {
@@ -180,6 +278,34 @@ macro_rules! declare_theme_options {
 }
 
 crate::declare_theme_options! {
+    [
+        /// Font of the text
+        font: FontFamily,
+        /// Size of the text, in pixels
+        font_size: u32,
+        /// Radius of the corners of buttons and text inputs
+        button_radius: u32,
+        /// Radius of the corners of boxes with information, like the mode indicator
+        info_box_radius: u32,
+        /// Radius of the corners of popups
+        popup_radius: u32,
+        /// Radius of the corners of tooltips
+        tooltip_radius: u32,
+        /// Width of the lines of the frame around the selection
+        selection_frame_width: u32,
+        /// Width of the border around popups, in their text color
+        popup_border_width: u32,
+        /// Width of the border around tooltips, in their text color
+        tooltip_border_width: u32,
+        /// Width of the lines of arrows, rectangles and freehand lines drawn on the screenshot
+        annotation_width: u32,
+        /// Height of the text written on the screenshot, in pixels. Rounded down to a
+        /// multiple of 7, the height of the bitmap font
+        annotation_text_size: u32,
+        /// Thickness of the crosshair lines following the cursor before there is a selection.
+        /// `0` disables the crosshair
+        crosshair_thickness: u32,
+    ],
     /// Cheatsheet background
     cheatsheet_bg,
     /// Cheatsheet text color
@@ -192,8 +318,14 @@ crate::declare_theme_options! {
 
     /// Color of the border around the selection
     selection_frame,
+    /// Contrasting color of the border around the selection, used by
+    /// some of the `selection-frame-style`s
+    selection_frame_alt,
     /// Color of the region outside of the selected area
     non_selected_region,
+    /// Color of the diagonal lines over the region outside of the selected area,
+    /// when `selection-frame-style` is `hatched`
+    non_selected_region_pattern,
     /// Color of drop shadow, used for stuff like:
     ///
     /// - drop shadow of icons
@@ -226,6 +358,14 @@ crate::declare_theme_options! {
     error_fg,
     /// Background color of the error boxes
     error_bg,
+    /// Color of the text on warnings
+    warning_fg,
+    /// Background color of the warning boxes
+    warning_bg,
+    /// Color of the text on info messages
+    info_fg,
+    /// Background color of the info message boxes
+    info_bg,
 
     //
     // --- Info Box ---
@@ -273,6 +413,152 @@ crate::declare_theme_options! {
     /// Background color of the image_uploaded popup
     image_uploaded_bg,
 
+    //
+    // --- Confirm exit popup ---
+    //
+    /// Foreground color of the popup asking to confirm exit
+    confirm_exit_fg,
+    /// Background color of the popup asking to confirm exit
+    confirm_exit_bg,
+
+    //
+    // --- Confirm preview popup ---
+    //
+    /// Foreground color of the popup previewing the captured image
+    confirm_preview_fg,
+    /// Background color of the popup previewing the captured image
+    confirm_preview_bg,
+
+    //
+    // --- Upload too large popup ---
+    //
+    /// Foreground color of the popup offering to make a screenshot smaller, before uploading it
+    upload_too_large_fg,
+    /// Background color of the popup offering to make a screenshot smaller, before uploading it
+    upload_too_large_bg,
+
+    //
+    // --- Guides ---
+    //
+    /// Color of the safe area guides inside of the selection
+    guide,
+    /// Text color of the popup to pick a guide
+    guides_fg,
+    /// Background color of the popup to pick a guide
+    guides_bg,
+
+    //
+    // --- Palette popup ---
+    //
+    /// Text color of the popup with the colors of the selection
+    color_palette_fg,
+    /// Background color of the popup with the colors of the selection
+    color_palette_bg,
+
+    //
+    // --- Histogram ---
+    //
+    /// Text color of the panel with the histogram of the selection,
+    /// also used for the luminance of the histogram
+    histogram_fg,
+    /// Background color of the panel with the histogram of the selection
+    histogram_bg,
+
+    //
+    // --- Frames ---
+    //
+    /// Text color of the scrub bar for picking the frame of an animated image
+    scrub_bar_fg,
+    /// Background color of the scrub bar for picking the frame of an animated image
+    scrub_bar_bg,
+
+    //
+    // --- Crop suggestion ---
+    //
+    /// Color of the dashed rectangle of the suggested crop
+    crop_suggestion,
+
+    //
+    // --- Recent regions ---
+    //
+    /// Color of the outlines of the recently used regions, shown before there is a selection
+    recent_region,
+
+    //
+    // --- Presentation ---
+    //
+    /// Color of the laser pointer following the cursor, with `--present`
+    laser_pointer,
+
+    //
+    // --- Measure ---
+    //
+    /// Color of the ruler dragged in the `measure` mode, and of its label
+    ruler,
+
+    //
+    // --- Crosshair ---
+    //
+    /// Color of the crosshair lines following the cursor before there is a selection
+    crosshair,
+
+    //
+    // --- Mode ---
+    //
+    /// Text color of the indicator showing the current mode
+    mode_indicator_fg,
+    /// Background color of the indicator showing the current mode
+    mode_indicator_bg,
+
+    //
+    // --- Stamp ---
+    //
+    /// Text color of the system information stamped onto the image
+    stamp_fg,
+    /// Background color of the system information stamped onto the image
+    stamp_bg,
+
+    //
+    // --- Annotations ---
+    //
+    /// Color of the arrows, rectangles and freehand lines drawn on the screenshot
+    annotation,
+    /// Color of the text written on the screenshot
+    annotation_text,
+
     /// Color of success, e.g. green check mark when copying text to clipboard
     success,
 }
+
+impl Theme {
+    /// Width of the lines of the frame around the selection
+    pub fn frame_width(&self) -> f32 {
+        self.selection_frame_width as f32
+    }
+
+    /// Border of buttons and text inputs
+    pub fn button_border(&self) -> iced::Border {
+        iced::Border::default().rounded(self.button_radius as f32)
+    }
+
+    /// Border of boxes with information, like the mode indicator
+    pub fn info_box_border(&self) -> iced::Border {
+        iced::Border::default().rounded(self.info_box_radius as f32)
+    }
+
+    /// Border of a popup with text of the `color`
+    pub fn popup_border(&self, color: iced::Color) -> iced::Border {
+        iced::Border::default()
+            .rounded(self.popup_radius as f32)
+            .width(self.popup_border_width as f32)
+            .color(color)
+    }
+
+    /// Border of tooltips
+    pub fn tooltip_border(&self) -> iced::Border {
+        iced::Border::default()
+            .rounded(self.tooltip_radius as f32)
+            .width(self.tooltip_border_width as f32)
+            .color(self.tooltip_fg)
+    }
+}
@@ -0,0 +1,309 @@
+//! Control a running ferrishot with `ferrishot ctl`
+//!
+//! With `control-socket` enabled, the app listens on a UNIX socket, or on a named pipe on
+//! Windows. Only the user running ferrishot can connect to it. `ferrishot ctl` sends it commands written just like inside of `on-start`, which
+//! run as if their keys were pressed. Scripts, tests and assistive tools drive ferrishot this way.
+//!
+//! A request is a single line with a JSON array of the commands, and so is its reply.
+//! The reply is sent once the commands have run.
+
+use std::{
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use iced::{
+    Task,
+    futures::{
+        SinkExt as _, Stream,
+        channel::{mpsc, oneshot},
+    },
+};
+use tokio::io::{
+    AsyncBufReadExt as _, AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _, BufReader,
+};
+
+use crate::config::commands::StartupCommand;
+
+/// Error sending commands to a running ferrishot
+#[derive(thiserror::Error, miette::Diagnostic, Debug)]
+pub enum Error {
+    /// Nothing listens on the socket
+    #[error("could not connect to a running ferrishot at {}: {source}", path.display())]
+    #[diagnostic(help("Is ferrishot running with `control-socket #true` in its config?"))]
+    Connect {
+        /// The socket
+        path: PathBuf,
+        /// Why it could not be connected to
+        source: io::Error,
+    },
+    /// The connection broke
+    #[error("failed to talk to the running ferrishot: {0}")]
+    Io(#[from] io::Error),
+    /// The reply could not be read
+    #[error("invalid reply from the running ferrishot: {0}")]
+    Reply(#[from] serde_json::Error),
+    /// The running ferrishot could not parse the commands
+    #[error("{0}")]
+    Rejected(String),
+}
+
+/// Requests longer than this many bytes are rejected
+const MAX_REQUEST_LEN: u64 = 64 * 1024;
+
+/// Reply of the running ferrishot to a request
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+enum Reply {
+    /// The commands ran
+    Ok,
+    /// The commands could not be parsed, so none of them run
+    Error(String),
+}
+
+/// Where the running ferrishot listens for commands
+///
+/// The socket is inside of the directory of the user's temporary files, which only they
+/// can access. It is created if it does not exist yet
+#[cfg(unix)]
+pub fn socket_path() -> io::Result<PathBuf> {
+    let dir = crate::temp::root();
+    crate::temp::create_private_dir(&dir)?;
+
+    Ok(dir.join("control.sock"))
+}
+
+/// Where the running ferrishot listens for commands
+///
+/// Named pipes are shared by all users, so the name of the pipe includes the user's name
+#[cfg(windows)]
+pub fn socket_path() -> io::Result<PathBuf> {
+    let user = std::env::var("USERNAME").map_err(io::Error::other)?;
+
+    Ok(PathBuf::from(format!(r"\\.\pipe\ferrishot-{user}")))
+}
+
+/// Parse the commands of a request. Each of them is written like inside of `on-start`
+fn parse(request: &str) -> Result<Vec<StartupCommand>, String> {
+    let commands = serde_json::from_str::<Vec<String>>(request).map_err(|err| err.to_string())?;
+
+    commands
+        .iter()
+        .map(|command| {
+            ferrishot_knus::parse::<Vec<StartupCommand>>("ferrishot ctl", command)
+                .map_err(|err| format!("{:?}", miette::Report::new(err)))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|commands| commands.into_iter().flatten().collect())
+}
+
+/// Send the `commands` to the running ferrishot, and wait until it accepts them
+pub async fn send(commands: &[String]) -> Result<(), Error> {
+    let path = socket_path()?;
+    let stream = connect(&path)
+        .await
+        .map_err(|source| Error::Connect { path, source })?;
+    let (read, mut write) = tokio::io::split(stream);
+
+    let mut request = serde_json::to_string(commands)?;
+    request.push('\n');
+    write.write_all(request.as_bytes()).await?;
+
+    let mut reply = String::new();
+    BufReader::new(read).read_line(&mut reply).await?;
+
+    match serde_json::from_str(&reply)? {
+        Reply::Ok => Ok(()),
+        Reply::Error(err) => Err(Error::Rejected(err)),
+    }
+}
+
+/// Connect to the socket of the running ferrishot
+#[cfg(unix)]
+async fn connect(path: &std::path::Path) -> io::Result<tokio::net::UnixStream> {
+    tokio::net::UnixStream::connect(path).await
+}
+
+/// Connect to the named pipe of the running ferrishot
+#[cfg(windows)]
+async fn connect(
+    path: &std::path::Path,
+) -> io::Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    tokio::net::windows::named_pipe::ClientOptions::new().open(path)
+}
+
+/// Listen for the commands of `ferrishot ctl`, and run them in the app
+pub fn listen() -> impl Stream<Item = crate::Message> {
+    iced::stream::channel(16, |mut output: mpsc::Sender<crate::Message>| async move {
+        if let Err(err) = serve(&mut output).await {
+            output
+                .send(crate::Message::Error(format!(
+                    "Failed to listen for `ferrishot ctl`: {err}"
+                )))
+                .await
+                .ok();
+        }
+    })
+}
+
+/// Accept connections to the socket, one at a time
+#[cfg(unix)]
+async fn serve(output: &mut mpsc::Sender<crate::Message>) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt as _;
+
+    let path = socket_path()?;
+
+    // left behind by a ferrishot which crashed. If another one is running, binding fails below
+    if path.exists() && tokio::net::UnixStream::connect(&path).await.is_err() {
+        std::fs::remove_file(&path)?;
+    }
+
+    let listener = tokio::net::UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    let uid = crate::temp::current_uid()?;
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+
+        let peer = stream.peer_cred()?.uid();
+        if peer != uid {
+            log::warn!("Refused a connection of `ferrishot ctl` by the user {peer}");
+            continue;
+        }
+
+        if let Err(err) = handle(stream, output).await {
+            log::warn!("Failed to handle a request of `ferrishot ctl`: {err}");
+        }
+    }
+}
+
+/// Accept connections to the named pipe, one at a time
+#[cfg(windows)]
+async fn serve(output: &mut mpsc::Sender<crate::Message>) -> io::Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let path = socket_path()?;
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(&path)?;
+
+    loop {
+        server.connect().await?;
+        // the next client connects to a new instance of the pipe
+        let client = std::mem::replace(&mut server, ServerOptions::new().create(&path)?);
+
+        if let Err(err) = handle(client, output).await {
+            log::warn!("Failed to handle a request of `ferrishot ctl`: {err}");
+        }
+    }
+}
+
+/// Read a request from the `stream`, send its commands to the app and reply
+async fn handle(
+    stream: impl AsyncRead + AsyncWrite,
+    output: &mut mpsc::Sender<crate::Message>,
+) -> io::Result<()> {
+    let (read, mut write) = tokio::io::split(stream);
+
+    let mut request = String::new();
+    BufReader::new(read.take(MAX_REQUEST_LEN))
+        .read_line(&mut request)
+        .await?;
+
+    let reply = if !request.ends_with('\n') && request.len() as u64 == MAX_REQUEST_LEN {
+        Reply::Error(format!(
+            "the request is longer than {MAX_REQUEST_LEN} bytes"
+        ))
+    } else {
+        match parse(&request) {
+            Ok(commands) => {
+                run(commands, output).await?;
+                Reply::Ok
+            }
+            Err(err) => Reply::Error(err),
+        }
+    };
+
+    let mut reply = serde_json::to_string(&reply)?;
+    reply.push('\n');
+    write.write_all(reply.as_bytes()).await
+}
+
+/// Send the `commands` to the app, and wait until it has run them
+async fn run(
+    commands: Vec<StartupCommand>,
+    output: &mut mpsc::Sender<crate::Message>,
+) -> io::Result<()> {
+    let (sender, ran) = oneshot::channel();
+
+    // the app handles messages in order, so once it has handled the last one, it ran
+    // all of the commands before it
+    let messages = commands
+        .into_iter()
+        .map(|command| crate::Message::Command {
+            action: command.into_command(),
+            count: 1,
+        })
+        .chain([crate::Message::Control(Message::Ran(Ran(Arc::new(
+            Mutex::new(Some(sender)),
+        ))))]);
+
+    for message in messages {
+        output.send(message).await.map_err(io::Error::other)?;
+    }
+
+    ran.await
+        .map_err(|_| io::Error::other("ferrishot exited before running the commands"))
+}
+
+/// Tells the request that its commands ran. Messages must be `Clone`, but this can
+/// only be used once
+#[derive(Debug, Clone)]
+pub struct Ran(Arc<Mutex<Option<oneshot::Sender<()>>>>);
+
+/// Control message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// All of the commands of a request ran
+    Ran(Ran),
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, _app: &mut crate::App) -> Task<crate::Message> {
+        match self {
+            Self::Ran(Ran(sender)) => {
+                let sender = sender
+                    .lock()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+                    .take();
+
+                if let Some(sender) = sender {
+                    // the request could have disconnected, then nobody waits for the reply
+                    sender.send(()).ok();
+                }
+            }
+        }
+
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_request() {
+        let commands = parse(r#"["select-region \"100x100+0+0\"", "copy-to-clipboard; exit"]"#)
+            .unwrap()
+            .into_iter()
+            .map(|command| command.into_command().name())
+            .collect::<Vec<_>>();
+
+        assert_eq!(commands, ["select-region", "copy-to-clipboard", "exit"]);
+
+        assert!(parse(r#"["frobnicate"]"#).is_err());
+        assert!(parse("select-region").is_err());
+    }
+}
@@ -0,0 +1,200 @@
+//! Write a crash report when ferrishot panics
+//!
+//! Ferrishot covers the entire screen. If it panics without explanation the user is
+//! left staring at a frozen fullscreen window, so instead we write a crash report to
+//! the cache directory, tell the user where it is and exit.
+//!
+//! The report has the panic with its backtrace, a summary of the config and the messages
+//! the app received last. Only the kind of each message is recorded, as that is cheap
+//! enough to do for every message. The last few messages are kept whole, and included
+//! with their contents.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use etcetera::BaseStrategy as _;
+
+/// How many of the most recent messages have their kind included in the crash report
+const MAX_RECORDED_MESSAGES: usize = 50;
+
+/// How many of the most recent messages are included in the crash report with their contents
+const MAX_DETAILED_MESSAGES: usize = 5;
+
+/// Exit code of the process after it crashed, same as the one Rust uses for panics
+const CRASH_EXIT_CODE: i32 = 101;
+
+/// Information gathered while ferrishot runs, included in the crash report
+struct Context {
+    /// Summary of the config that ferrishot is running with
+    config: Option<String>,
+    /// Kinds of the most recent messages received by the app
+    message_kinds: VecDeque<crate::message::MessageKind>,
+    /// The most recent messages received by the app
+    messages: VecDeque<crate::Message>,
+}
+
+/// Information for the crash report
+static CONTEXT: Mutex<Context> = Mutex::new(Context {
+    config: None,
+    message_kinds: VecDeque::new(),
+    messages: VecDeque::new(),
+});
+
+/// Install the panic hook which writes the crash report
+pub fn install() {
+    let default_hook = std::panic::take_hook();
+
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+
+        let report = report(info);
+
+        match write(&report) {
+            Ok(path) => {
+                log::error!(
+                    "ferrishot crashed, crash report written to {}",
+                    path.display()
+                );
+                #[allow(
+                    clippy::print_stderr,
+                    reason = "the user must know where the report is"
+                )]
+                {
+                    eprintln!(
+                        "\nferrishot crashed. A crash report was written to {}\nPlease attach it when opening an issue: {}/issues",
+                        path.display(),
+                        env!("CARGO_PKG_REPOSITORY"),
+                    );
+                }
+            }
+            Err(err) => {
+                log::error!("ferrishot crashed, failed to write crash report: {err}");
+                #[allow(
+                    clippy::print_stderr,
+                    reason = "the report could not be saved anywhere else"
+                )]
+                {
+                    eprintln!(
+                        "\nferrishot crashed. Failed to write the crash report ({err}):\n\n{report}"
+                    );
+                }
+            }
+        }
+
+        crate::temp::cleanup();
+
+        // the panic might have happened on a thread other than the one running the
+        // event loop, which would keep the fullscreen window open but unresponsive
+        std::process::exit(CRASH_EXIT_CODE);
+    }));
+}
+
+/// Remember the config that ferrishot runs with, to include it in the crash report
+pub fn set_config(config: &crate::Config) {
+    let summary = format!(
+        "size-indicator: {}\nselection-icons: {}\nconfirm-exit: {}\ndouble-click-action: {:?}\nselection-frame-style: {:?}\nguide: {:?}\nstamp enabled: {}\napp regions: {}",
+        config.size_indicator,
+        config.selection_icons,
+        config.confirm_exit,
+        config.double_click_action,
+        config.selection_frame_style,
+        config.guide,
+        config.stamp.enabled,
+        config.app_regions.apps.len(),
+    );
+
+    if let Ok(mut context) = CONTEXT.lock() {
+        context.config = Some(summary);
+    }
+}
+
+/// Remember a message received by the app, to include it in the crash report
+pub(crate) fn record_message(message: &crate::Message) {
+    if let Ok(mut context) = CONTEXT.lock() {
+        if context.message_kinds.len() >= MAX_RECORDED_MESSAGES {
+            context.message_kinds.pop_front();
+        }
+        context.message_kinds.push_back(message.into());
+
+        if context.messages.len() >= MAX_DETAILED_MESSAGES {
+            context.messages.pop_front();
+        }
+        context.messages.push_back(message.clone());
+    }
+}
+
+/// Create the contents of the crash report
+fn report(info: &std::panic::PanicHookInfo) -> String {
+    let mut report = String::new();
+
+    let _ = writeln!(report, "ferrishot {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(
+        report,
+        "{} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let _ = writeln!(
+        report,
+        "arguments: {}",
+        std::env::args().skip(1).collect::<Vec<_>>().join(" ")
+    );
+
+    let _ = writeln!(report, "\n--- panic ---\n{info}");
+    let _ = writeln!(
+        report,
+        "\n--- backtrace ---\n{}",
+        std::backtrace::Backtrace::force_capture()
+    );
+
+    // the panic could have happened while this thread held the lock,
+    // in which case locking again would never finish
+    let context = match CONTEXT.try_lock() {
+        Ok(context) => context,
+        Err(std::sync::TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        Err(std::sync::TryLockError::WouldBlock) => {
+            let _ = writeln!(report, "Could not access the config and the last messages");
+            return report;
+        }
+    };
+
+    let _ = writeln!(
+        report,
+        "--- config ---\n{}",
+        context
+            .config
+            .as_deref()
+            .unwrap_or("The config was not loaded yet")
+    );
+
+    let _ = writeln!(report, "\n--- kinds of the last messages ---");
+    for kind in &context.message_kinds {
+        let _ = writeln!(report, "{kind}");
+    }
+
+    let _ = writeln!(report, "\n--- last messages ---");
+    for message in &context.messages {
+        let _ = writeln!(report, "{message:?}");
+    }
+
+    report
+}
+
+/// Write the crash report to the cache directory, returning its path
+fn write(report: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    let dir = etcetera::choose_base_strategy()?.cache_dir();
+    std::fs::create_dir_all(&dir)?;
+
+    let path = dir.join(format!("ferrishot-crash-{timestamp}.txt"));
+    std::fs::write(&path, report)?;
+
+    Ok(path)
+}
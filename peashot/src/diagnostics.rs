@@ -0,0 +1,76 @@
+//! Information about the build and the environment ferrishot runs in
+//!
+//! Printed with `ferrishot --version --json`, to make bug reports actionable
+
+use std::path::Path;
+
+use crate::image::MonitorInfo;
+
+/// Build and runtime information
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    /// Version of ferrishot
+    pub version: &'static str,
+    /// Operating system ferrishot was compiled for
+    pub os: &'static str,
+    /// CPU architecture ferrishot was compiled for
+    pub arch: &'static str,
+    /// Whether this is a debug build
+    pub debug_assertions: bool,
+    /// Enabled cargo features
+    pub features: Vec<&'static str>,
+    /// The display server, e.g. `wayland` or `x11`
+    pub display_server: &'static str,
+    /// Renderer requested with `--renderer`
+    pub renderer: String,
+    /// Backend used to take screenshots
+    pub capture_backend: String,
+    /// Backend used to provide the clipboard
+    pub clipboard_backend: String,
+    /// All monitors, with their scale factors
+    pub monitors: Vec<MonitorInfo>,
+    /// Error obtained when trying to list the monitors
+    pub monitors_error: Option<String>,
+    /// Path to the config file
+    pub config_file: String,
+    /// Whether the config file exists
+    pub config_file_exists: bool,
+    /// Path to the log file
+    pub log_file: String,
+}
+
+impl Diagnostics {
+    /// Collect the diagnostics
+    pub fn collect(cli: &crate::Cli) -> Self {
+        let (monitors, monitors_error) = match crate::image::monitors() {
+            Ok(monitors) => (monitors, None),
+            Err(err) => (vec![], Some(err.to_string())),
+        };
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            os: std::env::consts::OS,
+            arch: std::env::consts::ARCH,
+            debug_assertions: cfg!(debug_assertions),
+            features: [cfg!(feature = "debug").then_some("debug")]
+                .into_iter()
+                .flatten()
+                .collect(),
+            display_server: crate::image::display_server(),
+            renderer: format!("{:?}", cli.renderer),
+            capture_backend: crate::image::capture_backend(),
+            clipboard_backend: crate::clipboard::backend(),
+            monitors,
+            monitors_error,
+            config_file: cli.config_file.clone(),
+            config_file_exists: Path::new(&cli.config_file).exists(),
+            log_file: cli.log_file.clone(),
+        }
+    }
+
+    /// Diagnostics formatted as JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("diagnostics are always valid JSON")
+    }
+}
@@ -0,0 +1,161 @@
+//! `ferrishot doctor` checks whether ferrishot is able to work in the current environment
+//!
+//! Each check prints whether it passed. Failed checks show a hint for how to fix them.
+
+use std::path::{Path, PathBuf};
+
+use etcetera::BaseStrategy as _;
+use strum::IntoEnumIterator as _;
+
+use crate::image::upload::ImageUploadService;
+
+/// A check that did not pass
+#[derive(thiserror::Error, miette::Diagnostic, Debug)]
+pub enum Problem {
+    /// Could not take a screenshot
+    #[error("could not take a screenshot: {0}")]
+    #[diagnostic(help(
+        "On Wayland, make sure `xdg-desktop-portal` and a portal backend for your compositor are running. On macOS, grant the Screen Recording permission"
+    ))]
+    Capture(String),
+    /// Could not access the clipboard
+    #[error("could not access the clipboard: {0}")]
+    #[diagnostic(help(
+        "On Linux, make sure you are in a Wayland session whose compositor supports data-control, or in an X11 session"
+    ))]
+    Clipboard(crate::clipboard::ClipboardError),
+    /// The clipboard daemon could not be spawned, or could not access the clipboard
+    #[error("the clipboard daemon failed: {0}")]
+    #[diagnostic(help(
+        "The clipboard daemon is ferrishot itself running in the background. Make sure ferrishot is not moved or deleted while running"
+    ))]
+    #[cfg(target_os = "linux")]
+    ClipboardDaemon(crate::clipboard::DaemonError),
+    /// Could not write to a directory
+    #[error("cannot write to the {what} directory {}: {source}", path.display())]
+    #[diagnostic(help("Check the permissions of the directory, or choose a different location"))]
+    Write {
+        /// What the directory is used for
+        what: &'static str,
+        /// The directory
+        path: PathBuf,
+        /// Why writing failed
+        source: std::io::Error,
+    },
+    /// Could not reach an upload service
+    #[error("could not reach {service:?}: {error}")]
+    #[diagnostic(help(
+        "Check your internet connection. Uploading still works as long as at least 1 service can be reached"
+    ))]
+    Upload {
+        /// The service that could not be reached
+        service: ImageUploadService,
+        /// Why it could not be reached
+        error: String,
+    },
+}
+
+/// Run all of the checks, printing the results
+///
+/// # Returns
+///
+/// `true` if all checks passed
+#[allow(
+    clippy::print_stderr,
+    clippy::print_stdout,
+    reason = "doctor reports its results to the terminal"
+)]
+pub fn run(cli: &crate::Cli) -> bool {
+    let green = anstyle::AnsiColor::Green
+        .on_default()
+        .effects(anstyle::Effects::BOLD);
+    let red = anstyle::AnsiColor::Red
+        .on_default()
+        .effects(anstyle::Effects::BOLD);
+    let reset = anstyle::Reset;
+
+    let mut all_passed = true;
+    let mut report = |name: &str, result: Result<(), Problem>| match result {
+        Ok(()) => println!("{green}✓{reset} {name}"),
+        Err(problem) => {
+            all_passed = false;
+            println!("{red}✗{reset} {name}");
+            eprintln!("{:?}", miette::Report::new(problem));
+        }
+    };
+
+    report(
+        "Take a screenshot",
+        crate::image::get_image(None)
+            .map(|_| ())
+            .map_err(|err| Problem::Capture(err.to_string())),
+    );
+
+    report(
+        "Access the clipboard",
+        crate::clipboard::check().map_err(Problem::Clipboard),
+    );
+
+    #[cfg(target_os = "linux")]
+    report(
+        "Spawn the clipboard daemon",
+        crate::clipboard::probe_daemon().map_err(Problem::ClipboardDaemon),
+    );
+
+    let mut dirs = vec![
+        (
+            "log",
+            Path::new(&cli.log_file).parent().map(Path::to_path_buf),
+        ),
+        (
+            "config",
+            Path::new(&cli.config_file).parent().map(Path::to_path_buf),
+        ),
+        (
+            "cache",
+            etcetera::choose_base_strategy()
+                .ok()
+                .map(|strategy| strategy.cache_dir()),
+        ),
+    ];
+    if let Some(save_path) = &cli.save_path {
+        dirs.push(("save", save_path.parent().map(Path::to_path_buf)));
+    }
+
+    for (what, path) in dirs {
+        let Some(path) = path else {
+            continue;
+        };
+
+        report(
+            &format!("Write to the {what} directory"),
+            can_write(&path).map_err(|source| Problem::Write { what, path, source }),
+        );
+    }
+
+    match tokio::runtime::Runtime::new() {
+        Ok(runtime) => {
+            for service in ImageUploadService::iter() {
+                report(
+                    &format!("Reach upload service {service:?}"),
+                    runtime
+                        .block_on(service.check_connectivity())
+                        .map_err(|err| Problem::Upload {
+                            service,
+                            error: err.to_string(),
+                        }),
+                );
+            }
+        }
+        Err(err) => log::error!("Failed to start the async runtime: {err}"),
+    }
+
+    all_passed
+}
+
+/// Check that we can create files in the directory, creating it if it does not exist
+fn can_write(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    tempfile::NamedTempFile::new_in(dir)?;
+    Ok(())
+}
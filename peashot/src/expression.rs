@@ -0,0 +1,158 @@
+//! Evaluate simple arithmetic expressions, like `1920/2` or `(300 + 16) * 2`
+//!
+//! Supports `+`, `-`, `*`, `/`, parentheses and negative numbers,
+//! with the usual precedence
+
+use std::{iter::Peekable, str::Chars};
+
+/// Error evaluating an expression
+#[derive(thiserror::Error, miette::Diagnostic, Debug, Clone, PartialEq, Eq)]
+pub enum ExpressionError {
+    /// The expression ended where a number was expected
+    #[error("Expected a number at the end")]
+    UnexpectedEnd,
+    /// A character which cannot be there
+    #[error("Unexpected `{0}`")]
+    UnexpectedChar(char),
+    /// Dividing by zero
+    #[error("Cannot divide by zero")]
+    DivisionByZero,
+    /// The number is malformed, like `1.2.3`
+    #[error("Invalid number `{0}`")]
+    InvalidNumber(String),
+}
+
+/// Evaluate the arithmetic `expression`
+pub fn eval(expression: &str) -> Result<f64, ExpressionError> {
+    let mut parser = Parser {
+        chars: expression.chars().peekable(),
+    };
+
+    let value = parser.sum()?;
+
+    match parser.next() {
+        Some(ch) => Err(ExpressionError::UnexpectedChar(ch)),
+        None => Ok(value),
+    }
+}
+
+/// Recursive descent parser, which evaluates the expression while reading it
+struct Parser<'a> {
+    /// Remaining characters of the expression
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    /// The next character which is not whitespace, without consuming it
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    /// Consume the next character which is not whitespace
+    fn next(&mut self) -> Option<char> {
+        self.peek()?;
+        self.chars.next()
+    }
+
+    /// Terms added or subtracted, like `1 + 2 * 3 - 4`
+    fn sum(&mut self) -> Result<f64, ExpressionError> {
+        let mut value = self.product()?;
+
+        loop {
+            match self.peek() {
+                Some('+') => {
+                    self.next();
+                    value += self.product()?;
+                }
+                Some('-') => {
+                    self.next();
+                    value -= self.product()?;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    /// Factors multiplied or divided, like `2 * 3 / 4`
+    fn product(&mut self) -> Result<f64, ExpressionError> {
+        let mut value = self.factor()?;
+
+        loop {
+            match self.peek() {
+                Some('*') => {
+                    self.next();
+                    value *= self.factor()?;
+                }
+                Some('/') => {
+                    self.next();
+                    let divisor = self.factor()?;
+                    if divisor == 0.0 {
+                        return Err(ExpressionError::DivisionByZero);
+                    }
+                    value /= divisor;
+                }
+                _ => return Ok(value),
+            }
+        }
+    }
+
+    /// A number, a negated factor like `-2`, or an expression in parentheses
+    fn factor(&mut self) -> Result<f64, ExpressionError> {
+        match self.peek() {
+            Some('-') => {
+                self.next();
+                Ok(-self.factor()?)
+            }
+            Some('(') => {
+                self.next();
+                let value = self.sum()?;
+                match self.next() {
+                    Some(')') => Ok(value),
+                    Some(ch) => Err(ExpressionError::UnexpectedChar(ch)),
+                    None => Err(ExpressionError::UnexpectedEnd),
+                }
+            }
+            Some(ch) if ch.is_ascii_digit() || ch == '.' => {
+                let mut number = String::new();
+                while let Some(digit) = self.chars.next_if(|ch| ch.is_ascii_digit() || *ch == '.') {
+                    number.push(digit);
+                }
+                number
+                    .parse()
+                    .map_err(|_| ExpressionError::InvalidNumber(number))
+            }
+            Some(ch) => Err(ExpressionError::UnexpectedChar(ch)),
+            None => Err(ExpressionError::UnexpectedEnd),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp, reason = "exact values")]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(eval("1920/2"), Ok(960.0));
+        assert_eq!(eval("300+16"), Ok(316.0));
+        assert_eq!(eval(" 2 + 3 * 4 "), Ok(14.0));
+        assert_eq!(eval("(2 + 3) * 4"), Ok(20.0));
+        assert_eq!(eval("10 - 2 - 3"), Ok(5.0));
+        assert_eq!(eval("-4 * -0.5"), Ok(2.0));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(eval("1 +"), Err(ExpressionError::UnexpectedEnd));
+        assert_eq!(eval("2 * x"), Err(ExpressionError::UnexpectedChar('x')));
+        assert_eq!(eval("(1 + 2"), Err(ExpressionError::UnexpectedEnd));
+        assert_eq!(eval("4 / (2 - 2)"), Err(ExpressionError::DivisionByZero));
+        assert_eq!(
+            eval("1.2.3"),
+            Err(ExpressionError::InvalidNumber("1.2.3".to_string()))
+        );
+    }
+}
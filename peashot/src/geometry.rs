@@ -220,6 +220,16 @@ impl FromStr for SideOrCorner {
 
 /// A named place of the rectangle
 impl Corner {
+    /// The next corner, going clockwise
+    pub const fn clockwise(self) -> Self {
+        match self {
+            Self::TopLeft => Self::TopRight,
+            Self::TopRight => Self::BottomRight,
+            Self::BottomRight => Self::BottomLeft,
+            Self::BottomLeft => Self::TopLeft,
+        }
+    }
+
     /// # Arguments
     ///
     /// - `self`: The corner, next to which we are resizing
@@ -282,6 +292,16 @@ pub struct Corners {
 }
 
 impl Corners {
+    /// Position of the `corner`
+    pub const fn get(&self, corner: Corner) -> Point {
+        match corner {
+            Corner::TopLeft => self.top_left,
+            Corner::TopRight => self.top_right,
+            Corner::BottomLeft => self.bottom_left,
+            Corner::BottomRight => self.bottom_right,
+        }
+    }
+
     /// Finds the nearest corner to this point
     pub fn nearest_corner(&self, point: Point) -> (Point, Corner) {
         let corners = [
@@ -302,57 +322,57 @@ impl Corners {
     }
 
     /// Return the interaction side for a point, if exists
-    pub fn side_at(&self, point: Point) -> Option<SideOrCorner> {
-        /// Shadow to apply to elements
-        /// The area around each side of the frame which allows that side to be hovered over and resized
-        const FRAME_INTERACTION_AREA: f32 = 35.0;
+    ///
+    /// `interaction_area` is the width of the area around each side and corner,
+    /// which allows it to be hovered over and resized
+    pub fn side_at(&self, point: Point, interaction_area: f32) -> Option<SideOrCorner> {
         let top = Rectangle {
             x: self.top_left.x,
-            y: self.top_left.y - FRAME_INTERACTION_AREA / 2.,
+            y: self.top_left.y - interaction_area / 2.,
             width: self.top_right.x - self.top_left.x,
-            height: FRAME_INTERACTION_AREA,
+            height: interaction_area,
         };
         let bottom = Rectangle {
             x: self.bottom_left.x,
-            y: self.bottom_left.y - FRAME_INTERACTION_AREA / 2.,
+            y: self.bottom_left.y - interaction_area / 2.,
             width: self.bottom_right.x - self.bottom_left.x,
-            height: FRAME_INTERACTION_AREA,
+            height: interaction_area,
         };
         let left = Rectangle {
-            x: self.top_left.x - FRAME_INTERACTION_AREA / 2.,
+            x: self.top_left.x - interaction_area / 2.,
             y: self.top_left.y,
-            width: FRAME_INTERACTION_AREA,
+            width: interaction_area,
             height: self.bottom_left.y - self.top_left.y,
         };
         let right = Rectangle {
-            x: self.top_right.x - FRAME_INTERACTION_AREA / 2.,
+            x: self.top_right.x - interaction_area / 2.,
             y: self.top_right.y,
-            width: FRAME_INTERACTION_AREA,
+            width: interaction_area,
             height: self.bottom_right.y - self.top_right.y,
         };
         let top_left = Rectangle {
-            x: self.top_left.x - FRAME_INTERACTION_AREA / 2.,
-            y: self.top_left.y - FRAME_INTERACTION_AREA / 2.,
-            width: FRAME_INTERACTION_AREA,
-            height: FRAME_INTERACTION_AREA,
+            x: self.top_left.x - interaction_area / 2.,
+            y: self.top_left.y - interaction_area / 2.,
+            width: interaction_area,
+            height: interaction_area,
         };
         let top_right = Rectangle {
-            x: self.top_right.x - FRAME_INTERACTION_AREA / 2.,
-            y: self.top_right.y - FRAME_INTERACTION_AREA / 2.,
-            width: FRAME_INTERACTION_AREA,
-            height: FRAME_INTERACTION_AREA,
+            x: self.top_right.x - interaction_area / 2.,
+            y: self.top_right.y - interaction_area / 2.,
+            width: interaction_area,
+            height: interaction_area,
         };
         let bottom_left = Rectangle {
-            x: self.bottom_left.x - FRAME_INTERACTION_AREA / 2.,
-            y: self.bottom_left.y - FRAME_INTERACTION_AREA / 2.,
-            width: FRAME_INTERACTION_AREA,
-            height: FRAME_INTERACTION_AREA,
+            x: self.bottom_left.x - interaction_area / 2.,
+            y: self.bottom_left.y - interaction_area / 2.,
+            width: interaction_area,
+            height: interaction_area,
         };
         let bottom_right = Rectangle {
-            x: self.bottom_right.x - FRAME_INTERACTION_AREA / 2.,
-            y: self.bottom_right.y - FRAME_INTERACTION_AREA / 2.,
-            width: FRAME_INTERACTION_AREA,
-            height: FRAME_INTERACTION_AREA,
+            x: self.bottom_right.x - interaction_area / 2.,
+            y: self.bottom_right.y - interaction_area / 2.,
+            width: interaction_area,
+            height: interaction_area,
         };
 
         [
@@ -418,7 +438,7 @@ pub impl Rectangle<f32> {
         self.y += dy;
 
         self.height = (self.height - dy).min((container.y + container.height) - self.y);
-        self.width = (self.width - dx).min((container.x + container.width) - self.y);
+        self.width = (self.width - dx).min((container.x + container.width) - self.x);
 
         self
     }
@@ -570,6 +590,79 @@ pub impl Rectangle<f32> {
         })
     }
 
+    /// Grow the rectangle around its center, until it is at least as large as `min`
+    fn expanded_to(self, min: Size) -> Self {
+        let rect = self.norm();
+        let center = rect.center();
+        let size = Size::new(rect.width.max(min.width), rect.height.max(min.height));
+
+        Self::new(
+            Point::new(center.x - size.width / 2.0, center.y - size.height / 2.0),
+            size,
+        )
+    }
+
+    /// Change the size of the rectangle to `size`, keeping its center in place
+    fn resized_around_center(self, size: Size) -> Self {
+        let center = self.norm().center();
+
+        Self::new(
+            Point::new(center.x - size.width / 2.0, center.y - size.height / 2.0),
+            size,
+        )
+    }
+
+    /// Move the rectangle the least amount so it is inside of the `container`,
+    /// without changing its size
+    fn moved_inside(self, container: Rectangle) -> Self {
+        let rect = self.norm();
+
+        rect.with_pos(|pos| Point {
+            x: pos
+                .x
+                .min(container.x + container.width - rect.width)
+                .max(container.x),
+            y: pos
+                .y
+                .min(container.y + container.height - rect.height)
+                .max(container.y),
+        })
+    }
+
+    /// Change the size of the rectangle to `size`, keeping the `anchor` corner in place
+    fn resized_from(self, anchor: Corner, size: Size) -> Self {
+        let rect = self.norm();
+
+        let x = match anchor {
+            Corner::TopLeft | Corner::BottomLeft => rect.x,
+            Corner::TopRight | Corner::BottomRight => rect.x + rect.width - size.width,
+        };
+        let y = match anchor {
+            Corner::TopLeft | Corner::TopRight => rect.y,
+            Corner::BottomLeft | Corner::BottomRight => rect.y + rect.height - size.height,
+        };
+
+        Self::new(Point::new(x, y), size)
+    }
+
+    /// Snap the edges of the rectangle to a grid, where each cell is `grid` pixels large
+    ///
+    /// The edges are snapped instead of the size, so the rectangle does not drift
+    /// when it is dragged. Works for rectangles with negative width or height
+    fn snapped(self, grid: f32) -> Self {
+        let snap = |value: f32| (value / grid).round() * grid;
+
+        let x = snap(self.x);
+        let y = snap(self.y);
+
+        Self {
+            x,
+            y,
+            width: snap(self.x + self.width) - x,
+            height: snap(self.y + self.height) - y,
+        }
+    }
+
     /// Convert this rectangle into a string
     fn as_str(&self) -> String {
         format!(
@@ -629,6 +722,14 @@ mod tests {
         assert_eq!(vec.y, 3.0);
     }
 
+    #[test]
+    fn test_corner_clockwise() {
+        assert_eq!(Corner::TopLeft.clockwise(), Corner::TopRight);
+        assert_eq!(Corner::TopRight.clockwise(), Corner::BottomRight);
+        assert_eq!(Corner::BottomRight.clockwise(), Corner::BottomLeft);
+        assert_eq!(Corner::BottomLeft.clockwise(), Corner::TopLeft);
+    }
+
     #[test]
     fn test_corner_resize_rect() {
         let initial_rect = Rectangle {
@@ -685,6 +786,97 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rectangle_expanded_to() {
+        // clicked without dragging
+        let click = Rectangle::new(Point::new(100.0, 50.0), Size::ZERO);
+        assert_eq!(
+            click.expanded_to(Size::square(10.0)),
+            Rectangle::new(Point::new(95.0, 45.0), Size::square(10.0))
+        );
+
+        // only the tiny dimension grows
+        let wide = Rectangle::new(Point::new(0.0, 20.0), Size::new(300.0, 2.0));
+        assert_eq!(
+            wide.expanded_to(Size::square(10.0)),
+            Rectangle::new(Point::new(0.0, 16.0), Size::new(300.0, 10.0))
+        );
+    }
+
+    #[test]
+    fn test_rectangle_moved_inside() {
+        let container = Rectangle::new(Point::ORIGIN, Size::new(200.0, 100.0));
+
+        assert_eq!(
+            Rectangle::new(Point::new(-5.0, 95.0), Size::square(10.0)).moved_inside(container),
+            Rectangle::new(Point::new(0.0, 90.0), Size::square(10.0))
+        );
+    }
+
+    #[test]
+    fn test_rectangle_resized_around_center() {
+        let rect = Rectangle::new(Point::new(10.0, 20.0), Size::new(100.0, 50.0));
+
+        assert_eq!(
+            rect.resized_around_center(Size::new(120.0, 30.0)),
+            Rectangle::new(Point::new(0.0, 30.0), Size::new(120.0, 30.0))
+        );
+        // a selection created from the bottom right has a negative size
+        assert_eq!(
+            Rectangle::new(Point::new(110.0, 70.0), Size::new(-100.0, -50.0))
+                .resized_around_center(Size::new(120.0, 30.0)),
+            Rectangle::new(Point::new(0.0, 30.0), Size::new(120.0, 30.0))
+        );
+    }
+
+    #[test]
+    fn test_rectangle_resized_from() {
+        let rect = Rectangle::new(Point::new(10.0, 20.0), Size::new(100.0, 50.0));
+        let size = Size::new(50.0, 100.0);
+
+        assert_eq!(
+            rect.resized_from(Corner::TopLeft, size),
+            Rectangle::new(Point::new(10.0, 20.0), size)
+        );
+        assert_eq!(
+            rect.resized_from(Corner::BottomRight, size),
+            Rectangle::new(Point::new(60.0, -30.0), size)
+        );
+        assert_eq!(
+            rect.resized_from(Corner::TopRight, size),
+            Rectangle::new(Point::new(60.0, 20.0), size)
+        );
+    }
+
+    #[test]
+    fn test_rectangle_snapped() {
+        let rect = Rectangle {
+            x: 10.4,
+            y: 19.6,
+            width: 100.3,
+            height: -40.2,
+        };
+
+        assert_eq!(
+            rect.snapped(1.0),
+            Rectangle {
+                x: 10.0,
+                y: 20.0,
+                width: 101.0,
+                height: -41.0
+            }
+        );
+        assert_eq!(
+            rect.snapped(8.0),
+            Rectangle {
+                x: 8.0,
+                y: 16.0,
+                width: 104.0,
+                height: -40.0
+            }
+        );
+    }
+
     #[test]
     fn test_corners_nearest_corner() {
         let corners = Corners {
@@ -718,83 +910,111 @@ mod tests {
 
     #[test]
     fn test_corners_side_at() {
-        const HALF_INTERACTION: f32 = 35.0 / 2.0;
+        const INTERACTION_AREA: f32 = 35.0;
+        const HALF_INTERACTION: f32 = INTERACTION_AREA / 2.0;
 
         let rect = Rectangle::new(Point::new(100.0, 100.0), Size::new(200.0, 150.0));
         let corners = rect.corners();
 
         assert_eq!(
-            corners.side_at(Point::new(100.0, 100.0)),
+            corners.side_at(Point::new(100.0, 100.0), INTERACTION_AREA),
             Some(SideOrCorner::Corner(Corner::TopLeft))
         );
         assert_eq!(
-            corners.side_at(Point::new(
-                100.0 - HALF_INTERACTION + 1.0,
-                100.0 - HALF_INTERACTION + 1.0
-            )),
+            corners.side_at(
+                Point::new(
+                    100.0 - HALF_INTERACTION + 1.0,
+                    100.0 - HALF_INTERACTION + 1.0
+                ),
+                INTERACTION_AREA
+            ),
             Some(SideOrCorner::Corner(Corner::TopLeft))
         );
         assert_eq!(
-            corners.side_at(Point::new(300.0, 100.0)),
+            corners.side_at(Point::new(300.0, 100.0), INTERACTION_AREA),
             Some(SideOrCorner::Corner(Corner::TopRight))
         );
         assert_eq!(
-            corners.side_at(Point::new(100.0, 250.0)),
+            corners.side_at(Point::new(100.0, 250.0), INTERACTION_AREA),
             Some(SideOrCorner::Corner(Corner::BottomLeft))
         );
         assert_eq!(
-            corners.side_at(Point::new(300.0, 250.0)),
+            corners.side_at(Point::new(300.0, 250.0), INTERACTION_AREA),
             Some(SideOrCorner::Corner(Corner::BottomRight))
         );
 
         assert_eq!(
-            corners.side_at(Point::new(200.0, 100.0)),
+            corners.side_at(Point::new(200.0, 100.0), INTERACTION_AREA),
             Some(SideOrCorner::Side(Side::Top))
         );
         assert_eq!(
-            corners.side_at(Point::new(200.0, 100.0 - HALF_INTERACTION + 1.0)),
+            corners.side_at(
+                Point::new(200.0, 100.0 - HALF_INTERACTION + 1.0),
+                INTERACTION_AREA
+            ),
             Some(SideOrCorner::Side(Side::Top))
         );
         assert_eq!(
-            corners.side_at(Point::new(200.0, 100.0 + HALF_INTERACTION - 1.0)),
+            corners.side_at(
+                Point::new(200.0, 100.0 + HALF_INTERACTION - 1.0),
+                INTERACTION_AREA
+            ),
             Some(SideOrCorner::Side(Side::Top))
         );
 
         assert_eq!(
-            corners.side_at(Point::new(200.0, 250.0)),
+            corners.side_at(Point::new(200.0, 250.0), INTERACTION_AREA),
             Some(SideOrCorner::Side(Side::Bottom))
         );
         assert_eq!(
-            corners.side_at(Point::new(200.0, 250.0 - HALF_INTERACTION + 1.0)),
+            corners.side_at(
+                Point::new(200.0, 250.0 - HALF_INTERACTION + 1.0),
+                INTERACTION_AREA
+            ),
             Some(SideOrCorner::Side(Side::Bottom))
         );
 
         assert_eq!(
-            corners.side_at(Point::new(100.0, 150.0)),
+            corners.side_at(Point::new(100.0, 150.0), INTERACTION_AREA),
             Some(SideOrCorner::Side(Side::Left))
         );
         assert_eq!(
-            corners.side_at(Point::new(100.0 - HALF_INTERACTION + 1.0, 150.0)),
+            corners.side_at(
+                Point::new(100.0 - HALF_INTERACTION + 1.0, 150.0),
+                INTERACTION_AREA
+            ),
             Some(SideOrCorner::Side(Side::Left))
         );
 
         assert_eq!(
-            corners.side_at(Point::new(300.0, 150.0)),
+            corners.side_at(Point::new(300.0, 150.0), INTERACTION_AREA),
             Some(SideOrCorner::Side(Side::Right))
         );
         assert_eq!(
-            corners.side_at(Point::new(300.0 + HALF_INTERACTION - 1.0, 150.0)),
+            corners.side_at(
+                Point::new(300.0 + HALF_INTERACTION - 1.0, 150.0),
+                INTERACTION_AREA
+            ),
             Some(SideOrCorner::Side(Side::Right))
         );
 
         // Test point outside any interaction area
-        assert_eq!(corners.side_at(Point::new(0.0, 0.0)), None);
         assert_eq!(
-            corners.side_at(Point::new(200.0, 100.0 + HALF_INTERACTION + 1.0)),
+            corners.side_at(Point::new(0.0, 0.0), INTERACTION_AREA),
+            None
+        );
+        assert_eq!(
+            corners.side_at(
+                Point::new(200.0, 100.0 + HALF_INTERACTION + 1.0),
+                INTERACTION_AREA
+            ),
             None
         );
         // Just below top interaction
-        assert_eq!(corners.side_at(Point::new(200.0, 200.0)), None);
+        assert_eq!(
+            corners.side_at(Point::new(200.0, 200.0), INTERACTION_AREA),
+            None
+        );
 
         // Point within
         // - top-left corner rect
@@ -805,7 +1025,7 @@ mod tests {
             100.0 - HALF_INTERACTION / 2.0,
         );
         assert_eq!(
-            corners.side_at(point_in_top_left_corner_interaction),
+            corners.side_at(point_in_top_left_corner_interaction, INTERACTION_AREA),
             Some(SideOrCorner::Corner(Corner::TopLeft))
         );
     }
@@ -81,6 +81,10 @@ load_icons! {
     Check,
     /// Loading...
     Spinner,
+    /// Keep the aspect ratio of the width and height
+    Link,
+    /// Show the actions which do not fit
+    More,
 }
 
 /// Expands to an SVG by reading from the `icons/` directory
@@ -1,74 +1,151 @@
-//! One of 3 actions:
+//! One of 4 actions:
 //!
 //! - Upload image
 //! - Copy image
 //! - Save image
-use std::path::PathBuf;
+//! - Save image as a PDF
+use std::path::{Path, PathBuf};
 
 use iced::Rectangle;
 use iced::Task;
 use image::DynamicImage;
 
 use crate::image::upload::ImageUploaded;
+use crate::ui::popup::{Popup, confirm_preview, upload_too_large};
 use crate::{App, geometry::RectangleExt as _, ui::popup::image_uploaded};
 use iced::widget;
 
 // INFO: Documentation comments for the enum are used in `--help`
 crate::declare_commands! {
-    #[derive(clap::ValueEnum)]
+    #[derive(clap::ValueEnum, ferrishot_knus::DecodeScalar)]
     /// Action to take with the image
     enum Command {
-        /// Copy image to the clipboard
+        /// Upload image to the internet
         UploadScreenshot,
-        /// Save image to a file
+        /// Copy image to the clipboard
         CopyToClipboard,
-        /// Upload image to the internet
+        /// Save image to a file
         SaveScreenshot,
+        /// Save image to a PDF file with a single page
+        SaveAsPdf,
     }
 }
 
 impl crate::command::Handler for Command {
     fn handle(self, app: &mut App, _count: u32) -> Task<crate::Message> {
-        let Some(rect) = app.selection.map(|sel| sel.rect.norm()) else {
-            app.errors.push(match self {
-                Self::CopyToClipboard => "There is no selection to copy",
-                Self::UploadScreenshot => "There is no selection to upload",
-                Self::SaveScreenshot => "There is no selection to save",
-            });
-            return Task::none();
+        let rect = match app.selection.map(|sel| sel.rect.norm()) {
+            Some(rect) => rect,
+            None if app.config.full_screen_without_selection => app.image.bounds(),
+            None => {
+                app.errors.warn(match self {
+                    Self::CopyToClipboard => "There is no selection to copy",
+                    Self::UploadScreenshot => "There is no selection to upload",
+                    Self::SaveScreenshot | Self::SaveAsPdf => "There is no selection to save",
+                });
+                return Task::none();
+            }
         };
 
+        if self == Self::CopyToClipboard
+            && let Some(err) = &app.clipboard_error
+        {
+            let keys_of = |action| {
+                app.config
+                    .keys
+                    .keys_of(&crate::Command::ImageUpload(action))
+                    .map(|keys| format!(" ({keys})"))
+                    .unwrap_or_default()
+            };
+            app.errors.warn(format!(
+                "Cannot copy, the clipboard is unavailable: {err}. Save{} or upload{} the screenshot instead",
+                keys_of(Self::SaveScreenshot),
+                keys_of(Self::UploadScreenshot),
+            ));
+            return Task::none();
+        }
+
+        let image = App::process_image(
+            rect,
+            &app.image,
+            &app.annotations.shapes,
+            app.is_stamp_enabled.then_some(&app.config.stamp),
+            &app.config.theme,
+        );
+
+        if matches!(app.popup, Some(Popup::ConfirmPreview(_))) {
+            // the preview was confirmed
+            app.popup = None;
+        } else if app.config.confirm_preview {
+            app.popup = Some(Popup::ConfirmPreview(confirm_preview::State {
+                action: self,
+                width: image.width(),
+                height: image.height(),
+                image: widget::image::Handle::from_rgba(
+                    image.width(),
+                    image.height(),
+                    image.to_rgba8().into_raw(),
+                ),
+            }));
+            return Task::none();
+        }
+
         if self == Self::UploadScreenshot {
             app.is_uploading_image = true;
         }
 
-        let image = App::process_image(rect, &app.image);
+        let max_upload_size = app.config.max_upload_size;
+        let skip_duplicates = app.config.skip_duplicate_captures;
 
         Task::future(async move {
-            match self.execute(image, rect).await {
-                Ok((Output::Saved | Output::Copied, _)) => crate::message::Message::Exit,
-                Ok((
-                    Output::Uploaded {
-                        path,
-                        data,
-                        file_size,
-                    },
-                    ImageData { height, width },
-                )) => crate::Message::ImageUploaded(image_uploaded::Message::ImageUploaded(
-                    image_uploaded::ImageUploadedData {
-                        image_uploaded: data,
-                        uploaded_image: widget::image::Handle::from_path(&path),
-                        height,
-                        width,
-                        file_size,
-                    },
-                )),
-                Err(err) => crate::Message::Error(err.to_string()),
-            }
+            outcome(
+                self.execute(image, rect, max_upload_size, skip_duplicates)
+                    .await,
+                upload_too_large::Attempt {
+                    region: rect,
+                    scale: 1.0,
+                    format: image::ImageFormat::Png,
+                },
+            )
         })
     }
 }
 
+/// Message with the outcome of running an action on the image. When the image was too
+/// large to upload, the `attempt` can be retried with a smaller image
+pub fn outcome(
+    result: Result<(Output, ImageData), Error>,
+    attempt: upload_too_large::Attempt,
+) -> crate::Message {
+    match result {
+        Ok((Output::Saved | Output::Copied, _)) => crate::message::Message::Exit,
+        Ok((
+            Output::Uploaded {
+                bytes,
+                data,
+                file_size,
+            },
+            ImageData { height, width },
+        )) => crate::Message::ImageUploaded(image_uploaded::Message::ImageUploaded(
+            image_uploaded::ImageUploadedData {
+                image_uploaded: data,
+                uploaded_image: widget::image::Handle::from_bytes(bytes.clone()),
+                encoded_image: bytes.into(),
+                height,
+                width,
+                file_size,
+            },
+        )),
+        Err(Error::UploadTooLarge { size, limit }) => crate::Message::UploadTooLarge(
+            upload_too_large::Message::TooLarge(upload_too_large::State {
+                size,
+                limit,
+                attempt,
+            }),
+        ),
+        Err(err) => crate::Message::Error(err.to_string()),
+    }
+}
+
 /// Data about the image
 pub struct ImageData {
     /// Height of the image (pixels)
@@ -91,9 +168,62 @@ pub enum Output {
         data: ImageUploaded,
         /// file size in bytes
         file_size: u64,
-        /// Path to the uploaded image
-        path: PathBuf,
+        /// The uploaded image, encoded. Kept in memory, as the file it was
+        /// uploaded from is deleted right after uploading
+        bytes: Vec<u8>,
+    },
+}
+
+/// What is printed with `--json` after running an action outside of the app
+#[derive(serde::Serialize, Debug)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum JsonOutput<'a> {
+    /// Saved to a file
+    Save {
+        /// Width of the image (pixels)
+        width: u32,
+        /// Height of the image (pixels)
+        height: u32,
+        /// Size of the file, readable by humans
+        file_size: String,
+        /// Size of the file
+        file_size_in_bytes: u64,
+        /// Where the image was saved
+        save_path: String,
+    },
+    /// Copied to the clipboard
+    Copy {
+        /// Width of the image (pixels)
+        width: u32,
+        /// Height of the image (pixels)
+        height: u32,
     },
+    /// Uploaded to the internet
+    Upload {
+        /// Width of the image (pixels)
+        width: u32,
+        /// Height of the image (pixels)
+        height: u32,
+        /// Size of the uploaded file, readable by humans
+        file_size: String,
+        /// Size of the uploaded file
+        file_size_in_bytes: u64,
+        /// Link to the image
+        link: &'a str,
+        /// How long until the image expires, as told by the service
+        expires_in: &'a str,
+    },
+}
+
+impl std::fmt::Display for JsonOutput<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_string_pretty(self).map_err(|_| std::fmt::Error)?;
+        writeln!(f, "{json}")
+    }
 }
 
 /// Image action error
@@ -108,6 +238,18 @@ pub enum Error {
     /// Image upload error
     #[error("failed to upload the image: {0}")]
     ImageUpload(String),
+    /// The encoded image is larger than `max-upload-size`, so it was not uploaded
+    #[error(
+        "the image is {}, larger than the `max-upload-size` of {}. Select a smaller region, or raise `max-upload-size`",
+        human_size(.size),
+        human_size(.limit)
+    )]
+    UploadTooLarge {
+        /// Size of the encoded image, in bytes
+        size: u64,
+        /// The `max-upload-size`, in bytes
+        limit: u64,
+    },
     /// Image error
     #[error(transparent)]
     SaveImage(#[from] image::ImageError),
@@ -123,20 +265,38 @@ impl Command {
             Self::CopyToClipboard => crate::Command::ImageUpload(Self::CopyToClipboard),
             Self::SaveScreenshot => crate::Command::ImageUpload(Self::SaveScreenshot),
             Self::UploadScreenshot => crate::Command::ImageUpload(Self::UploadScreenshot),
+            Self::SaveAsPdf => crate::Command::ImageUpload(Self::SaveAsPdf),
+        }
+    }
+
+    /// Short description of the action, shown to the user
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::CopyToClipboard => "Copy screenshot to clipboard",
+            Self::SaveScreenshot => "Save screenshot to a file",
+            Self::UploadScreenshot => "Upload screenshot",
+            Self::SaveAsPdf => "Save screenshot as a PDF",
         }
     }
 
     /// Execute the action
+    ///
+    /// Images larger than `max_upload_size` bytes are not uploaded. `0` allows any size.
+    /// With `skip_duplicates`, the same image as the last one uploaded is not uploaded again
     pub async fn execute(
         self,
         image: DynamicImage,
         region: Rectangle,
+        max_upload_size: u64,
+        skip_duplicates: bool,
     ) -> Result<(Output, ImageData), Error> {
         let image_data = ImageData {
             height: image.height(),
             width: image.width(),
         };
 
+        let _ = ACCEPTED_REGION.set(region);
+
         // NOTE: Not a hard error, so no need to abort the main action
         if let Err(failed_to_write) = crate::last_region::write(region) {
             log::error!(
@@ -151,39 +311,109 @@ impl Command {
                 bytes: std::borrow::Cow::Borrowed(image.as_bytes()),
             })
             .map(|_| (Output::Copied, image_data))?,
-            Self::SaveScreenshot => {
-                let _ = SAVED_IMAGE.set(image);
+            Self::SaveScreenshot | Self::SaveAsPdf => {
+                let _ = SAVED_IMAGE.set(SavedImage {
+                    image,
+                    is_pdf: self == Self::SaveAsPdf,
+                });
                 (Output::Saved, image_data)
             }
-            Self::UploadScreenshot => {
-                let path = tempfile::TempDir::new()?
-                    .into_path()
-                    .join("ferrishot-screenshot.png");
-
-                // TODO: allow configuring the upload format
-                // in-app
-                image.save_with_format(&path, image::ImageFormat::Png)?;
-
-                (
-                    Output::Uploaded {
-                        data: crate::image::upload::upload(&path).await.map_err(|err| {
-                            err.into_iter()
-                                .next()
-                                .map(Error::ImageUpload)
-                                .expect("at least 1 image upload provider")
-                        })?,
-                        file_size: path.metadata().map(|meta| meta.len()).unwrap_or(0),
-                        path,
-                    },
-                    image_data,
+            Self::UploadScreenshot => (
+                upload(
+                    &image,
+                    image::ImageFormat::Png,
+                    max_upload_size,
+                    skip_duplicates,
                 )
-            }
+                .await?,
+                image_data,
+            ),
         };
 
         Ok(out)
     }
 }
 
+/// Encode the `image` in the `format`, and upload it
+///
+/// Nothing is uploaded when the encoded image is larger than `max_upload_size` bytes,
+/// since the services would refuse it after a long upload. `0` allows any size
+///
+/// With `skip_duplicates`, the link of the last upload is used when it was the same
+/// encoded image
+///
+/// # Errors
+///
+/// Could not encode the image, it is too large, or none of the services accepted it
+pub async fn upload(
+    image: &DynamicImage,
+    format: image::ImageFormat,
+    max_upload_size: u64,
+    skip_duplicates: bool,
+) -> Result<Output, Error> {
+    let mut bytes = Vec::new();
+    let mut writer = std::io::Cursor::new(&mut bytes);
+    match format {
+        // JPEG has no alpha channel
+        image::ImageFormat::Jpeg => image.to_rgb8().write_to(&mut writer, format)?,
+        _ => image.write_to(&mut writer, format)?,
+    }
+
+    let file_size = bytes.len() as u64;
+
+    if max_upload_size > 0 && file_size > max_upload_size {
+        return Err(Error::UploadTooLarge {
+            size: file_size,
+            limit: max_upload_size,
+        });
+    }
+
+    let checksum = skip_duplicates.then(|| crate::last_capture::checksum([bytes.as_slice()]));
+
+    if let Some(data) = checksum
+        .as_deref()
+        .and_then(crate::last_capture::previous_upload)
+    {
+        log::info!(
+            "The screenshot is the same as the last one uploaded, so it was not uploaded again"
+        );
+        return Ok(Output::Uploaded {
+            data,
+            file_size,
+            bytes,
+        });
+    }
+
+    // the services upload files, so the image is only written to disk while it is uploaded
+    let extension = format.extensions_str().first().unwrap_or(&"png");
+    let file = crate::temp::write(&format!("screenshot.{extension}"), &bytes)?;
+
+    let uploaded = crate::image::upload::upload(file.path()).await;
+    drop(file);
+
+    let data = uploaded.map_err(|err| {
+        err.into_iter()
+            .next()
+            .map(Error::ImageUpload)
+            .expect("at least 1 image upload provider")
+    })?;
+
+    if let Some(checksum) = checksum {
+        crate::last_capture::remember_upload(checksum, &data);
+    }
+
+    Ok(Output::Uploaded {
+        data,
+        file_size,
+        bytes,
+    })
+}
+
+/// Size in bytes, readable by humans
+fn human_size(bytes: &u64) -> String {
+    human_bytes::human_bytes(*bytes as f64)
+}
+
 /// The image to save to a file, chosen by the user in a file picker.
 ///
 /// Unfortunately, there is simply no way to communicate something from
@@ -217,4 +447,303 @@ impl Command {
 /// having to close this. But this seems to not be possible. Perhaps in the
 /// future there will be some kind of file explorer Iced widget that we
 /// can use instead of the native file explorer.
-pub static SAVED_IMAGE: std::sync::OnceLock<DynamicImage> = std::sync::OnceLock::new();
+pub static SAVED_IMAGE: std::sync::OnceLock<SavedImage> = std::sync::OnceLock::new();
+
+/// An image to save to a file, see [`SAVED_IMAGE`]
+#[derive(Debug)]
+pub struct SavedImage {
+    /// The image
+    pub image: DynamicImage,
+    /// Wrap the image into a PDF, instead of picking the format from the extension of the file
+    pub is_pdf: bool,
+}
+
+impl SavedImage {
+    /// Checksum of the image
+    pub fn checksum(&self) -> String {
+        crate::last_capture::checksum([
+            self.image.width().to_le_bytes().as_slice(),
+            self.image.height().to_le_bytes().as_slice(),
+            self.image.as_bytes(),
+        ])
+    }
+
+    /// Save the image to `path`. PDFs use pages of `page_size`
+    ///
+    /// The color profile at `icc_profile` is embedded into PNG, JPEG and WebP files,
+    /// so they look the same as on the screen. Other formats are saved without it
+    ///
+    /// The image is written to a temporary file next to `path`, which replaces `path`
+    /// once it is complete. If saving fails, a file which was already at `path` is kept
+    ///
+    /// # Errors
+    ///
+    /// Could not read the color profile, or encode or write the image
+    pub fn save(
+        &self,
+        path: &Path,
+        page_size: crate::image::pdf::PageSize,
+        icc_profile: Option<&Path>,
+    ) -> Result<(), Error> {
+        use std::io::Write as _;
+
+        // an unknown extension fails before anything is written
+        let format = if self.is_pdf {
+            None
+        } else {
+            Some(image::ImageFormat::from_path(path)?)
+        };
+
+        let dir = path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let mut temp = tempfile::Builder::new();
+        temp.prefix(".ferrishot-");
+        // the same permissions as a file which is created normally
+        #[cfg(unix)]
+        temp.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o666));
+        let temp = temp.tempfile_in(dir)?;
+
+        let mut file = std::io::BufWriter::new(temp.as_file());
+        match format {
+            Some(format) => self.encode(&mut file, format, icc_profile)?,
+            None => file.write_all(&crate::image::pdf::encode(&self.image, page_size)?)?,
+        }
+        file.flush()?;
+        drop(file);
+
+        temp.persist(path).map_err(|err| err.error)?;
+
+        Ok(())
+    }
+
+    /// Write the image into the `file` as the `format`, embedding the `icc_profile`
+    /// if the format supports it
+    fn encode(
+        &self,
+        file: &mut (impl std::io::Write + std::io::Seek),
+        format: image::ImageFormat,
+        icc_profile: Option<&Path>,
+    ) -> Result<(), Error> {
+        /// Write the `image` with the `encoder`, embedding the `icc_profile`
+        fn encode(
+            image: &DynamicImage,
+            mut encoder: impl image::ImageEncoder,
+            icc_profile: Vec<u8>,
+        ) -> image::ImageResult<()> {
+            encoder
+                .set_icc_profile(icc_profile)
+                .map_err(image::ImageError::Unsupported)?;
+            image.write_with_encoder(encoder)
+        }
+
+        let Some(icc_profile) = icc_profile else {
+            self.image.write_to(file, format)?;
+            return Ok(());
+        };
+
+        let icc_profile = std::fs::read(icc_profile)?;
+
+        match format {
+            image::ImageFormat::Png => encode(
+                &self.image,
+                image::codecs::png::PngEncoder::new(file),
+                icc_profile,
+            )?,
+            // JPEG has no alpha channel
+            image::ImageFormat::Jpeg => encode(
+                &DynamicImage::ImageRgb8(self.image.to_rgb8()),
+                image::codecs::jpeg::JpegEncoder::new(file),
+                icc_profile,
+            )?,
+            image::ImageFormat::WebP => encode(
+                &self.image,
+                image::codecs::webp::WebPEncoder::new_lossless(file),
+                icc_profile,
+            )?,
+            format => {
+                log::warn!("{format:?} files can't embed a color profile, saving without it");
+                self.image.write_to(file, format)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The region which an action was executed on
+///
+/// Like `SAVED_IMAGE`, this is how it gets out of the iced application.
+/// Written to `--write-region-file` at the end of `main`
+pub static ACCEPTED_REGION: std::sync::OnceLock<Rectangle> = std::sync::OnceLock::new();
+
+/// What to do when saving to a path that already exists
+#[derive(ferrishot_knus::DecodeScalar, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SaveConflict {
+    /// Append `-1`, `-2`... to the name of the file, until there is no such file
+    #[default]
+    Rename,
+    /// Replace the existing file
+    Overwrite,
+    /// Ask whether to replace the existing file. Otherwise, rename
+    Ask,
+}
+
+impl SaveConflict {
+    /// The path to actually save the image to, when it should be saved to `path`
+    pub fn resolve(self, path: PathBuf) -> PathBuf {
+        if !path.exists() {
+            return path;
+        }
+
+        match self {
+            Self::Overwrite => path,
+            Self::Rename => unused_path(&path),
+            Self::Ask => {
+                let overwrite = rfd::MessageDialog::new()
+                    .set_title("File already exists")
+                    .set_description(format!(
+                        "{} already exists. Replace it?\n\nOtherwise, the screenshot is saved next to it under a new name",
+                        path.display()
+                    ))
+                    .set_buttons(rfd::MessageButtons::YesNo)
+                    .show()
+                    == rfd::MessageDialogResult::Yes;
+
+                if overwrite { path } else { unused_path(&path) }
+            }
+        }
+    }
+}
+
+/// `path` with `-n` appended to the name of the file, before the extension
+fn numbered_path(path: &Path, n: u32) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+
+    path.with_file_name(match path.extension() {
+        Some(extension) => format!("{stem}-{n}.{}", extension.to_string_lossy()),
+        None => format!("{stem}-{n}"),
+    })
+}
+
+/// The first numbered version of `path` which does not exist
+fn unused_path(path: &Path) -> PathBuf {
+    (1..)
+        .map(|n| numbered_path(path, n))
+        .find(|path| !path.exists())
+        .expect("there is always a number which is not taken yet")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn json_output_is_valid_json() {
+        let upload = JsonOutput::Upload {
+            width: 4,
+            height: 2,
+            file_size: "1 KiB".to_string(),
+            file_size_in_bytes: 1024,
+            link: "https://example.com/\"quoted\"",
+            expires_in: "1 hour",
+        };
+        let json: serde_json::Value = serde_json::from_str(&upload.to_string()).unwrap();
+        assert_eq!(json["type"], "upload");
+        assert_eq!(json["fileSizeInBytes"], 1024);
+        assert_eq!(json["link"], "https://example.com/\"quoted\"");
+
+        let copy: serde_json::Value = serde_json::from_str(
+            &JsonOutput::Copy {
+                width: 4,
+                height: 2,
+            }
+            .to_string(),
+        )
+        .unwrap();
+        assert_eq!(copy["type"], "copy");
+    }
+
+    #[test]
+    fn numbered_path_keeps_extension() {
+        assert_eq!(
+            numbered_path(Path::new("/tmp/shot.png"), 1),
+            PathBuf::from("/tmp/shot-1.png")
+        );
+        assert_eq!(
+            numbered_path(Path::new("shot.tar.gz"), 12),
+            PathBuf::from("shot.tar-12.gz")
+        );
+        assert_eq!(numbered_path(Path::new("shot"), 2), PathBuf::from("shot-2"));
+    }
+
+    #[test]
+    fn upload_larger_than_the_limit() {
+        let image = DynamicImage::new_rgba8(64, 64);
+        let result = tokio::runtime::Runtime::new().unwrap().block_on(upload(
+            &image,
+            image::ImageFormat::Png,
+            10,
+            false,
+        ));
+
+        assert!(matches!(
+            result,
+            Err(Error::UploadTooLarge { size, limit: 10 }) if size > 10
+        ));
+    }
+
+    #[test]
+    fn save_with_icc_profile() {
+        use image::ImageDecoder as _;
+
+        let dir = tempfile::tempdir().unwrap();
+        let profile = dir.path().join("monitor.icc");
+        std::fs::write(&profile, b"not really a color profile").unwrap();
+
+        let saved = SavedImage {
+            image: DynamicImage::new_rgba8(4, 4),
+            is_pdf: false,
+        };
+        let path = dir.path().join("shot.png");
+        saved
+            .save(
+                &path,
+                crate::image::pdf::PageSize::default(),
+                Some(&profile),
+            )
+            .unwrap();
+
+        let mut decoder = image::codecs::png::PngDecoder::new(std::io::BufReader::new(
+            std::fs::File::open(&path).unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(
+            decoder.icc_profile().unwrap(),
+            Some(b"not really a color profile".to_vec())
+        );
+    }
+
+    #[test]
+    fn failed_save_keeps_the_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shot.unknown");
+        std::fs::write(&path, "existing").unwrap();
+
+        let saved = SavedImage {
+            image: DynamicImage::new_rgba8(4, 4),
+            is_pdf: false,
+        };
+        assert!(
+            saved
+                .save(&path, crate::image::pdf::PageSize::default(), None)
+                .is_err()
+        );
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "existing");
+        // no temporary file is left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -4,10 +4,25 @@ pub mod action;
 
 pub mod upload;
 
+pub mod stamp;
+
+pub mod pdf;
+
+pub mod palette;
+
 mod screenshot;
-use std::path::PathBuf;
+pub use screenshot::{
+    CaptureBackend, CaptureBackendKind, MonitorChoice, MonitorInfo,
+    backend_name as capture_backend, display_server, monitor as current_monitor, monitors,
+    set_backend as set_capture_backend, set_monitor, spans_monitors,
+};
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
 
-use image::ImageReader;
+use image::{AnimationDecoder as _, ImageFormat, ImageReader};
 
 mod rgba_handle;
 pub use rgba_handle::RgbaHandle;
@@ -39,7 +54,93 @@ pub fn get_image(file: Option<&PathBuf>) -> Result<RgbaHandle, GetImageError> {
         .map_or_else(
             // no path passed = take image of the monitor
             screenshot::take,
-            |img| RgbaHandle::new(img.width(), img.height(), img.into_rgba8().into_raw()).pipe(Ok),
+            |img| RgbaHandle::from_image(img.into_rgba8()).pipe(Ok),
         )?
         .pipe(Ok)
 }
+
+/// Most frames of an animated image that are decoded. The frames after it are left out
+const MAX_FRAMES: usize = 500;
+
+/// Frames of the GIF or APNG at `path`, up to [`MAX_FRAMES`] of them
+///
+/// `None` when the image is neither. A GIF which is not animated has a single frame,
+/// which can be used as the image instead of decoding it again with [`get_image`]
+pub fn get_frames(path: &Path) -> Result<Option<Vec<RgbaHandle>>, GetImageError> {
+    let open = || File::open(path).map(BufReader::new);
+
+    let frames = match ImageReader::open(path)?.with_guessed_format()?.format() {
+        Some(ImageFormat::Gif) => image::codecs::gif::GifDecoder::new(open()?)?.into_frames(),
+        Some(ImageFormat::Png) => {
+            let decoder = image::codecs::png::PngDecoder::new(open()?)?;
+            if !decoder.is_apng()? {
+                return Ok(None);
+            }
+            decoder.apng()?.into_frames()
+        }
+        _ => return Ok(None),
+    };
+
+    let mut frames = frames
+        // one more than the limit, to know if any frames are left out
+        .take(MAX_FRAMES + 1)
+        .map(|frame| frame.map(|frame| RgbaHandle::from_image(frame.into_buffer())))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if frames.len() > MAX_FRAMES {
+        log::warn!(
+            "{} has more than {MAX_FRAMES} frames, the rest are left out",
+            path.display()
+        );
+        frames.truncate(MAX_FRAMES);
+    }
+
+    Ok((!frames.is_empty()).then_some(frames))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn frames_of_animated_gif() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("animated.gif");
+
+        let mut encoder = image::codecs::gif::GifEncoder::new(File::create(&path).unwrap());
+        encoder
+            .encode_frames([0x00, 0xff].map(|value| {
+                image::Frame::new(image::RgbaImage::from_pixel(
+                    4,
+                    2,
+                    image::Rgba([value, value, value, 0xff]),
+                ))
+            }))
+            .unwrap();
+        drop(encoder);
+
+        let frames = get_frames(&path).unwrap().unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!((frames[1].width(), frames[1].height()), (4, 2));
+        assert_eq!(frames[1].as_image().get_pixel(0, 0).0, [0xff; 4]);
+    }
+
+    #[test]
+    fn still_gif_has_a_single_frame() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("still.gif");
+        image::RgbaImage::new(4, 2).save(&path).unwrap();
+
+        assert_eq!(get_frames(&path).unwrap().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn still_image_has_no_frames() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("still.png");
+        image::RgbaImage::new(4, 2).save(&path).unwrap();
+
+        assert!(get_frames(&path).unwrap().is_none());
+    }
+}
@@ -0,0 +1,99 @@
+//! Find the dominant colors of an image
+//!
+//! Uses popularity bucketing: similar colors share a bucket, and the buckets with
+//! the most pixels win. Each bucket becomes the average of its colors.
+
+use std::collections::HashMap;
+
+use image::RgbaImage;
+
+/// At most this many pixels are looked at, so large selections stay fast
+const MAX_SAMPLES: usize = 100_000;
+
+/// Bits of each channel that are dropped to find the bucket of a color. Colors which
+/// only differ in these bits are counted as the same color
+const BUCKET_SHIFT: u8 = 3;
+
+/// The `count` most common colors of the `image`, most common first
+///
+/// Fully transparent pixels are ignored.
+pub fn dominant_colors(image: &RgbaImage, count: usize) -> Vec<[u8; 3]> {
+    let pixel_count = (image.width() * image.height()) as usize;
+    let step = pixel_count.div_ceil(MAX_SAMPLES).max(1);
+
+    // for each bucket, the number of pixels in it and the sum of their channels
+    let mut buckets = HashMap::<[u8; 3], (u64, [u64; 3])>::new();
+
+    for pixel in image.pixels().step_by(step).filter(|pixel| pixel[3] != 0) {
+        let color = [pixel[0], pixel[1], pixel[2]];
+        let (pixels, sum) = buckets
+            .entry(color.map(|channel| channel >> BUCKET_SHIFT))
+            .or_default();
+
+        *pixels += 1;
+        for (sum, channel) in sum.iter_mut().zip(color) {
+            *sum += u64::from(channel);
+        }
+    }
+
+    let mut buckets = buckets.into_values().collect::<Vec<_>>();
+    // ties are broken by the color, so the order does not depend on the hash map
+    buckets.sort_unstable_by_key(|&(pixels, sum)| (std::cmp::Reverse(pixels), sum));
+
+    buckets
+        .into_iter()
+        .take(count)
+        .map(|(pixels, sum)| sum.map(|channel| (channel / pixels) as u8))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn most_common_color_comes_first() {
+        let image = RgbaImage::from_fn(4, 4, |x, _| {
+            if x == 0 {
+                Rgba([0, 0, 255, 255])
+            } else {
+                Rgba([255, 0, 0, 255])
+            }
+        });
+
+        assert_eq!(dominant_colors(&image, 2), vec![[255, 0, 0], [0, 0, 255]]);
+        // asking for more colors than there are does not invent any
+        assert_eq!(dominant_colors(&image, 5), vec![[255, 0, 0], [0, 0, 255]]);
+    }
+
+    #[test]
+    fn ignores_transparent_pixels() {
+        let image = RgbaImage::from_fn(2, 2, |x, _| {
+            if x == 0 {
+                Rgba([0, 255, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 0])
+            }
+        });
+
+        assert_eq!(dominant_colors(&image, 3), vec![[0, 255, 0]]);
+    }
+
+    #[test]
+    fn similar_colors_are_counted_together() {
+        // 3 pixels of nearly the same gray outnumber 2 pixels of white
+        let image = RgbaImage::from_fn(5, 1, |x, _| match x {
+            0 => Rgba([100, 100, 100, 255]),
+            1 => Rgba([101, 101, 101, 255]),
+            2 => Rgba([102, 102, 102, 255]),
+            _ => Rgba([255, 255, 255, 255]),
+        });
+
+        assert_eq!(
+            dominant_colors(&image, 2),
+            vec![[101, 101, 101], [255, 255, 255]]
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Write an image into a single-page PDF
+//!
+//! The PDF contains nothing but the image, so writing the few objects by hand
+//! is simpler than pulling in a PDF library.
+
+use std::io::Write as _;
+
+use image::DynamicImage;
+
+/// Space between the image and the edges of the page, in points
+const MARGIN: f32 = 36.0;
+
+/// Size of the page of the PDF
+#[derive(ferrishot_knus::DecodeScalar, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PageSize {
+    /// The page is exactly the size of the image, 1 pixel per point
+    #[default]
+    Image,
+    /// A4 paper. The image is centered, and shrunk to fit if it is too large
+    A4,
+    /// US Letter paper. The image is centered, and shrunk to fit if it is too large
+    Letter,
+}
+
+impl PageSize {
+    /// Width and height of the page in points, for an image of the given size
+    fn points(self, image_width: f32, image_height: f32) -> (f32, f32) {
+        match self {
+            Self::Image => (image_width, image_height),
+            Self::A4 => (595.28, 841.89),
+            Self::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+/// Encode the `image` as a PDF with a single page
+pub fn encode(image: &DynamicImage, page_size: PageSize) -> std::io::Result<Vec<u8>> {
+    let rgb = image.to_rgb8();
+    let (width, height) = (rgb.width() as f32, rgb.height() as f32);
+
+    let (page_width, page_height) = page_size.points(width, height);
+
+    // never scale the image up, that would only make it blurry
+    let scale = if page_size == PageSize::Image {
+        1.0
+    } else {
+        ((page_width - MARGIN * 2.0) / width)
+            .min((page_height - MARGIN * 2.0) / height)
+            .min(1.0)
+    };
+    let (image_width, image_height) = (width * scale, height * scale);
+
+    let mut pixels = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    pixels.write_all(rgb.as_raw())?;
+    let pixels = pixels.finish()?;
+
+    // draws the image, placed in the center of the page
+    let contents = format!(
+        "q {image_width} 0 0 {image_height} {} {} cm /Im0 Do Q",
+        (page_width - image_width) / 2.0,
+        (page_height - image_height) / 2.0,
+    );
+
+    let mut pdf = Pdf::default();
+
+    pdf.object(b"<< /Type /Catalog /Pages 2 0 R >>")?;
+    pdf.object(b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>")?;
+    pdf.object(
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {page_width} {page_height}] \
+             /Resources << /XObject << /Im0 4 0 R >> >> /Contents 5 0 R >>"
+        )
+        .as_bytes(),
+    )?;
+    pdf.stream(
+        &format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB \
+             /BitsPerComponent 8 /Filter /FlateDecode",
+            rgb.width(),
+            rgb.height()
+        ),
+        &pixels,
+    )?;
+    pdf.stream("", contents.as_bytes())?;
+
+    pdf.finish()
+}
+
+/// A PDF being written
+#[derive(Default)]
+struct Pdf {
+    /// Contents of the file written so far
+    bytes: Vec<u8>,
+    /// Byte offset of each object, in order of the object numbers starting at 1
+    offsets: Vec<usize>,
+}
+
+impl Pdf {
+    /// Append the next object to the PDF
+    fn object(&mut self, dictionary: &[u8]) -> std::io::Result<()> {
+        self.start_object()?;
+        self.bytes.write_all(dictionary)?;
+        self.bytes.write_all(b"\nendobj\n")
+    }
+
+    /// Append the next object to the PDF, as a stream with the `data`
+    ///
+    /// `entries` are added to the dictionary of the stream, in addition to its length
+    fn stream(&mut self, entries: &str, data: &[u8]) -> std::io::Result<()> {
+        self.start_object()?;
+        write!(
+            self.bytes,
+            "<< {entries} /Length {} >>\nstream\n",
+            data.len()
+        )?;
+        self.bytes.write_all(data)?;
+        self.bytes.write_all(b"\nendstream\nendobj\n")
+    }
+
+    /// Write the start of the next object, remembering where it begins
+    fn start_object(&mut self) -> std::io::Result<()> {
+        if self.bytes.is_empty() {
+            // the comment with binary characters tells tools that the file is binary
+            self.bytes.write_all(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n")?;
+        }
+        self.offsets.push(self.bytes.len());
+        write!(self.bytes, "{} 0 obj\n", self.offsets.len())
+    }
+
+    /// Write the cross-reference table, which lets readers find each object
+    fn finish(mut self) -> std::io::Result<Vec<u8>> {
+        let xref_offset = self.bytes.len();
+
+        write!(
+            self.bytes,
+            "xref\n0 {}\n0000000000 65535 f \n",
+            self.offsets.len() + 1
+        )?;
+        for offset in &self.offsets {
+            write!(self.bytes, "{offset:010} 00000 n \n")?;
+        }
+        write!(
+            self.bytes,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            self.offsets.len() + 1
+        )?;
+
+        Ok(self.bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn offsets_point_at_objects() {
+        let image = DynamicImage::new_rgba8(30, 20);
+        let pdf = encode(&image, PageSize::A4).unwrap();
+
+        let text = String::from_utf8_lossy(&pdf);
+        let xref = text.find("xref\n").unwrap();
+        let table = &text[xref..];
+
+        // skip the `xref` line, the subsection header and the free entry
+        for (number, entry) in table.lines().skip(3).take(5).enumerate() {
+            let offset = entry[..10].parse::<usize>().unwrap();
+            assert!(
+                pdf[offset..].starts_with(format!("{} 0 obj\n", number + 1).as_bytes()),
+                "object {} is not at offset {offset}",
+                number + 1
+            );
+        }
+
+        let startxref = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|rest| rest.lines().next())
+            .unwrap()
+            .parse::<usize>()
+            .unwrap();
+        assert_eq!(startxref, pdf.len() - table.len());
+    }
+}
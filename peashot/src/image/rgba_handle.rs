@@ -5,13 +5,38 @@ use iced::{Rectangle, advanced::image::Bytes, widget::image::Handle};
 /// The `RgbaHandle` is a wrapper for a handle pointing to decoded image pixels in RGBA format.
 ///
 /// This is a more specialized version of `iced::widget::image::Handle`
+///
+/// Screenshots of large or virtual desktops take hundreds of megabytes, so there is only
+/// ever a single copy of the pixels. Both the `Handle` rendered by Iced and the
+/// `image` crate used to process the capture borrow the same shared buffer
 #[derive(Debug, Clone)]
-pub struct RgbaHandle(Handle);
+pub struct RgbaHandle {
+    /// Width of the image
+    width: u32,
+    /// Height of the image
+    height: u32,
+    /// RGBA pixels, shared with the `handle`
+    pixels: Bytes,
+    /// Handle to the `pixels` for rendering
+    handle: Handle,
+}
 
 impl RgbaHandle {
     /// Create handle to an image represented in RGBA format
     pub fn new(width: u32, height: u32, pixels: impl Into<Bytes>) -> Self {
-        Self(Handle::from_rgba(width, height, pixels.into()))
+        let pixels = pixels.into();
+
+        Self {
+            width,
+            height,
+            handle: Handle::from_rgba(width, height, pixels.clone()),
+            pixels,
+        }
+    }
+
+    /// Create handle from an image, taking over its pixels without copying them
+    pub fn from_image(image: image::RgbaImage) -> Self {
+        Self::new(image.width(), image.height(), image.into_raw())
     }
 
     /// Get the bounds of this image
@@ -25,37 +50,29 @@ impl RgbaHandle {
     }
 
     /// Width of the image
-    pub fn width(&self) -> u32 {
-        self.raw().0
+    pub const fn width(&self) -> u32 {
+        self.width
     }
 
     /// Height of the image
-    pub fn height(&self) -> u32 {
-        self.raw().1
+    pub const fn height(&self) -> u32 {
+        self.height
     }
 
     /// RGBA bytes of the image
-    pub fn bytes(&self) -> &Bytes {
-        self.raw().2
+    pub fn bytes(&self) -> &[u8] {
+        &self.pixels
     }
 
-    /// Returns the width, height and RGBA pixels
-    fn raw(&self) -> (u32, u32, &Bytes) {
-        let Handle::Rgba {
-            width,
-            height,
-            ref pixels,
-            ..
-        } = self.0
-        else {
-            unreachable!("handle is guaranteed to be Rgba")
-        };
-        (width, height, pixels)
+    /// Borrow the pixels as an image, without copying them
+    pub fn as_image(&self) -> image::ImageBuffer<image::Rgba<u8>, &[u8]> {
+        image::ImageBuffer::from_raw(self.width, self.height, self.bytes())
+            .expect("handle stores exactly `width * height` RGBA pixels")
     }
 }
 
 impl From<RgbaHandle> for Handle {
     fn from(value: RgbaHandle) -> Self {
-        value.0
+        value.handle
     }
 }
@@ -0,0 +1,52 @@
+//! Draw a test pattern instead of capturing the screen
+//!
+//! Behaves like a single monitor, so ferrishot can be tested where nothing can be captured,
+//! like in CI or over SSH
+
+use super::{CaptureBackend, MonitorInfo, ScreenshotError};
+use crate::image::RgbaHandle;
+
+/// Width of the fake monitor
+const WIDTH: u32 = 1920;
+/// Height of the fake monitor
+const HEIGHT: u32 = 1080;
+/// Size of the squares of the checkerboard
+const SQUARE: u32 = 60;
+
+/// Backend which captures a checkerboard
+#[derive(Debug, Clone, Copy)]
+pub struct Fake;
+
+impl CaptureBackend for Fake {
+    fn name(&self) -> String {
+        "fake".to_string()
+    }
+
+    fn capture(&self, monitor: &MonitorInfo) -> Result<RgbaHandle, ScreenshotError> {
+        let image = image::RgbaImage::from_fn(monitor.width, monitor.height, |x, y| {
+            if (x / SQUARE + y / SQUARE) % 2 == 0 {
+                image::Rgba([0x40, 0x40, 0x40, 0xff])
+            } else {
+                image::Rgba([0xc0, 0xc0, 0xc0, 0xff])
+            }
+        });
+
+        Ok(RgbaHandle::from_image(image))
+    }
+
+    fn monitors(&self) -> Result<Vec<MonitorInfo>, ScreenshotError> {
+        Ok(vec![self.current_monitor()?])
+    }
+
+    fn current_monitor(&self) -> Result<MonitorInfo, ScreenshotError> {
+        Ok(MonitorInfo {
+            name: "fake".to_string(),
+            x: 0,
+            y: 0,
+            width: WIDTH,
+            height: HEIGHT,
+            scale_factor: 1.0,
+            is_primary: true,
+        })
+    }
+}
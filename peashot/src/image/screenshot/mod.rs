@@ -0,0 +1,438 @@
+//! Take screenshot of the current monitor
+//!
+//! Screenshots are taken by a [`CaptureBackend`]. Each backend lives in its own module,
+//! so a fix for one platform does not touch the code shared by all of them.
+//! The backend is picked with `--capture-backend`, and is xcap by default
+//!
+//! Which monitor is captured is picked with `--monitor`. With `all`, each monitor is
+//! captured on its own and the screenshots are stitched into a single image, placed
+//! where the monitors are relative to each other
+
+mod fake;
+mod xcap;
+
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use super::RgbaHandle;
+
+/// Could not retrieve the screenshot
+#[derive(thiserror::Error, Debug)]
+pub enum ScreenshotError {
+    /// The position of the mouse is unavailable
+    #[error("Could not get position of the mouse")]
+    MousePosition,
+    #[error("Could not get the active monitor: {0}")]
+    /// There is no active monitor
+    Monitor(Box<dyn std::error::Error + Send + Sync>),
+    /// Could not capture the screenshot for some reason
+    #[error("Could not take a screenshot: {0}")]
+    Screenshot(Box<dyn std::error::Error + Send + Sync>),
+    /// The monitor passed to `--monitor` does not exist
+    #[error("There is no monitor {monitor}. The monitors are: {available}")]
+    UnknownMonitor {
+        /// Index or name of the monitor
+        monitor: String,
+        /// Names of the monitors which exist
+        available: String,
+    },
+}
+
+/// A way of taking screenshots and getting information about the monitors
+pub trait CaptureBackend: Send + Sync {
+    /// Name of the backend, for diagnostics
+    fn name(&self) -> String;
+    /// Take a screenshot of the `monitor`
+    fn capture(&self, monitor: &MonitorInfo) -> Result<RgbaHandle, ScreenshotError>;
+    /// List all of the monitors
+    fn monitors(&self) -> Result<Vec<MonitorInfo>, ScreenshotError>;
+    /// The monitor under the mouse, from which ferrishot was invoked
+    fn current_monitor(&self) -> Result<MonitorInfo, ScreenshotError>;
+}
+
+/// Which backend takes the screenshots
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaptureBackendKind {
+    /// Capture with xcap, which supports Windows, macOS, X11 and Wayland
+    #[default]
+    Xcap,
+    /// Draw a test pattern instead of capturing the screen, for tests and for
+    /// trying out ferrishot where the screen can't be captured
+    Fake,
+}
+
+impl CaptureBackendKind {
+    /// Implementation of the backend
+    fn backend(self) -> &'static dyn CaptureBackend {
+        match self {
+            Self::Xcap => &xcap::Xcap,
+            Self::Fake => &fake::Fake,
+        }
+    }
+}
+
+/// Backend picked with `--capture-backend`
+static BACKEND: OnceLock<CaptureBackendKind> = OnceLock::new();
+
+/// Take the screenshots with the `backend`. Only the first call has an effect
+pub fn set_backend(backend: CaptureBackendKind) {
+    let _ = BACKEND.set(backend);
+}
+
+/// The backend which takes the screenshots
+fn backend() -> &'static dyn CaptureBackend {
+    BACKEND.get().copied().unwrap_or_default().backend()
+}
+
+/// Which monitors to capture
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MonitorChoice {
+    /// The monitor under the mouse, from which ferrishot was invoked
+    #[default]
+    Current,
+    /// The monitor at this index of the list of monitors, starting from `0`
+    Index(usize),
+    /// The monitor with this name
+    Name(String),
+    /// All of the monitors, stitched into a single image
+    All,
+}
+
+impl FromStr for MonitorChoice {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "current" => Self::Current,
+            "all" => Self::All,
+            _ => s
+                .parse()
+                .map_or_else(|_| Self::Name(s.to_string()), Self::Index),
+        })
+    }
+}
+
+/// Monitor picked with `--monitor`
+static MONITOR: OnceLock<MonitorChoice> = OnceLock::new();
+
+/// Capture the `monitor`. Only the first call has an effect
+pub fn set_monitor(monitor: MonitorChoice) {
+    let _ = MONITOR.set(monitor);
+}
+
+/// Whether the screenshot contains more than a single monitor
+pub fn spans_monitors() -> bool {
+    MONITOR.get() == Some(&MonitorChoice::All)
+}
+
+/// The monitors picked with `--monitor`
+fn chosen_monitors() -> Result<Vec<MonitorInfo>, ScreenshotError> {
+    let choice = MONITOR.get().cloned().unwrap_or_default();
+
+    let unknown = |monitors: &[MonitorInfo], monitor: String| ScreenshotError::UnknownMonitor {
+        monitor,
+        available: monitors
+            .iter()
+            .enumerate()
+            .map(|(index, monitor)| format!("{index} ({})", monitor.name))
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+
+    match choice {
+        MonitorChoice::Current => Ok(vec![backend().current_monitor()?]),
+        MonitorChoice::All => monitors(),
+        MonitorChoice::Index(index) => {
+            let mut monitors = monitors()?;
+            if index < monitors.len() {
+                Ok(vec![monitors.swap_remove(index)])
+            } else {
+                Err(unknown(&monitors, index.to_string()))
+            }
+        }
+        MonitorChoice::Name(name) => {
+            let mut monitors = monitors()?;
+            match monitors.iter().position(|monitor| monitor.name == name) {
+                Some(index) => Ok(vec![monitors.swap_remove(index)]),
+                None => Err(unknown(&monitors, name)),
+            }
+        }
+    }
+}
+
+/// Take a screenshot and return a handle to the image
+pub fn take() -> Result<RgbaHandle, ScreenshotError> {
+    let monitors = chosen_monitors()?;
+
+    if let [monitor] = monitors.as_slice() {
+        return backend().capture(monitor);
+    }
+
+    let screenshots = monitors
+        .iter()
+        .map(|monitor| backend().capture(monitor).map(|image| (monitor, image)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // on macOS, monitors are placed in logical points, but screenshots are in pixels
+    let scale_factor = if cfg!(target_os = "macos") {
+        bounding_monitor(&monitors).scale_factor
+    } else {
+        1.0
+    };
+
+    Ok(stitch(&screenshots, scale_factor))
+}
+
+/// Area covering all of the `monitors`
+fn bounding_monitor(monitors: &[MonitorInfo]) -> MonitorInfo {
+    let left = monitors.iter().map(|monitor| monitor.x).min().unwrap_or(0);
+    let top = monitors.iter().map(|monitor| monitor.y).min().unwrap_or(0);
+    let right = monitors
+        .iter()
+        .map(|monitor| monitor.x + monitor.width as i32)
+        .max()
+        .unwrap_or(0);
+    let bottom = monitors
+        .iter()
+        .map(|monitor| monitor.y + monitor.height as i32)
+        .max()
+        .unwrap_or(0);
+
+    // the window spanning the monitors can only have a single scale factor
+    let scale_factor = monitors
+        .iter()
+        .find(|monitor| monitor.is_primary)
+        .or(monitors.first())
+        .map_or(1.0, |monitor| monitor.scale_factor);
+
+    MonitorInfo {
+        name: monitors
+            .iter()
+            .map(|monitor| monitor.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" + "),
+        x: left,
+        y: top,
+        width: (right - left).max(0) as u32,
+        height: (bottom - top).max(0) as u32,
+        scale_factor,
+        is_primary: monitors.iter().any(|monitor| monitor.is_primary),
+    }
+}
+
+/// Place the screenshot of each monitor where the monitor is, in a single image
+///
+/// Positions of the monitors are multiplied by the `scale_factor` to get the position
+/// in the image. Parts of the image which are not covered by any of the monitors are black
+fn stitch(screenshots: &[(&MonitorInfo, RgbaHandle)], scale_factor: f32) -> RgbaHandle {
+    let left = screenshots
+        .iter()
+        .map(|(monitor, _)| monitor.x)
+        .min()
+        .unwrap_or(0);
+    let top = screenshots
+        .iter()
+        .map(|(monitor, _)| monitor.y)
+        .min()
+        .unwrap_or(0);
+
+    // the screenshot could be larger than the monitor, if the monitor is scaled
+    let placed = screenshots
+        .iter()
+        .map(|(monitor, image)| {
+            let position = |offset: i32| (offset as f32 * scale_factor).round() as u32;
+            (position(monitor.x - left), position(monitor.y - top), image)
+        })
+        .collect::<Vec<_>>();
+
+    let width = placed
+        .iter()
+        .map(|(x, _, image)| x + image.width())
+        .max()
+        .unwrap_or(0);
+    let height = placed
+        .iter()
+        .map(|(_, y, image)| y + image.height())
+        .max()
+        .unwrap_or(0);
+
+    let mut stitched = image::RgbaImage::from_pixel(width, height, image::Rgba([0, 0, 0, 0xff]));
+    for (x, y, image) in placed {
+        image::imageops::replace(&mut stitched, &image.as_image(), x.into(), y.into());
+    }
+
+    RgbaHandle::from_image(stitched)
+}
+
+/// Information about a monitor, for diagnostics
+#[derive(serde::Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    /// Name of the monitor
+    pub name: String,
+    /// X coordinate of the top-left corner
+    pub x: i32,
+    /// Y coordinate of the top-left corner
+    pub y: i32,
+    /// Width in pixels
+    pub width: u32,
+    /// Height in pixels
+    pub height: u32,
+    /// How much the contents of the monitor are scaled
+    pub scale_factor: f32,
+    /// Whether this is the primary monitor
+    pub is_primary: bool,
+}
+
+/// List all of the monitors
+pub fn monitors() -> Result<Vec<MonitorInfo>, ScreenshotError> {
+    backend().monitors()
+}
+
+/// Information about the monitor which is captured. When multiple monitors are
+/// captured, it covers all of them
+pub fn monitor() -> Result<MonitorInfo, ScreenshotError> {
+    let monitors = chosen_monitors()?;
+
+    Ok(match monitors.as_slice() {
+        [monitor] => monitor.clone(),
+        monitors => bounding_monitor(monitors),
+    })
+}
+
+/// The display server that ferrishot is running under
+pub fn display_server() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "quartz"
+    } else if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        "wayland"
+    } else if std::env::var_os("DISPLAY").is_some() {
+        "x11"
+    } else {
+        "unknown"
+    }
+}
+
+/// Which backend is used to take screenshots
+pub fn backend_name() -> String {
+    backend().name()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn fake_backend() {
+        let backend = CaptureBackendKind::Fake.backend();
+
+        let monitor = backend.current_monitor().unwrap();
+        let image = backend.capture(&monitor).unwrap();
+
+        assert_eq!(
+            (image.width(), image.height()),
+            (monitor.width, monitor.height)
+        );
+        assert_eq!(backend.monitors().unwrap().len(), 1);
+        assert_eq!(backend.name(), "fake");
+    }
+
+    #[test]
+    fn monitor_choice() {
+        assert_eq!("all".parse(), Ok(MonitorChoice::All));
+        assert_eq!("1".parse(), Ok(MonitorChoice::Index(1)));
+        assert_eq!(
+            "HDMI-1".parse(),
+            Ok(MonitorChoice::Name("HDMI-1".to_string()))
+        );
+    }
+
+    fn monitor(name: &str, x: i32, y: i32, width: u32, height: u32) -> MonitorInfo {
+        MonitorInfo {
+            name: name.to_string(),
+            x,
+            y,
+            width,
+            height,
+            scale_factor: 1.0,
+            is_primary: x == 0 && y == 0,
+        }
+    }
+
+    #[test]
+    fn bounding_monitor_covers_all_monitors() {
+        let monitors = [
+            monitor("left", -100, 20, 100, 50),
+            monitor("right", 0, 0, 200, 100),
+        ];
+
+        assert_eq!(
+            bounding_monitor(&monitors),
+            MonitorInfo {
+                name: "left + right".to_string(),
+                is_primary: true,
+                ..monitor("", -100, 0, 300, 100)
+            }
+        );
+    }
+
+    #[test]
+    fn stitched_monitors_keep_their_positions() {
+        let white = image::Rgba([0xff, 0xff, 0xff, 0xff]);
+        let red = image::Rgba([0xff, 0x00, 0x00, 0xff]);
+
+        let left = monitor("left", -10, 5, 10, 5);
+        let right = monitor("right", 0, 0, 20, 10);
+        let stitched = stitch(
+            &[
+                (
+                    &left,
+                    RgbaHandle::from_image(image::RgbaImage::from_pixel(10, 5, white)),
+                ),
+                (
+                    &right,
+                    RgbaHandle::from_image(image::RgbaImage::from_pixel(20, 10, red)),
+                ),
+            ],
+            1.0,
+        );
+        let stitched = stitched.as_image();
+
+        assert_eq!(stitched.dimensions(), (30, 10));
+        assert_eq!(*stitched.get_pixel(0, 5), white);
+        assert_eq!(*stitched.get_pixel(10, 0), red);
+        // not covered by any monitor
+        assert_eq!(*stitched.get_pixel(0, 0), image::Rgba([0, 0, 0, 0xff]));
+    }
+
+    #[test]
+    fn stitched_monitors_are_placed_in_pixels() {
+        let white = image::Rgba([0xff, 0xff, 0xff, 0xff]);
+        let red = image::Rgba([0xff, 0x00, 0x00, 0xff]);
+
+        // positions in logical points, screenshots in pixels
+        let left = monitor("left", 0, 0, 10, 5);
+        let right = monitor("right", 10, 0, 10, 5);
+        let stitched = stitch(
+            &[
+                (
+                    &left,
+                    RgbaHandle::from_image(image::RgbaImage::from_pixel(20, 10, white)),
+                ),
+                (
+                    &right,
+                    RgbaHandle::from_image(image::RgbaImage::from_pixel(20, 10, red)),
+                ),
+            ],
+            2.0,
+        );
+        let stitched = stitched.as_image();
+
+        assert_eq!(stitched.dimensions(), (40, 10));
+        assert_eq!(*stitched.get_pixel(19, 9), white);
+        assert_eq!(*stitched.get_pixel(20, 0), red);
+    }
+}
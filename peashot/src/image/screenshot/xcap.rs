@@ -0,0 +1,59 @@
+//! Capture the screen with xcap, which supports Windows, macOS, X11 and Wayland
+
+use super::{CaptureBackend, MonitorInfo, ScreenshotError};
+use crate::image::RgbaHandle;
+
+/// Backend using [`xcap`](::xcap)
+#[derive(Debug, Clone, Copy)]
+pub struct Xcap;
+
+impl Xcap {
+    /// The monitor under the mouse
+    fn monitor() -> Result<::xcap::Monitor, ScreenshotError> {
+        let mouse_position::mouse_position::Mouse::Position { x, y } =
+            mouse_position::mouse_position::Mouse::get_mouse_position()
+        else {
+            return Err(ScreenshotError::MousePosition);
+        };
+
+        ::xcap::Monitor::from_point(x, y).map_err(|err| ScreenshotError::Monitor(err.into()))
+    }
+
+    /// Get information about the `monitor`
+    fn monitor_info(monitor: &::xcap::Monitor) -> Result<MonitorInfo, ::xcap::XCapError> {
+        Ok(MonitorInfo {
+            name: monitor.name()?,
+            x: monitor.x()?,
+            y: monitor.y()?,
+            width: monitor.width()?,
+            height: monitor.height()?,
+            scale_factor: monitor.scale_factor()?,
+            is_primary: monitor.is_primary()?,
+        })
+    }
+}
+
+impl CaptureBackend for Xcap {
+    fn name(&self) -> String {
+        format!("xcap ({})", super::display_server())
+    }
+
+    fn capture(&self, monitor: &MonitorInfo) -> Result<RgbaHandle, ScreenshotError> {
+        let screenshot = ::xcap::Monitor::from_point(monitor.x, monitor.y)
+            .map_err(|err| ScreenshotError::Monitor(err.into()))?
+            .capture_image()
+            .map_err(|err| ScreenshotError::Screenshot(err.into()))?;
+
+        Ok(RgbaHandle::from_image(screenshot))
+    }
+
+    fn monitors(&self) -> Result<Vec<MonitorInfo>, ScreenshotError> {
+        ::xcap::Monitor::all()
+            .and_then(|monitors| monitors.iter().map(Self::monitor_info).collect())
+            .map_err(|err| ScreenshotError::Monitor(err.into()))
+    }
+
+    fn current_monitor(&self) -> Result<MonitorInfo, ScreenshotError> {
+        Self::monitor_info(&Self::monitor()?).map_err(|err| ScreenshotError::Monitor(err.into()))
+    }
+}
@@ -0,0 +1,292 @@
+//! Stamp system information onto the captured image
+//!
+//! Useful for bug reports and compliance workflows, where it matters *where* and
+//! *when* a screenshot was taken.
+//!
+//! ```kdl
+//! stamp "{hostname} {date} {time}" enabled=#true corner=bottom-right scale=2
+//! ```
+//!
+//! The text is rendered with a tiny built-in 5x7 bitmap font, so that the
+//! output does not depend on which fonts are installed on the system.
+
+use iced::Task;
+use image::RgbaImage;
+
+crate::declare_commands! {
+    enum Command {
+        /// Toggle whether the stamp will be added to the next capture
+        ToggleStamp,
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
+        match self {
+            Self::ToggleStamp => {
+                app.is_stamp_enabled = !app.is_stamp_enabled;
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// Width of a single glyph, in pixels (before scaling)
+pub const GLYPH_WIDTH: u32 = 5;
+/// Height of a single glyph, in pixels (before scaling)
+pub const GLYPH_HEIGHT: u32 = 7;
+/// Empty space between glyphs, and around the text
+pub const GLYPH_SPACING: u32 = 1;
+
+/// Corner of the image in which to place the stamp
+#[derive(ferrishot_knus::DecodeScalar, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StampCorner {
+    /// Top-left corner
+    TopLeft,
+    /// Top-right corner
+    TopRight,
+    /// Bottom-left corner
+    BottomLeft,
+    /// Bottom-right corner
+    BottomRight,
+}
+
+/// The `stamp` node in the config
+#[derive(ferrishot_knus::Decode, Debug, Clone)]
+pub struct Stamp {
+    /// Text of the stamp. Supports the following placeholders:
+    ///
+    /// - `{hostname}`: Name of this computer
+    /// - `{date}`: Current date, e.g. `2025-05-17`
+    /// - `{time}`: Current time, e.g. `14:03:59`
+    /// - `{version}`: Version of ferrishot
+    #[ferrishot_knus(argument)]
+    pub template: String,
+    /// Add the stamp to captures by default
+    #[ferrishot_knus(default, property)]
+    pub enabled: bool,
+    /// Where to place the stamp
+    #[ferrishot_knus(default = StampCorner::BottomRight, property)]
+    pub corner: StampCorner,
+    /// Each pixel of the font is rendered as a `scale` x `scale` square
+    #[ferrishot_knus(default = 2, property)]
+    pub scale: u32,
+}
+
+impl Stamp {
+    /// Text of the stamp, with all placeholders replaced by their values
+    pub fn text(&self) -> String {
+        let now = chrono::Local::now();
+
+        expand(
+            &self.template,
+            &[
+                ("hostname", hostname()),
+                ("date", now.format("%Y-%m-%d").to_string()),
+                ("time", now.format("%H:%M:%S").to_string()),
+                ("version", env!("CARGO_PKG_VERSION").to_string()),
+            ],
+        )
+    }
+
+    /// Draw the stamp on top of the `image`
+    pub fn draw(&self, image: &mut RgbaImage, theme: &crate::Theme) {
+        let text = self.text();
+        let scale = self.scale.max(1);
+
+        let chars = text.chars().count() as u32;
+        let box_width = (chars * (GLYPH_WIDTH + GLYPH_SPACING) + GLYPH_SPACING) * scale;
+        let box_height = (GLYPH_HEIGHT + GLYPH_SPACING * 2) * scale;
+
+        let (box_x, box_y) = match self.corner {
+            StampCorner::TopLeft => (0, 0),
+            StampCorner::TopRight => (image.width().saturating_sub(box_width), 0),
+            StampCorner::BottomLeft => (0, image.height().saturating_sub(box_height)),
+            StampCorner::BottomRight => (
+                image.width().saturating_sub(box_width),
+                image.height().saturating_sub(box_height),
+            ),
+        };
+
+        for y in box_y..(box_y + box_height).min(image.height()) {
+            for x in box_x..(box_x + box_width).min(image.width()) {
+                blend(image.get_pixel_mut(x, y), theme.stamp_bg);
+            }
+        }
+
+        for (x, y) in text_pixels(&text, scale) {
+            let x = box_x + GLYPH_SPACING * scale + x;
+            let y = box_y + GLYPH_SPACING * scale + y;
+
+            for y in y..(y + scale).min(image.height()) {
+                for x in x..(x + scale).min(image.width()) {
+                    blend(image.get_pixel_mut(x, y), theme.stamp_fg);
+                }
+            }
+        }
+    }
+}
+
+/// Top-left corners of the `scale` x `scale` squares which make up the `text`, when it
+/// is rendered with the bitmap font. Relative to the top-left corner of the text
+pub fn text_pixels(text: &str, scale: u32) -> impl Iterator<Item = (u32, u32)> + '_ {
+    text.chars().enumerate().flat_map(move |(i, ch)| {
+        let glyph_x = i as u32 * (GLYPH_WIDTH + GLYPH_SPACING);
+
+        glyph(ch)
+            .into_iter()
+            .enumerate()
+            .flat_map(move |(row, bits)| {
+                (0..GLYPH_WIDTH)
+                    .filter(move |col| bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0)
+                    .map(move |col| ((glyph_x + col) * scale, row as u32 * scale))
+            })
+    })
+}
+
+/// Replace every `{name}` in the `template` with its value
+fn expand(template: &str, vars: &[(&str, String)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), value)
+        })
+}
+
+/// Name of this computer, or `unknown` if it could not be determined
+fn hostname() -> String {
+    std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|hostname| hostname.trim().to_string())
+        .filter(|hostname| !hostname.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Paint `color` on top of the `pixel`, respecting the color's opacity
+pub fn blend(pixel: &mut image::Rgba<u8>, color: iced::Color) {
+    let [r, g, b, a] = color.into_rgba8();
+    let alpha = f32::from(a) / 255.0;
+
+    for (channel, value) in pixel.0.iter_mut().zip([r, g, b]) {
+        *channel = (f32::from(value) * alpha + f32::from(*channel) * (1.0 - alpha)) as u8;
+    }
+}
+
+/// Rows of the 5x7 glyph for the character. The 5 lowest bits of each row are
+/// the pixels, left to right.
+///
+/// Lowercase letters are rendered as uppercase, and characters which do not have a glyph
+/// are rendered as `?`
+#[rustfmt::skip]
+const fn glyph(ch: char) -> [u8; 7] {
+    match ch.to_ascii_uppercase() {
+        ' ' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        '.' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100],
+        ',' => [0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b00100, 0b01000],
+        ':' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b01100, 0b00000],
+        ';' => [0b00000, 0b01100, 0b01100, 0b00000, 0b01100, 0b00100, 0b01000],
+        '-' => [0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000],
+        '_' => [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111],
+        '+' => [0b00000, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0b00000],
+        '=' => [0b00000, 0b00000, 0b11111, 0b00000, 0b11111, 0b00000, 0b00000],
+        '*' => [0b00000, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0b00000],
+        '/' => [0b00000, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b00000],
+        '\\' => [0b00000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00000],
+        '|' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        '(' => [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010],
+        ')' => [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000],
+        '[' => [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110],
+        ']' => [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110],
+        '<' => [0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010],
+        '>' => [0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000],
+        '@' => [0b01110, 0b10001, 0b00001, 0b01101, 0b10101, 0b10101, 0b01110],
+        '#' => [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010],
+        '%' => [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011],
+        '&' => [0b01100, 0b10010, 0b10100, 0b01000, 0b10101, 0b10010, 0b01101],
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00000, 0b00100],
+        '\'' => [0b01100, 0b00100, 0b01000, 0b00000, 0b00000, 0b00000, 0b00000],
+        '"' => [0b01010, 0b01010, 0b01010, 0b00000, 0b00000, 0b00000, 0b00000],
+        _ => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b00000, 0b00100],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn expand_placeholders() {
+        let vars = [
+            ("hostname", "laptop".to_string()),
+            ("version", "0.3.0".to_string()),
+        ];
+
+        assert_eq!(
+            expand("{hostname} - ferrishot v{version}", &vars),
+            "laptop - ferrishot v0.3.0"
+        );
+        assert_eq!(expand("{hostname}{hostname}", &vars), "laptoplaptop");
+        assert_eq!(expand("{unknown} {version}", &vars), "{unknown} 0.3.0");
+        assert_eq!(expand("no placeholders", &vars), "no placeholders");
+    }
+
+    #[test]
+    fn lowercase_is_uppercase() {
+        assert_eq!(glyph('a'), glyph('A'));
+        assert_eq!(glyph('z'), glyph('Z'));
+    }
+
+    #[test]
+    fn unknown_glyph_is_question_mark() {
+        assert_eq!(glyph('€'), glyph('?'));
+        assert_ne!(glyph('?'), glyph(' '));
+    }
+
+    #[test]
+    fn text_pixels_are_scaled_and_placed_after_each_other() {
+        // the top row of `1` only has the middle pixel
+        assert_eq!(text_pixels("1", 2).next(), Some((4, 0)));
+        // the space before it moves it by the width of a glyph and the spacing
+        assert_eq!(text_pixels(" 1", 1).next(), Some((8, 0)));
+        assert_eq!(text_pixels(" ", 1).next(), None);
+    }
+}
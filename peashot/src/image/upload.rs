@@ -109,6 +109,7 @@ pub async fn upload(file_path: &Path) -> Result<ImageUploaded, Vec<String>> {
     DecodeScalar,
     strum::EnumIter,
     strum::EnumCount,
+    clap::ValueEnum,
 )]
 #[serde(rename_all = "kebab-case")]
 /// Choose which image upload service should be used by default when pressing "Upload Online"
@@ -152,7 +153,7 @@ pub enum Error {
 
 impl ImageUploadService {
     /// Conservative estimate for how long until images expire
-    fn expires_in(self) -> &'static str {
+    pub(crate) fn expires_in(self) -> &'static str {
         match self {
             Self::Litterbox => "3 days",
             Self::Catbox => "2 weeks",
@@ -171,6 +172,18 @@ impl ImageUploadService {
         }
     }
 
+    /// Check whether the upload service can be reached. Any response
+    /// from the server counts, even if it is an error status
+    pub async fn check_connectivity(self) -> Result<(), Error> {
+        HTTP_CLIENT
+            .head(self.post_url())
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
     /// Upload the image to the given upload service
     pub async fn upload_image(self, file_path: &Path) -> Result<ImageUploaded, Error> {
         let request = HTTP_CLIENT
@@ -0,0 +1,278 @@
+//! Remember the last saved or uploaded capture, to skip it when the next one is the same
+//!
+//! Scripts which take a screenshot every few seconds would otherwise save or upload the
+//! same image over and over. With `skip-duplicate-captures`, the path or link of the
+//! previous capture is used instead, when the new image has the same checksum
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use etcetera::BaseStrategy as _;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator as _;
+
+use crate::image::upload::{ImageUploadService, ImageUploaded};
+
+/// Could not remember the last capture
+#[derive(thiserror::Error, miette::Diagnostic, Debug)]
+pub enum Error {
+    /// Can't find home dir
+    #[error(transparent)]
+    HomeDir(#[from] etcetera::HomeDirError),
+    /// Failed to write the last capture file
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// Failed to serialize the last capture
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Name of the file with the last capture
+pub const LAST_CAPTURE_FILENAME: &str = "ferrishot-last-capture.json";
+
+/// Links are only used again for this long, as it is the shortest time that any of
+/// the services keeps the images for
+const MAX_UPLOAD_AGE: Duration = Duration::from_secs(3 * 60 * 60);
+
+/// What happened to the last capture
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+enum Output {
+    /// Saved to a file
+    Save {
+        /// Where the image was saved
+        path: PathBuf,
+        /// Whether the image was wrapped into a PDF
+        is_pdf: bool,
+    },
+    /// Uploaded to the internet
+    Upload {
+        /// Link to the image
+        link: String,
+        /// How long until the image expires, as told by the service
+        expires_in: String,
+        /// When the image was uploaded, in seconds since the Unix epoch
+        uploaded_at: u64,
+    },
+}
+
+/// The last saved or uploaded capture
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct LastCapture {
+    /// Checksum of the image
+    checksum: String,
+    /// What happened to the image
+    #[serde(flatten)]
+    output: Output,
+}
+
+/// Checksum of the `parts`, which together make up an image. Uses 64-bit FNV-1a,
+/// which is the same on every platform and for every version of ferrishot
+pub fn checksum<'a>(parts: impl IntoIterator<Item = &'a [u8]>) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let hash = parts
+        .into_iter()
+        .flatten()
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+        });
+
+    format!("{hash:016x}")
+}
+
+/// Seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Path to the file with the last capture
+fn path() -> Result<PathBuf, Error> {
+    Ok(etcetera::choose_base_strategy()?
+        .cache_dir()
+        .join(LAST_CAPTURE_FILENAME))
+}
+
+/// Read the last capture, if there is one
+fn read() -> Option<LastCapture> {
+    let contents = fs::read_to_string(path().ok()?).ok()?;
+
+    serde_json::from_str(&contents)
+        .inspect_err(|err| log::warn!("Failed to read the last capture: {err}"))
+        .ok()
+}
+
+/// Remember the `last` capture
+fn write(last: &LastCapture) {
+    let written = path().and_then(|path| Ok(fs::write(path, serde_json::to_string(last)?)?));
+
+    if let Err(err) = written {
+        log::error!("Failed to remember the last capture: {err}");
+    }
+}
+
+/// Where the last capture was saved, if it has the `checksum` and the file still exists
+pub fn previous_save(checksum: &str, is_pdf: bool) -> Option<PathBuf> {
+    read().and_then(|last| saved(last, checksum, is_pdf))
+}
+
+/// Where the `last` capture was saved, if it has the `checksum`
+fn saved(last: LastCapture, checksum: &str, is_pdf: bool) -> Option<PathBuf> {
+    match last.output {
+        Output::Save {
+            path,
+            is_pdf: was_pdf,
+        } if last.checksum == checksum && was_pdf == is_pdf && path.exists() => Some(path),
+        _ => None,
+    }
+}
+
+/// Remember that the image with the `checksum` was saved to the `path`
+pub fn remember_save(checksum: String, path: &Path, is_pdf: bool) {
+    write(&LastCapture {
+        checksum,
+        output: Output::Save {
+            path: path.to_path_buf(),
+            is_pdf,
+        },
+    });
+}
+
+/// The last capture, if it has the `checksum` and was uploaded recently enough that
+/// it still exists
+pub fn previous_upload(checksum: &str) -> Option<ImageUploaded> {
+    read().and_then(|last| uploaded(last, checksum, now()))
+}
+
+/// The `last` capture if it has the `checksum`, and it is still uploaded at `now`
+fn uploaded(last: LastCapture, checksum: &str, now: u64) -> Option<ImageUploaded> {
+    let Output::Upload {
+        link,
+        expires_in,
+        uploaded_at,
+    } = last.output
+    else {
+        return None;
+    };
+
+    let is_uploaded = now.saturating_sub(uploaded_at) < MAX_UPLOAD_AGE.as_secs();
+    if last.checksum != checksum || !is_uploaded {
+        return None;
+    }
+
+    Some(ImageUploaded {
+        link,
+        expires_in: ImageUploadService::iter()
+            .map(ImageUploadService::expires_in)
+            .find(|service_expires_in| *service_expires_in == expires_in)?,
+    })
+}
+
+/// Remember that the image with the `checksum` was `uploaded`
+pub fn remember_upload(checksum: String, uploaded: &ImageUploaded) {
+    write(&LastCapture {
+        checksum,
+        output: Output::Upload {
+            link: uploaded.link.clone(),
+            expires_in: uploaded.expires_in.to_string(),
+            uploaded_at: now(),
+        },
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn upload(checksum: &str, uploaded_at: u64) -> LastCapture {
+        LastCapture {
+            checksum: checksum.to_string(),
+            output: Output::Upload {
+                link: "https://example.com/image.png".to_string(),
+                expires_in: "3 hours".to_string(),
+                uploaded_at,
+            },
+        }
+    }
+
+    #[test]
+    fn checksum_of_the_whole_image() {
+        assert_eq!(checksum([b"".as_slice()]), "cbf29ce484222325");
+        assert_eq!(checksum([b"a".as_slice()]), "af63dc4c8601ec8c");
+        // split differently, same image
+        assert_eq!(
+            checksum([b"ab".as_slice(), b"c".as_slice()]),
+            checksum([b"a".as_slice(), b"bc".as_slice()])
+        );
+    }
+
+    #[test]
+    fn same_upload_is_used_again() {
+        let uploaded = uploaded(upload("abc", 1_000), "abc", 1_060).unwrap();
+
+        assert_eq!(uploaded.link, "https://example.com/image.png");
+        assert_eq!(uploaded.expires_in, "3 hours");
+    }
+
+    #[test]
+    fn different_or_expired_upload_is_not_used_again() {
+        assert!(uploaded(upload("abc", 1_000), "def", 1_060).is_none());
+        assert!(
+            uploaded(
+                upload("abc", 1_000),
+                "abc",
+                1_000 + MAX_UPLOAD_AGE.as_secs()
+            )
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn last_capture_round_trip() {
+        let last = LastCapture {
+            checksum: "abc".to_string(),
+            output: Output::Save {
+                path: PathBuf::from("/tmp/screenshot.png"),
+                is_pdf: false,
+            },
+        };
+
+        let json = serde_json::to_string(&last).unwrap();
+        assert_eq!(
+            json,
+            r#"{"checksum":"abc","type":"save","path":"/tmp/screenshot.png","isPdf":false}"#
+        );
+        assert_eq!(serde_json::from_str::<LastCapture>(&json).unwrap(), last);
+    }
+
+    #[test]
+    fn saved_as_a_different_format_is_not_used_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("screenshot.png");
+        fs::write(&path, "").unwrap();
+        let last = || LastCapture {
+            checksum: "abc".to_string(),
+            output: Output::Save {
+                path: path.clone(),
+                is_pdf: false,
+            },
+        };
+
+        assert_eq!(saved(last(), "abc", false), Some(path.clone()));
+        assert_eq!(saved(last(), "abc", true), None);
+
+        // the file was deleted since
+        fs::remove_file(&path).unwrap();
+        assert_eq!(saved(last(), "abc", false), None);
+    }
+}
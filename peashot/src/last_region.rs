@@ -1,11 +1,13 @@
 //! Read and write the last region of a rectangle
+//!
+//! The file keeps a few of the most recently used regions, one per line, newest first
 use crate::{
     geometry::RectangleExt as _,
     lazy_rect::{LazyRectangle, ParseRectError},
 };
 use etcetera::BaseStrategy as _;
 use iced::Rectangle;
-use std::{fs, io::Write as _, str::FromStr as _};
+use std::{fs, io::Write as _, path::Path, str::FromStr as _};
 use tap::Pipe as _;
 
 /// Could not get the last region
@@ -24,34 +26,139 @@ pub enum Error {
 /// Name of the file used to read the last region
 pub const LAST_REGION_FILENAME: &str = "ferrishot-last-region.txt";
 
+/// How many of the recently used regions are remembered
+pub const HISTORY_LEN: usize = 8;
+
 /// Read the last region used
 pub fn read(image_bounds: Rectangle) -> Result<Option<Rectangle>, Error> {
     etcetera::choose_base_strategy()?
         .cache_dir()
         .join(LAST_REGION_FILENAME)
         .pipe(fs::read_to_string)?
-        .pipe_deref(LazyRectangle::from_str)?
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .pipe(LazyRectangle::from_str)?
         .pipe(|lazy_rect| lazy_rect.init(image_bounds))
         .pipe(Some)
         .pipe(Ok)
 }
 
-/// Write the last used region
-pub(crate) fn write(region: Rectangle) -> Result<(), Error> {
+/// Read the recently used regions, newest first
+pub fn history(image_bounds: Rectangle) -> Result<Vec<Rectangle>, Error> {
     etcetera::choose_base_strategy()?
         .cache_dir()
         .join(LAST_REGION_FILENAME)
-        .pipe(fs::File::create)?
-        .write_all(region.as_str().as_bytes())?
+        .pipe(fs::read_to_string)?
+        .pipe(|history| parse_history(&history, image_bounds))
         .pipe(Ok)
 }
 
-#[cfg(not(target_os = "linux"))]
+/// Parse each line of the `history` as a region, skipping the malformed ones
+fn parse_history(history: &str, image_bounds: Rectangle) -> Vec<Rectangle> {
+    history
+        .lines()
+        .filter_map(|line| {
+            LazyRectangle::from_str(line)
+                .inspect_err(|err| log::warn!("Skipping malformed region {line:?}: {err}"))
+                .ok()
+        })
+        .map(|lazy_rect| lazy_rect.init(image_bounds))
+        .collect()
+}
+
+/// Write the last used region
+pub(crate) fn write(region: Rectangle) -> Result<(), Error> {
+    let path = etcetera::choose_base_strategy()?
+        .cache_dir()
+        .join(LAST_REGION_FILENAME);
+
+    // no history yet
+    let history = fs::read_to_string(&path).unwrap_or_default();
+
+    path.pipe(fs::File::create)?
+        .write_all(with_region(&history, &region.as_str()).as_bytes())?
+        .pipe(Ok)
+}
+
+/// Add the `region` to the top of the `history`, removing it from further down
+/// and forgetting the oldest regions
+fn with_region(history: &str, region: &str) -> String {
+    std::iter::once(region)
+        .chain(history.lines().filter(|line| *line != region))
+        .take(HISTORY_LEN)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Write the accepted region to a file chosen by the user, for use in scripts
+pub fn write_region_file(path: &Path, region: Rectangle) -> Result<(), Error> {
+    fs::write(path, region.as_str())?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn newest_region_first() {
+        assert_eq!(with_region("", "10x10+0+0"), "10x10+0+0");
+        assert_eq!(
+            with_region("10x10+0+0\n20x20+5+5", "30x30+1+1"),
+            "30x30+1+1\n10x10+0+0\n20x20+5+5"
+        );
+        // used again, so it moves to the top
+        assert_eq!(
+            with_region("10x10+0+0\n20x20+5+5", "20x20+5+5"),
+            "20x20+5+5\n10x10+0+0"
+        );
+    }
+
+    #[test]
+    fn oldest_regions_are_forgotten() {
+        let history = (0..HISTORY_LEN)
+            .map(|i| format!("{i}x{i}+0+0"))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let new_history = with_region(&history, "100x100+0+0");
+
+        assert_eq!(new_history.lines().count(), HISTORY_LEN);
+        assert_eq!(new_history.lines().next(), Some("100x100+0+0"));
+        assert!(!new_history.contains(&format!("{0}x{0}+0+0", HISTORY_LEN - 1)));
+    }
+
+    #[test]
+    fn malformed_regions_are_skipped() {
+        let image_bounds = Rectangle {
+            x: 0.0,
+            y: 0.0,
+            width: 1920.0,
+            height: 1080.0,
+        };
+
+        assert_eq!(
+            parse_history("10x10+0+0\nnot a region\n20x20+5+5", image_bounds),
+            vec![
+                Rectangle {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 10.0,
+                    height: 10.0,
+                },
+                Rectangle {
+                    x: 5.0,
+                    y: 5.0,
+                    width: 20.0,
+                    height: 20.0,
+                },
+            ]
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
     #[test]
     fn write_and_read_last_region() {
         let region = Rectangle {
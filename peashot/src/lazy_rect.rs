@@ -29,6 +29,10 @@
 //!
 //! - -50% moves it to the left by 50px, -50% * 100px (width) = -50px
 //! - similar with height, but -50% * 150px (width) = -75px
+//!
+//! `W`, `H` and `X` can also be simple arithmetic, like `1920/2x1080/2+0+0`.
+//! Just like plain numbers, a whole result is an amount of pixels and anything else is a percentage.
+//! `+` can only be used in `W`, since it separates the other parts
 
 use std::{
     fmt,
@@ -38,7 +42,7 @@ use std::{
 
 use iced::Rectangle;
 
-use crate::geometry::RectangleExt as _;
+use crate::{expression::ExpressionError, geometry::RectangleExt as _};
 
 /// Percentage
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -106,6 +110,9 @@ pub enum ParseLengthError {
     /// Parse int error
     #[error(transparent)]
     ParseIntError(ParseIntError),
+    /// Invalid arithmetic expression
+    #[error(transparent)]
+    Expression(ExpressionError),
 }
 
 impl FromStr for Length {
@@ -115,6 +122,16 @@ impl FromStr for Length {
         s.parse::<u32>().map_or_else(
             |_| match s.parse::<Percentage>() {
                 Ok(percent) => Ok(Self::Relative(percent)),
+                // like "1920/2"
+                Err(_) if s.contains(['+', '-', '*', '/', '(']) => {
+                    match crate::expression::eval(s) {
+                        Ok(n) if n.fract() == 0.0 && (0.0..=f64::from(u32::MAX)).contains(&n) => {
+                            Ok(Self::Absolute(n as u32))
+                        }
+                        Ok(n) => Ok(Self::Relative(Percentage(n as f32))),
+                        Err(err) => Err(ParseLengthError::Expression(err)),
+                    }
+                }
                 Err(percent_err) => Err(ParseLengthError::ParsePercentageError(percent_err)),
             },
             |n| Ok(Self::Absolute(n)),
@@ -395,6 +412,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn arithmetic() {
+        assert_eq!(
+            "1920/2x1080/2+300+16"
+                .parse::<LazyRectangle>()
+                .unwrap()
+                .init(Rectangle {
+                    x: 0.0,
+                    y: 0.0,
+                    width: 1920.0,
+                    height: 1080.0,
+                }),
+            Rectangle {
+                x: 300.0,
+                y: 16.0,
+                width: 960.0,
+                height: 540.0
+            }
+        );
+        // not a whole number, so it is a percentage
+        assert_eq!(
+            "1/4x100+0+0".parse::<LazyRectangle>().unwrap().width,
+            Length::Relative(Percentage(0.25))
+        );
+        assert_eq!(
+            "1920/0x100+0+0".parse::<LazyRectangle>().unwrap_err(),
+            ParseRectError::ParseLengthError(ParseLengthError::Expression(
+                ExpressionError::DivisionByZero
+            ))
+        );
+    }
+
     /// Rectangle gets clapped into bounds
     #[test]
     fn clamp_exceeding_bounds() {
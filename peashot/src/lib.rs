@@ -15,6 +15,7 @@ mod command {
 
 mod clipboard;
 mod config;
+mod expression;
 mod geometry;
 mod icons;
 mod image;
@@ -27,13 +28,30 @@ use config::commands::Command;
 use config::Theme;
 use message::Message;
 
+pub mod app_regions;
+pub mod control;
+pub mod crash_report;
+pub mod diagnostics;
+pub mod doctor;
+pub mod last_capture;
 pub mod last_region;
 pub mod logging;
+pub mod replay;
+pub mod temp;
+pub mod undo_save;
+pub mod upload_file;
 
 #[cfg(target_os = "linux")]
 pub use clipboard::{CLIPBOARD_DAEMON_ID, run_clipboard_daemon};
+pub use clipboard::{ClipboardError, set_image, set_text};
 
-pub use config::{Cli, Config, DEFAULT_KDL_CONFIG_STR, DEFAULT_LOG_FILE_PATH};
-pub use image::action::SAVED_IMAGE;
-pub use image::get_image;
+pub use config::{
+    Cli, Config, ConfigCommand, DEFAULT_KDL_CONFIG_STR, DEFAULT_LOG_FILE_PATH, KeysCommand,
+    Subcommand,
+};
+pub use image::action::{ACCEPTED_REGION, SAVED_IMAGE};
+pub use image::{
+    CaptureBackendKind, MonitorChoice, get_frames, get_image, set_capture_backend, set_monitor,
+};
 pub use ui::App;
+pub use ui::app::edit_window_settings;
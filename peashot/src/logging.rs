@@ -1,28 +1,38 @@
 //! Initialize ferrishot logging to file or stderr
 
+use std::io::Write as _;
+
+/// Format of each logged line
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable text
+    #[default]
+    Text,
+    /// A JSON object per line, for shipping logs into tools like journald or ELK
+    Json,
+}
+
 /// Uses the `log` crate to log either to the standard output or the log file.
 ///
 /// See `CONTRIBUTING.md` for info on which params ferrishot takes
 /// for logging specifically that are normally hidden.
 pub fn initialize(cli: &crate::Cli) {
+    let log_format = cli.log_format;
+
     if cli.log_stderr {
-        env_logger::builder()
+        let mut builder = env_logger::builder();
+        if log_format == LogFormat::Json {
+            builder.format(format_json);
+        }
+        builder
             .filter_module(cli.log_filter.as_deref().unwrap_or(""), cli.log_level)
             .init();
     } else {
-        use std::io::Write as _;
-
         match std::fs::File::create(std::path::PathBuf::from(&*cli.log_file)) {
             Ok(file) => env_logger::Builder::new()
-                .format(|buf, record| {
-                    writeln!(
-                        buf,
-                        "[{time} {level} {module}] {message}",
-                        time = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f"),
-                        level = record.level(),
-                        module = record.module_path().unwrap_or("unknown"),
-                        message = record.args(),
-                    )
+                .format(move |buf, record| match log_format {
+                    LogFormat::Text => format_text(buf, record),
+                    LogFormat::Json => format_json(buf, record),
                 })
                 .target(env_logger::Target::Pipe(Box::new(file)))
                 .filter(cli.log_filter.as_deref(), cli.log_level)
@@ -34,3 +44,49 @@ pub fn initialize(cli: &crate::Cli) {
         }
     }
 }
+
+/// Format the log line as human-readable text
+fn format_text(buf: &mut env_logger::fmt::Formatter, record: &log::Record) -> std::io::Result<()> {
+    writeln!(
+        buf,
+        "[{time} {level} {module}] {message}",
+        time = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f"),
+        level = record.level(),
+        module = record.module_path().unwrap_or("unknown"),
+        message = record.args(),
+    )
+}
+
+/// Format the log line as a single JSON object
+fn format_json(buf: &mut env_logger::fmt::Formatter, record: &log::Record) -> std::io::Result<()> {
+    /// Collects key-value pairs of the record, e.g. `log::info!(path:? = path; "saved")`
+    struct Fields(serde_json::Map<String, serde_json::Value>);
+
+    impl<'kvs> log::kv::VisitSource<'kvs> for Fields {
+        fn visit_pair(
+            &mut self,
+            key: log::kv::Key<'kvs>,
+            value: log::kv::Value<'kvs>,
+        ) -> Result<(), log::kv::Error> {
+            self.0.insert(
+                key.to_string(),
+                serde_json::Value::String(value.to_string()),
+            );
+            Ok(())
+        }
+    }
+
+    let mut fields = Fields(serde_json::Map::new());
+    // can only fail if the visitor returns an error, ours never does
+    let _ = record.key_values().visit(&mut fields);
+
+    let line = serde_json::json!({
+        "timestamp": chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+        "level": record.level().as_str(),
+        "module": record.module_path().unwrap_or("unknown"),
+        "message": record.args().to_string(),
+        "fields": fields.0,
+    });
+
+    writeln!(buf, "{line}")
+}
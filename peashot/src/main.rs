@@ -1,6 +1,7 @@
 //! The ferrishot app
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use clap::Parser as _;
 use ferrishot::Cli;
@@ -41,13 +42,112 @@ fn main() -> miette::Result<()> {
         return Ok(());
     }
 
+    // Saving a screenshot happens after the window closed. The toast to undo the save
+    // needs a new window, so it runs as its own process
+    if std::env::args_os()
+        .nth(1)
+        .is_some_and(|arg| arg == ferrishot::undo_save::UNDO_SAVE_ID)
+    {
+        return ferrishot::undo_save::run()
+            .map_err(|err| miette!("Failed to show the toast to undo saving: {err}"));
+    }
+
     // Parse command line arguments
-    let cli = Arc::new(Cli::parse());
+    let mut cli = Cli::parse();
+    cli.apply_subcommand();
+    let cli = Arc::new(cli);
+
+    // Iced picks the renderer from this environment variable. When it is not set,
+    // Iced tries the GPU renderer first and falls back to the software renderer
+    if let Some(backend) = cli.renderer.backend() {
+        // SAFETY: No other threads have been spawned yet
+        unsafe {
+            std::env::set_var("ICED_BACKEND", backend);
+        }
+    }
 
     // Setup logging
     ferrishot::logging::initialize(&cli);
 
-    if cli.dump_default_config {
+    ferrishot::set_capture_backend(cli.capture_backend);
+    ferrishot::set_monitor(cli.monitor.clone());
+
+    // Write a crash report instead of leaving a frozen fullscreen window
+    ferrishot::crash_report::install();
+
+    // Remove temporary files left behind by ferrishot processes that were killed,
+    // and those of this process once it exits
+    let _temp_files = ferrishot::temp::init();
+
+    if let Some(ferrishot::Subcommand::Doctor) = cli.command {
+        return if ferrishot::doctor::run(&cli) {
+            Ok(())
+        } else {
+            Err(miette!("Some of the checks failed"))
+        };
+    }
+
+    if cli.version {
+        if cli.json {
+            println!(
+                "{}",
+                ferrishot::diagnostics::Diagnostics::collect(&cli).to_json()
+            );
+        } else {
+            println!("ferrishot {}", env!("CARGO_PKG_VERSION"));
+        }
+
+        return Ok(());
+    }
+
+    if let Some(ferrishot::Subcommand::Upload { file, service }) = &cli.command {
+        let runtime = tokio::runtime::Runtime::new().into_diagnostic()?;
+        let output = runtime.block_on(ferrishot::upload_file::run(file, *service, cli.json))?;
+
+        if !cli.silent {
+            print!("{output}");
+        }
+
+        return Ok(());
+    }
+
+    if let Some(ferrishot::Subcommand::Ctl { commands }) = &cli.command {
+        let runtime = tokio::runtime::Runtime::new().into_diagnostic()?;
+        runtime.block_on(ferrishot::control::send(commands))?;
+
+        return Ok(());
+    }
+
+    let config_command = match &cli.command {
+        Some(ferrishot::Subcommand::Config(command)) => Some(*command),
+        _ => None,
+    };
+
+    if config_command == Some(ferrishot::ConfigCommand::Path) {
+        println!("{}", cli.config_file);
+        return Ok(());
+    }
+
+    if config_command == Some(ferrishot::ConfigCommand::Check) {
+        let config = ferrishot::Config::parse(&cli.config_file)?;
+
+        if !cli.silent {
+            for conflict in config.keys.conflicts {
+                eprintln!("{:?}", miette::Report::new(conflict));
+            }
+            println!("{} is valid", cli.config_file);
+        }
+
+        return Ok(());
+    }
+
+    if let Some(ferrishot::Subcommand::Keys(ferrishot::KeysCommand::List)) = cli.command {
+        let config = ferrishot::Config::parse(&cli.config_file)?;
+        print!("{}", config.keys.list(cli.json));
+        return Ok(());
+    }
+
+    if cli.dump_default_config || config_command == Some(ferrishot::ConfigCommand::Dump) {
         std::fs::create_dir_all(
             std::path::PathBuf::from(&cli.config_file)
                 .parent()
@@ -64,8 +164,17 @@ fn main() -> miette::Result<()> {
         return Ok(());
     }
 
+    // `ferrishot edit` opens this image instead of taking a screenshot
+    let edited_image = match &cli.command {
+        Some(ferrishot::Subcommand::Edit { image }) => Some(image.clone()),
+        _ => None,
+    };
+
     // these variables need to be re-used after the `iced::application` ends
     let cli_save_path = cli.save_path.clone();
+    let is_save_path_given = cli_save_path.is_some();
+    let cli_edited_image = edited_image.clone();
+    let cli_write_region_file = cli.write_region_file.clone();
     let is_silent = cli.silent;
 
     if let Some(delay) = cli.delay {
@@ -77,56 +186,115 @@ fn main() -> miette::Result<()> {
 
     // Parse user's `ferrishot.kdl` config file
     let config = Arc::new(ferrishot::Config::parse(&cli.config_file)?);
+    ferrishot::crash_report::set_config(&config);
+    let save_conflict = config.save_conflict;
+    let pdf_page_size = config.pdf_page_size;
+    let icc_profile = config.icc_profile.clone();
+    let undo_save_timeout = Duration::from_secs(config.undo_save_timeout.into());
+    let skip_duplicate_captures = config.skip_duplicate_captures;
+
+    // Animated images are edited one frame at a time, picked with a scrub bar
+    let frames = match edited_image.as_ref().or(cli.file.as_ref()) {
+        Some(path) if cli.replay.is_none() => ferrishot::get_frames(path)?,
+        _ => None,
+    };
+
+    // Replay a recording made with `--record`, which brings its own screenshot
+    let (replay, image) = match (&cli.replay, &frames) {
+        (Some(path), _) => ferrishot::replay::Replay::read(path)
+            .map(|(replay, image)| (Some(replay), image))
+            .map_err(|err| miette!("Failed to read the recording: {err}"))?,
+        (None, Some(frames)) => (None, frames[0].clone()),
+        (None, None) => (
+            None,
+            ferrishot::get_image(edited_image.as_ref().or(cli.file.as_ref()))?,
+        ),
+    };
+    // a single frame is just the image, with nothing to scrub through
+    let frames = frames.filter(|frames| frames.len() > 1);
 
     // The image that we are going to be editing
-    let image = Arc::new(ferrishot::get_image(cli.file.as_ref())?);
+    let image = Arc::new(image);
 
     // start the app with an initial selection of the image
     let initial_region = if cli.last_region {
         ferrishot::last_region::read(image.bounds())?
     } else {
-        cli.region.map(|lazy_rect| lazy_rect.init(image.bounds()))
+        cli.region
+            // the focused app has nothing to do with an image being edited
+            .or_else(|| {
+                (edited_image.is_none() && !cli.present)
+                    .then(|| config.app_regions.for_focused_app())
+                    .flatten()
+            })
+            .map(|lazy_rect| lazy_rect.init(image.bounds()))
     };
 
+    // a region of the focused app is only a suggestion, which the user sees in the app
+    let is_region_given = cli.region.is_some() || cli.last_region;
+    let is_headless = cli.accept_on_select.is_some() && initial_region.is_some() && is_region_given;
+
     let generate_output = match (cli.accept_on_select, initial_region) {
         // If we want to do an action as soon as we have a selection,
         // AND we start the app with the selection: Then don't even launch a window.
         //
         // Run in 'headless' mode and perform the action instantly
-        (Some(accept_on_select), Some(region)) => {
+        (Some(accept_on_select), Some(region)) if is_region_given => {
             let runtime = tokio::runtime::Runtime::new().into_diagnostic()?;
 
-            App::headless(accept_on_select, region, image, cli.json)
+            App::headless(accept_on_select, region, image, cli.json, config)
                 .pipe(|fut| runtime.block_on(fut))
                 .map_err(|err| miette!("Failed to start ferrishot (headless): {err}"))?
                 .pipe(Some)
         }
         // Launch full ferrishot app
         _ => {
+            let icon = match &cli.window_icon {
+                Some(path) => iced::window::icon::from_file(path).map_err(|err| {
+                    miette!("Failed to load the window icon {}: {err}", path.display())
+                })?,
+                None => iced::window::icon::from_rgba(LOGO.to_vec(), 64, 64)
+                    .expect("Icon to be valid RGBA bytes"),
+            };
+
+            let mut window_settings = if edited_image.is_some() && !cli.present {
+                ferrishot::edit_window_settings(&image)
+            } else {
+                config.window_mode.settings(&image)
+            };
+            window_settings.icon = Some(icon);
+            #[cfg(target_os = "linux")]
+            {
+                window_settings.platform_specific.application_id = cli.app_id.clone();
+            }
+
+            let settings = iced::Settings {
+                default_font: config.theme.font.into(),
+                default_text_size: iced::Pixels(config.theme.font_size as f32),
+                ..Default::default()
+            };
+
             iced::application(
                 move || {
-                    App::builder()
+                    let app = App::builder()
                         .cli(Arc::clone(&cli))
                         .config(Arc::clone(&config))
                         .maybe_initial_region(initial_region)
                         .image(Arc::clone(&image))
-                        .build()
+                        .maybe_frames(frames.clone())
+                        .maybe_replay(replay.clone())
+                        .build();
+                    let on_start = app.on_start();
+
+                    (app, on_start)
                 },
                 App::update,
                 App::view,
             )
             .subscription(App::subscription)
-            .window(iced::window::Settings {
-                level: iced::window::Level::Normal,
-                fullscreen: true,
-                icon: Some(
-                    iced::window::icon::from_rgba(LOGO.to_vec(), 64, 64)
-                        .expect("Icon to be valid RGBA bytes"),
-                ),
-                ..Default::default()
-            })
-            .title("ferrishot")
-            .default_font(iced::Font::MONOSPACE)
+            .window(window_settings)
+            .title(App::title)
+            .settings(settings)
             .run()
             .map_err(|err| miette!("Failed to start ferrishot: {err}"))?;
 
@@ -135,22 +303,66 @@ fn main() -> miette::Result<()> {
     };
 
     let saved_path = if let Some(saved_image) = ferrishot::SAVED_IMAGE.get() {
-        if let Some(save_path) = cli_save_path.or_else(|| {
-            // Open file explorer to choose where to save the image
-            let dialog = rfd::FileDialog::new()
-                .set_title("Save Screenshot")
-                .save_file();
-
-            if dialog.is_none() {
-                log::info!("The file dialog was closed before a file was chosen");
-            }
+        let checksum = skip_duplicate_captures.then(|| saved_image.checksum());
 
-            dialog
+        if let Some(previous_path) = checksum.as_deref().and_then(|checksum| {
+            ferrishot::last_capture::previous_save(checksum, saved_image.is_pdf)
         }) {
+            log::info!(
+                "The screenshot is the same as the one saved to {}, so it was not saved again",
+                previous_path.display()
+            );
+            Some(previous_path)
+        } else if let Some(save_path) = cli_save_path
+            // the file dialog already asks before replacing a file
+            .map(|path| save_conflict.resolve(path))
+            .or_else(|| {
+                // Open file explorer to choose where to save the image
+                let mut dialog = rfd::FileDialog::new().set_title("Save Screenshot");
+
+                if saved_image.is_pdf {
+                    dialog = dialog
+                        .add_filter("PDF", &["pdf"])
+                        .set_file_name("screenshot.pdf");
+                }
+
+                // suggest saving over the edited image
+                if let Some(edited_image) = &cli_edited_image {
+                    if let Some(directory) = edited_image.parent() {
+                        dialog = dialog.set_directory(directory);
+                    }
+                    if let Some(file_name) = edited_image.file_name() {
+                        dialog = dialog.set_file_name(file_name.to_string_lossy());
+                    }
+                }
+
+                let dialog = dialog.save_file();
+
+                if dialog.is_none() {
+                    log::info!("The file dialog was closed before a file was chosen");
+                }
+
+                dialog
+            })
+        {
             saved_image
-                .save(&save_path)
+                .save(&save_path, pdf_page_size, icc_profile.as_deref())
                 .map_err(|err| miette!("Failed to save the screenshot: {err}"))?;
 
+            if let Some(checksum) = checksum {
+                ferrishot::last_capture::remember_save(checksum, &save_path, saved_image.is_pdf);
+            }
+
+            // scripts which pass `--save-path`, or run headless, have nobody to click the toast
+            if !undo_save_timeout.is_zero()
+                && !is_silent
+                && !is_headless
+                && !is_save_path_given
+                && let Err(err) = ferrishot::undo_save::spawn(&save_path, undo_save_timeout)
+            {
+                log::error!("Failed to show the toast to undo saving: {err}");
+            }
+
             Some(save_path)
         } else {
             None
@@ -159,6 +371,13 @@ fn main() -> miette::Result<()> {
         None
     };
 
+    if let Some(path) = cli_write_region_file
+        && let Some(region) = ferrishot::ACCEPTED_REGION.get()
+    {
+        ferrishot::last_region::write_region_file(&path, *region)
+            .map_err(|err| miette!("Failed to write the region to {}: {err}", path.display()))?;
+    }
+
     if let Some(print_output) = generate_output {
         let output = print_output(saved_path);
         if !is_silent {
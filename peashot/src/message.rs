@@ -11,7 +11,8 @@ pub trait Handler {
 }
 
 /// Represents an action happening in the application
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, strum::EnumDiscriminants)]
+#[strum_discriminants(name(MessageKind), derive(strum::EnumIter, strum::Display))]
 pub enum Message {
     /// Close the app
     Exit,
@@ -23,14 +24,46 @@ pub enum Message {
     Tick(Instant),
     /// Letters message
     Letters(ui::popup::letters::Message),
+    /// Palette message
+    Palette(ui::popup::palette::Message),
+    /// Help message
+    Help(ui::popup::help::Message),
+    /// Radial menu message
+    RadialMenu(ui::popup::radial_menu::Message),
+    /// Upload too large message
+    UploadTooLarge(ui::popup::upload_too_large::Message),
+    /// Selection icons message
+    SelectionIcons(ui::selection_icons::Message),
+    /// Histogram message
+    Histogram(ui::histogram::Message),
+    /// Frames message
+    Frames(ui::frames::Message),
+    /// Control message, for `ferrishot ctl`
+    Control(crate::control::Message),
+    /// Magnifier message
+    Magnifier(ui::magnifier::Message),
+    /// Measure message
+    Measure(ui::measure::Message),
+    /// Annotations message
+    Annotations(ui::annotations::Message),
     /// Size indicator message
     SizeIndicator(ui::size_indicator::Message),
     /// Selection message
     Selection(Box<ui::selection::Message>),
     /// Keybinding cheatsheet message
     KeyCheatsheet(ui::popup::keybindings_cheatsheet::Message),
+    /// Debug overlay message
+    DebugOverlay(ui::debug_overlay::Message),
     /// An error occured, display to the user
     Error(String),
+    /// A selection was made with `--accept-on-select`, so its action should run
+    AcceptOnSelect(crate::image::action::Command),
+    /// The user pressed a key or used the mouse
+    Interaction,
+    /// Check whether ferrishot was idle for longer than `idle-timeout`
+    IdleCheck(Instant),
+    /// An input event happened which should be written to the recording
+    Record(crate::replay::RecordedEvent),
     /// Do nothing
     NoOp,
     /// A command can be triggered by a keybind
@@ -48,4 +81,14 @@ pub enum Message {
         /// in which case we'd move to the right by `N * count` instead
         count: u32,
     },
+    /// A keybinding with a `region` was pressed while nothing is selected,
+    /// so the region is selected before running the command
+    CommandInRegion {
+        /// What to do when this keybind is pressed
+        action: Command,
+        /// How many times it was pressed
+        count: u32,
+        /// The region to select
+        region: crate::lazy_rect::LazyRectangle,
+    },
 }
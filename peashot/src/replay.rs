@@ -0,0 +1,448 @@
+//! Record the input events received by ferrishot, and replay them later
+//!
+//! This is useful for reproducing bugs. Run ferrishot with `--record events.jsonl`,
+//! do whatever it takes to trigger the bug and then anyone can run
+//! `ferrishot --replay events.jsonl` to see the exact same thing happen.
+//!
+//! A recording consists of 2 files:
+//! - The events, stored as JSON lines. The first line is a header, every
+//!   other line is a single event with the time at which it happened
+//! - The screenshot that ferrishot was started with, stored as PNG next to the events
+//!
+//! Replaying feeds the events through the same `update` of the canvas that handles
+//! all of the input events, so behaviour is deterministic.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufRead as _, BufReader, BufWriter, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use iced::keyboard::{self, Key, Modifiers, key};
+use iced::{Point, mouse};
+use strum::IntoEnumIterator as _;
+
+use crate::config::named_key::Named;
+use crate::image::RgbaHandle;
+use crate::ui::app::AppKeysState;
+use crate::ui::selection::SelectionKeysState;
+
+/// Failed to record or replay events
+#[derive(thiserror::Error, miette::Diagnostic, Debug)]
+pub enum Error {
+    /// Failed to read or write the recording
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// A line of the recording is invalid
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// Failed to save or open the screenshot of the recording
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
+    /// Failed to open the screenshot of the recording
+    #[error(transparent)]
+    GetImage(#[from] crate::image::GetImageError),
+    /// The recording is empty
+    #[error("The recording {0} is empty")]
+    #[diagnostic(help("Create a recording with `--record <FILE>`"))]
+    Empty(PathBuf),
+}
+
+/// First line of the recording
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+struct Header {
+    /// Version of ferrishot which made the recording
+    version: String,
+    /// Screenshot that ferrishot was started with, relative to the recording
+    screenshot: PathBuf,
+}
+
+/// Every line of the recording after the header
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+struct Entry {
+    /// Milliseconds since the start of ferrishot
+    time: u64,
+    /// The event that happened
+    event: RecordedEvent,
+}
+
+/// Mouse button which was recorded
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MouseButton {
+    /// The left mouse button
+    Left,
+    /// The right mouse button
+    Right,
+    /// The middle mouse button
+    Middle,
+}
+
+/// Key which was recorded
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecordedKey {
+    /// A named key, such as `enter`
+    Named(String),
+    /// A key which produces a character, such as `a`
+    Character(String),
+    /// A key which is not known
+    Unidentified,
+}
+
+impl RecordedKey {
+    /// Create from an Iced key
+    fn from_iced(key: &Key) -> Self {
+        match key {
+            Key::Named(named) => Named::iter()
+                .find(|key| key.to_iced() == *named)
+                .map_or(Self::Unidentified, |key| {
+                    Self::Named(<&'static str>::from(key).to_string())
+                }),
+            Key::Character(ch) => Self::Character(ch.to_string()),
+            Key::Unidentified => Self::Unidentified,
+        }
+    }
+
+    /// Convert into an Iced key
+    fn to_iced(&self) -> Key {
+        match self {
+            Self::Named(name) => name
+                .parse::<Named>()
+                .map_or(Key::Unidentified, |key| Key::Named(key.to_iced())),
+            Self::Character(ch) => Key::Character(ch.as_str().into()),
+            Self::Unidentified => Key::Unidentified,
+        }
+    }
+}
+
+/// An input event which can be recorded
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", tag = "type")]
+pub enum RecordedEvent {
+    /// The cursor moved to this position
+    CursorMoved {
+        /// Horizontal position of the cursor
+        x: i32,
+        /// Vertical position of the cursor
+        y: i32,
+    },
+    /// A mouse button was pressed
+    ButtonPressed {
+        /// The button
+        button: MouseButton,
+    },
+    /// A mouse button was released
+    ButtonReleased {
+        /// The button
+        button: MouseButton,
+    },
+    /// A key was pressed
+    KeyPressed {
+        /// The key, without modifiers applied
+        key: RecordedKey,
+        /// The key, with modifiers applied
+        modified_key: RecordedKey,
+        /// Modifiers that were held
+        modifiers: u32,
+    },
+    /// A key was released
+    KeyReleased {
+        /// The key, without modifiers applied
+        key: RecordedKey,
+        /// The key, with modifiers applied
+        modified_key: RecordedKey,
+        /// Modifiers that were held
+        modifiers: u32,
+    },
+}
+
+impl RecordedEvent {
+    /// Create from an Iced event, if it is one that we record
+    pub fn from_iced(event: &iced::Event) -> Option<Self> {
+        use iced::Event::{Keyboard, Mouse};
+
+        let button = |button: &mouse::Button| match button {
+            mouse::Button::Left => Some(MouseButton::Left),
+            mouse::Button::Right => Some(MouseButton::Right),
+            mouse::Button::Middle => Some(MouseButton::Middle),
+            _ => None,
+        };
+
+        match event {
+            Mouse(mouse::Event::CursorMoved { position }) => Some(Self::CursorMoved {
+                x: position.x.round() as i32,
+                y: position.y.round() as i32,
+            }),
+            Mouse(mouse::Event::ButtonPressed(btn)) => Some(Self::ButtonPressed {
+                button: button(btn)?,
+            }),
+            Mouse(mouse::Event::ButtonReleased(btn)) => Some(Self::ButtonReleased {
+                button: button(btn)?,
+            }),
+            Keyboard(keyboard::Event::KeyPressed {
+                key,
+                modified_key,
+                modifiers,
+                ..
+            }) => Some(Self::KeyPressed {
+                key: RecordedKey::from_iced(key),
+                modified_key: RecordedKey::from_iced(modified_key),
+                modifiers: modifiers.bits(),
+            }),
+            Keyboard(keyboard::Event::KeyReleased {
+                key,
+                modified_key,
+                modifiers,
+                ..
+            }) => Some(Self::KeyReleased {
+                key: RecordedKey::from_iced(key),
+                modified_key: RecordedKey::from_iced(modified_key),
+                modifiers: modifiers.bits(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Convert into an Iced event
+    pub fn to_iced(&self) -> iced::Event {
+        use iced::Event::{Keyboard, Mouse};
+
+        let button = |button: MouseButton| match button {
+            MouseButton::Left => mouse::Button::Left,
+            MouseButton::Right => mouse::Button::Right,
+            MouseButton::Middle => mouse::Button::Middle,
+        };
+
+        match self {
+            Self::CursorMoved { x, y } => Mouse(mouse::Event::CursorMoved {
+                position: Point::new(*x as f32, *y as f32),
+            }),
+            Self::ButtonPressed { button: btn } => Mouse(mouse::Event::ButtonPressed(button(*btn))),
+            Self::ButtonReleased { button: btn } => {
+                Mouse(mouse::Event::ButtonReleased(button(*btn)))
+            }
+            Self::KeyPressed {
+                key,
+                modified_key,
+                modifiers,
+            } => {
+                let modified_key = modified_key.to_iced();
+                Keyboard(keyboard::Event::KeyPressed {
+                    key: key.to_iced(),
+                    text: match &modified_key {
+                        Key::Character(ch) => Some(ch.clone()),
+                        _ => None,
+                    },
+                    modified_key,
+                    physical_key: key::Physical::Unidentified(key::NativeCode::Unidentified),
+                    location: keyboard::Location::Standard,
+                    modifiers: Modifiers::from_bits_truncate(*modifiers),
+                    repeat: false,
+                })
+            }
+            Self::KeyReleased {
+                key,
+                modified_key,
+                modifiers,
+            } => Keyboard(keyboard::Event::KeyReleased {
+                key: key.to_iced(),
+                modified_key: modified_key.to_iced(),
+                physical_key: key::Physical::Unidentified(key::NativeCode::Unidentified),
+                location: keyboard::Location::Standard,
+                modifiers: Modifiers::from_bits_truncate(*modifiers),
+            }),
+        }
+    }
+}
+
+/// Path of the screenshot which belongs to the recording at `path`
+fn screenshot_path(path: &Path) -> PathBuf {
+    path.with_extension("png")
+}
+
+/// Writes events to a recording as they happen
+#[derive(Debug)]
+pub struct Recorder {
+    /// The recording
+    file: BufWriter<fs::File>,
+}
+
+impl Recorder {
+    /// Start a new recording at `path`, saving the `image` next to it
+    pub fn create(path: &Path, image: &RgbaHandle) -> Result<Self, Error> {
+        let screenshot = screenshot_path(path);
+
+        image::save_buffer(
+            &screenshot,
+            image.bytes(),
+            image.width(),
+            image.height(),
+            image::ExtendedColorType::Rgba8,
+        )?;
+
+        let mut file = BufWriter::new(fs::File::create(path)?);
+
+        serde_json::to_writer(
+            &mut file,
+            &Header {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                screenshot: screenshot
+                    .file_name()
+                    .map_or_else(|| screenshot.clone(), PathBuf::from),
+            },
+        )?;
+        writeln!(file)?;
+
+        log::info!("Recording events to {}", path.display());
+
+        Ok(Self { file })
+    }
+
+    /// Write the `event` which happened `elapsed` since the start of ferrishot
+    pub fn write(&mut self, elapsed: Duration, event: RecordedEvent) -> Result<(), Error> {
+        serde_json::to_writer(
+            &mut self.file,
+            &Entry {
+                time: elapsed.as_millis() as u64,
+                event,
+            },
+        )?;
+        writeln!(self.file)?;
+        // the recording must survive ferrishot crashing, as that is usually
+        // exactly the bug we want to reproduce
+        self.file.flush()?;
+
+        Ok(())
+    }
+}
+
+/// A recording which is being replayed
+#[derive(Debug, Clone)]
+pub struct Replay {
+    /// Events which have not been replayed yet
+    events: VecDeque<Entry>,
+    /// Last known position of the cursor
+    pub cursor: Option<Point>,
+    /// State of the canvas that receives the replayed events
+    pub state: (AppKeysState, SelectionKeysState),
+}
+
+impl Replay {
+    /// Read the recording at `path`, returning the replay and the screenshot
+    pub fn read(path: &Path) -> Result<(Self, RgbaHandle), Error> {
+        let mut lines = BufReader::new(fs::File::open(path)?).lines();
+
+        let header: Header = serde_json::from_str(
+            &lines
+                .next()
+                .ok_or_else(|| Error::Empty(path.to_path_buf()))??,
+        )?;
+
+        if header.version != env!("CARGO_PKG_VERSION") {
+            log::warn!(
+                "Recording was made with ferrishot {}, but this is {}",
+                header.version,
+                env!("CARGO_PKG_VERSION")
+            );
+        }
+
+        let events = lines
+            .filter(|line| line.as_ref().is_ok_and(|line| !line.trim().is_empty()))
+            .map(|line| Ok(serde_json::from_str::<Entry>(&line?)?))
+            .collect::<Result<VecDeque<_>, Error>>()?;
+
+        let screenshot = path.parent().map_or_else(
+            || header.screenshot.clone(),
+            |dir| dir.join(&header.screenshot),
+        );
+        let image = crate::image::get_image(Some(&screenshot))?;
+
+        Ok((
+            Self {
+                events,
+                cursor: None,
+                state: Default::default(),
+            },
+            image,
+        ))
+    }
+
+    /// Take the next event which should have happened by the time `elapsed`
+    /// since the start of ferrishot
+    pub fn next_due(&mut self, elapsed: Duration) -> Option<iced::Event> {
+        if self.events.front()?.time > elapsed.as_millis() as u64 {
+            return None;
+        }
+
+        let event = self.events.pop_front()?.event;
+
+        if let RecordedEvent::CursorMoved { x, y } = event {
+            self.cursor = Some(Point::new(x as f32, y as f32));
+        }
+
+        Some(event.to_iced())
+    }
+
+    /// All of the events have been replayed
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn event_roundtrip() {
+        let events = [
+            RecordedEvent::CursorMoved { x: 40, y: 1200 },
+            RecordedEvent::ButtonPressed {
+                button: MouseButton::Left,
+            },
+            RecordedEvent::KeyPressed {
+                key: RecordedKey::Named("arrow-left".to_string()),
+                modified_key: RecordedKey::Named("arrow-left".to_string()),
+                modifiers: Modifiers::SHIFT.bits(),
+            },
+            RecordedEvent::KeyReleased {
+                key: RecordedKey::Character("g".to_string()),
+                modified_key: RecordedKey::Character("G".to_string()),
+                modifiers: Modifiers::SHIFT.bits(),
+            },
+        ];
+
+        for event in events {
+            assert_eq!(RecordedEvent::from_iced(&event.to_iced()), Some(event));
+        }
+    }
+
+    #[test]
+    fn replay_in_order() {
+        let mut replay = Replay {
+            events: VecDeque::from([
+                Entry {
+                    time: 10,
+                    event: RecordedEvent::CursorMoved { x: 5, y: 5 },
+                },
+                Entry {
+                    time: 20,
+                    event: RecordedEvent::ButtonPressed {
+                        button: MouseButton::Left,
+                    },
+                },
+            ]),
+            cursor: None,
+            state: Default::default(),
+        };
+
+        assert!(replay.next_due(Duration::from_millis(5)).is_none());
+        assert!(replay.next_due(Duration::from_millis(15)).is_some());
+        assert_eq!(replay.cursor, Some(Point::new(5.0, 5.0)));
+        assert!(replay.next_due(Duration::from_millis(15)).is_none());
+        assert!(replay.next_due(Duration::from_millis(25)).is_some());
+        assert!(replay.is_finished());
+    }
+}
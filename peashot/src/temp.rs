@@ -0,0 +1,383 @@
+//! Temporary files written by ferrishot
+//!
+//! Every temporary file goes into a directory of the process that wrote it, inside of
+//! a directory of the user that only they can read. Files are removed when their
+//! [`Artifact`] is dropped, the directory of the process is removed when ferrishot
+//! exits or crashes, and directories left behind by processes that were killed are
+//! removed by the next ferrishot that starts.
+//!
+//! A file can be handed off to another process, like the image given to the clipboard
+//! daemon. Those outlive the process that wrote them. The process they were handed off to
+//! removes them, and the startup sweep removes them in case it didn't, once they are
+//! older than [`STALE_HANDED_OFF`]
+
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Handed off files are removed by the startup sweep after this long, in case the
+/// process they were handed off to never removed them
+const STALE_HANDED_OFF: Duration = Duration::from_secs(60 * 60);
+
+/// Directories of processes which are not running anymore are removed after this long,
+/// on platforms where we can't tell if the process is still running
+#[cfg(not(target_os = "linux"))]
+const STALE_PROCESS_DIR: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Prefix of the files which were handed off to other processes
+const HANDED_OFF_PREFIX: &str = "handed-off-";
+
+/// Directory of this process, created when the first file is written
+static PROCESS_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Makes the name of every file unique, even when the same name is requested twice
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Directory containing the temporary files of all ferrishot processes of this user
+///
+/// `XDG_RUNTIME_DIR` belongs to the user already. The temporary directory is shared
+/// by all users, so the name of the directory includes the user's ID
+#[cfg(not(test))]
+pub fn root() -> PathBuf {
+    if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        return PathBuf::from(runtime_dir).join("ferrishot");
+    }
+
+    #[cfg(unix)]
+    let name = match current_uid() {
+        Ok(uid) => format!("ferrishot-{uid}"),
+        Err(err) => {
+            log::warn!("Failed to get the ID of the user: {err}");
+            format!("ferrishot-{}", std::env::var("USER").unwrap_or_default())
+        }
+    };
+    // on Windows, the temporary directory is different for every user
+    #[cfg(not(unix))]
+    let name = "ferrishot".to_string();
+
+    std::env::temp_dir().join(name)
+}
+
+/// Tests write their files into a directory of their own, instead of where ferrishot does
+#[cfg(test)]
+pub fn root() -> PathBuf {
+    /// Directory shared by all of the tests
+    static ROOT: std::sync::LazyLock<tempfile::TempDir> =
+        std::sync::LazyLock::new(|| tempfile::tempdir().expect("temporary directory"));
+
+    ROOT.path().join("ferrishot")
+}
+
+/// ID of the user running ferrishot
+///
+/// A new file is owned by the user who created it, which saves calling into libc
+#[cfg(unix)]
+pub fn current_uid() -> io::Result<u32> {
+    use std::os::unix::fs::MetadataExt as _;
+
+    static UID: OnceLock<u32> = OnceLock::new();
+
+    if let Some(uid) = UID.get() {
+        return Ok(*uid);
+    }
+
+    let uid = tempfile::tempfile()?.metadata()?.uid();
+
+    Ok(*UID.get_or_init(|| uid))
+}
+
+/// Create the directory at `path`, which only the user can access
+///
+/// Fails if something else is at the `path`, like a symlink or a directory of another
+/// user, which could have been put there to read the files written into it
+pub fn create_private_dir(path: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt as _, PermissionsExt as _};
+
+        use std::os::unix::fs::MetadataExt as _;
+
+        builder.mode(0o700).create(path)?;
+
+        let metadata = fs::symlink_metadata(path)?;
+        if !metadata.is_dir() || metadata.uid() != current_uid()? {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "{} is not a directory owned by the current user",
+                    path.display()
+                ),
+            ));
+        }
+
+        // the directory could have existed already, created with other permissions
+        if metadata.permissions().mode() & 0o077 != 0 {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o700))?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    builder.create(path)
+}
+
+/// Create the file at `path` containing `bytes`, which only the user can access
+fn create_private_file(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path)?.write_all(bytes)
+}
+
+/// Directory of this process, creating it if it does not exist yet
+fn process_dir() -> io::Result<&'static Path> {
+    if let Some(dir) = PROCESS_DIR.get() {
+        return Ok(dir);
+    }
+
+    let root = root();
+    create_private_dir(&root)?;
+    let dir = root.join(std::process::id().to_string());
+    create_private_dir(&dir)?;
+
+    Ok(PROCESS_DIR.get_or_init(|| dir))
+}
+
+/// A temporary file, removed when this is dropped
+#[derive(Debug)]
+pub struct Artifact {
+    /// Where the file is
+    path: PathBuf,
+}
+
+impl Artifact {
+    /// Path to the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The file will be removed by another process, instead of when this is dropped
+    ///
+    /// # Returns
+    ///
+    /// Where the file was moved to
+    pub fn hand_off(self) -> io::Result<PathBuf> {
+        let name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let path = root().join(format!("{HANDED_OFF_PREFIX}{}-{name}", std::process::id()));
+
+        fs::rename(&self.path, &path)?;
+        std::mem::forget(self);
+
+        Ok(path)
+    }
+}
+
+impl Drop for Artifact {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            log::warn!(
+                "Failed to remove the temporary file {}: {err}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Write a temporary file containing `bytes`. Its name ends with `name`
+pub fn write(name: &str, bytes: &[u8]) -> io::Result<Artifact> {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let path = process_dir()?.join(format!("{id}-{name}"));
+
+    create_private_file(&path, bytes)?;
+
+    Ok(Artifact { path })
+}
+
+/// Remove all of the temporary files of this process
+pub fn cleanup() {
+    let Some(dir) = PROCESS_DIR.get() else {
+        return;
+    };
+
+    if let Err(err) = fs::remove_dir_all(dir)
+        && err.kind() != io::ErrorKind::NotFound
+    {
+        log::warn!(
+            "Failed to remove the temporary files in {}: {err}",
+            dir.display()
+        );
+    }
+}
+
+/// Removes the temporary files of this process when dropped
+#[derive(Debug)]
+#[must_use = "the temporary files are removed when this is dropped"]
+pub struct Guard;
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        cleanup();
+    }
+}
+
+/// Remove the temporary files left behind by ferrishot processes which are gone
+///
+/// # Returns
+///
+/// Guard which removes the temporary files of this process once it is dropped
+pub fn init() -> Guard {
+    let root = root();
+
+    if root.exists() {
+        for path in stale(&root, SystemTime::now()) {
+            let removed = if path.is_dir() {
+                fs::remove_dir_all(&path)
+            } else {
+                fs::remove_file(&path)
+            };
+
+            match removed {
+                Ok(()) => log::info!("Removed stale temporary file {}", path.display()),
+                Err(err) => log::warn!(
+                    "Failed to remove stale temporary file {}: {err}",
+                    path.display()
+                ),
+            }
+        }
+    }
+
+    Guard
+}
+
+/// Whether the process with this `pid` is still running
+#[cfg(target_os = "linux")]
+fn is_running(pid: u32, _modified: SystemTime, _now: SystemTime) -> bool {
+    Path::new("/proc").join(pid.to_string()).exists()
+}
+
+/// Whether the process with this `pid` might still be running
+#[cfg(not(target_os = "linux"))]
+fn is_running(_pid: u32, modified: SystemTime, now: SystemTime) -> bool {
+    now.duration_since(modified)
+        .is_ok_and(|age| age < STALE_PROCESS_DIR)
+}
+
+/// Files and directories inside of `root` which are left behind
+fn stale(root: &Path, now: SystemTime) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            let Ok(modified) = entry.metadata().and_then(|meta| meta.modified()) else {
+                return false;
+            };
+
+            if name.starts_with(HANDED_OFF_PREFIX) {
+                now.duration_since(modified)
+                    .is_ok_and(|age| age >= STALE_HANDED_OFF)
+            } else if let Ok(pid) = name.parse::<u32>() {
+                pid != std::process::id() && !is_running(pid, modified, now)
+            } else {
+                false
+            }
+        })
+        .map(|entry| entry.path())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn stale_handed_off_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let handed_off = dir.path().join(format!("{HANDED_OFF_PREFIX}1-0-image"));
+        fs::write(&handed_off, "").unwrap();
+        fs::write(dir.path().join("unrelated"), "").unwrap();
+
+        // the daemon could still be reading it
+        assert_eq!(stale(dir.path(), SystemTime::now()), Vec::<PathBuf>::new());
+
+        assert_eq!(
+            stale(dir.path(), SystemTime::now() + STALE_HANDED_OFF),
+            vec![handed_off]
+        );
+    }
+
+    #[test]
+    fn directory_of_this_process_is_not_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join(std::process::id().to_string())).unwrap();
+
+        assert_eq!(
+            stale(
+                dir.path(),
+                SystemTime::now() + Duration::from_secs(365 * 24 * 60 * 60)
+            ),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn only_the_user_can_access_files() {
+        use std::os::unix::fs::PermissionsExt as _;
+
+        let dir = tempfile::tempdir().unwrap();
+        let private_dir = dir.path().join("private");
+        create_private_dir(&private_dir).unwrap();
+        let file = private_dir.join("file");
+        create_private_file(&file, b"secret").unwrap();
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&private_dir), 0o700);
+        assert_eq!(mode(&file), 0o600);
+        assert_eq!(fs::read(&file).unwrap(), b"secret");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn symlink_is_not_a_private_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target");
+        fs::create_dir(&target).unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        assert_eq!(
+            create_private_dir(&link).unwrap_err().kind(),
+            io::ErrorKind::PermissionDenied
+        );
+    }
+
+    #[test]
+    fn artifact_is_removed_when_dropped() {
+        let artifact = write("image.png", b"image").unwrap();
+        let path = artifact.path().to_path_buf();
+        assert!(path.exists());
+
+        drop(artifact);
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,93 @@
+//! With `--accept-on-select`, wait a little before running the action
+//!
+//! A hint counts down until the action runs. Pressing a key or clicking during the
+//! countdown cancels it, so a slightly-off selection can be adjusted first.
+
+use std::time::{Duration, Instant};
+
+use iced::{
+    Background, Element,
+    Length::Fill,
+    widget::{container, text},
+};
+
+/// Action waiting to run on the accepted selection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AcceptCountdown {
+    /// The action to run
+    pub action: crate::image::action::Command,
+    /// When the action runs
+    pub deadline: Instant,
+}
+
+impl AcceptCountdown {
+    /// Run the `action` after `delay`
+    pub fn new(action: crate::image::action::Command, delay: Duration) -> Self {
+        Self {
+            action,
+            deadline: Instant::now() + delay,
+        }
+    }
+
+    /// How long until the action runs, at `now`
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.deadline.saturating_duration_since(now)
+    }
+
+    /// Show how long until the action runs, and how to cancel it
+    pub fn view(&self, app: &crate::App) -> Element<'_, crate::Message> {
+        /// Space between the hint and the bottom of the screen
+        const MARGIN: f32 = 40.0;
+
+        let hint = container(
+            text(format!(
+                "{} in {:.1}s. Press any key or click to cancel",
+                self.action.description(),
+                self.remaining(Instant::now()).as_secs_f32()
+            ))
+            .font(app.config.theme.font),
+        )
+        .padding([8.0, 16.0])
+        .style(|_| container::Style {
+            text_color: Some(app.config.theme.info_box_fg),
+            background: Some(Background::Color(app.config.theme.info_box_bg)),
+            border: app
+                .config
+                .theme
+                .info_box_border()
+                .color(app.config.theme.info_box_border)
+                .width(1.5),
+            ..Default::default()
+        });
+
+        container(hint)
+            .center_x(Fill)
+            .align_bottom(Fill)
+            .padding(MARGIN)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn remaining() {
+        let countdown = AcceptCountdown::new(
+            crate::image::action::Command::CopyToClipboard,
+            Duration::from_millis(500),
+        );
+
+        assert_eq!(countdown.remaining(countdown.deadline), Duration::ZERO);
+        assert_eq!(
+            countdown.remaining(countdown.deadline + Duration::from_secs(1)),
+            Duration::ZERO
+        );
+        assert_eq!(
+            countdown.remaining(countdown.deadline - Duration::from_millis(200)),
+            Duration::from_millis(200)
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Animations running in the app
+//!
+//! Ferrishot only needs to be redrawn every frame while something animates.
+//! Ticking every frame while idling fullscreen keeps the CPU and GPU busy for nothing,
+//! so the tick subscription is only active while one of these animations runs.
+
+use std::time::Duration;
+
+use crate::App;
+
+/// How long a single pulse of the pre-selected region takes
+const SELECTION_PULSE_PERIOD: Duration = Duration::from_millis(600);
+
+/// How many times the pre-selected region pulses
+const SELECTION_PULSES: u32 = 3;
+
+/// Something which changes every frame, so needs the app to tick
+#[derive(strum::EnumIter, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Animation {
+    /// Spinner shown while the screenshot is being uploaded
+    UploadSpinner,
+    /// Error messages, which disappear after a while
+    Errors,
+    /// Recorded events are replayed at the time they happened
+    Replay,
+    /// The selection is dragged, which is applied once per frame
+    Drag,
+    /// The border of the region selected on startup, like with `--region`,
+    /// pulses to show where it is
+    SelectionPulse,
+    /// Countdown until the action of `--accept-on-select` runs
+    AcceptCountdown,
+}
+
+impl Animation {
+    /// Whether the animation is currently running
+    pub fn is_running(self, app: &App) -> bool {
+        match self {
+            Self::UploadSpinner => app.is_uploading_image,
+            Self::Errors => app.errors.is_visible(&app.config.toasts),
+            Self::Replay => app.replay.is_some(),
+            Self::Drag => {
+                app.pending_drag.is_some() || app.selection.is_some_and(|sel| !sel.is_idle())
+            }
+            Self::SelectionPulse => selection_pulse(app).is_some(),
+            Self::AcceptCountdown => app.accept_countdown.is_some(),
+        }
+    }
+
+    /// Whether any animation is currently running
+    pub fn any_running(app: &App) -> bool {
+        use strum::IntoEnumIterator as _;
+
+        Self::iter().any(|animation| animation.is_running(app))
+    }
+}
+
+/// How far the current pulse of the pre-selected region is, from `0.0` to `1.0`
+///
+/// `None` once the pulses are over, or when nothing was selected on startup
+pub fn selection_pulse(app: &App) -> Option<f32> {
+    let pulses = app.time_elapsed.as_secs_f32() / SELECTION_PULSE_PERIOD.as_secs_f32();
+
+    (app.is_selection_preselected && app.selection.is_some() && pulses < SELECTION_PULSES as f32)
+        .then(|| pulses.fract())
+}
@@ -0,0 +1,426 @@
+//! Arrows, rectangles, freehand lines and text drawn on top of the screenshot
+//!
+//! Shapes are drawn by dragging the mouse in the `annotate` mode, with the tool picked
+//! by `set-annotation-tool` or by the icons around the selection. Text is typed after
+//! clicking where it goes. They are composited onto the captured image by
+//! [`App::process_image`](crate::App::process_image).
+//!
+//! Text uses the bitmap font of the stamp, so it looks exactly the same on the screen
+//! and in the captured image. That font only has uppercase ASCII, so lowercase letters
+//! are drawn uppercase and characters it lacks are drawn as `?`. The `font` of the
+//! theme does not apply to it.
+
+mod raster;
+
+use iced::{Point, Size, Task, Vector, widget::canvas};
+
+pub use raster::composite;
+
+use crate::image::stamp::{GLYPH_HEIGHT, GLYPH_SPACING, GLYPH_WIDTH, text_pixels};
+use crate::ui::mode::Mode;
+
+crate::declare_commands! {
+    enum Command {
+        /// Draw with the tool by dragging the mouse, switching to the `annotate` mode
+        SetAnnotationTool {
+            tool: Tool,
+        },
+        /// Remove all of the drawn shapes
+        ClearAnnotations,
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
+        match self {
+            Self::SetAnnotationTool { tool } => {
+                app.annotations.tool = tool;
+                app.mode = Mode::Annotate;
+            }
+            Self::ClearAnnotations => {
+                app.annotations.shapes.clear();
+                app.annotations.drawing = None;
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// What dragging the mouse draws, in the `annotate` mode
+#[derive(ferrishot_knus::DecodeScalar, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    /// Straight line with a head at the end
+    #[default]
+    Arrow,
+    /// Outline of a rectangle, from one corner to the opposite one
+    Rectangle,
+    /// Freehand line following the cursor
+    Pen,
+    /// Text typed after clicking where it goes
+    Text,
+}
+
+/// A shape drawn on top of the screenshot, in the coordinates of the screenshot
+#[derive(Debug, Clone, PartialEq)]
+pub enum Shape {
+    /// Line from `from`, with the head at `to`
+    Arrow {
+        /// Where the drag started
+        from: Point,
+        /// Where the drag ended, and the arrow points
+        to: Point,
+    },
+    /// Outline of the rectangle between 2 opposite corners
+    Rectangle {
+        /// Where the drag started
+        from: Point,
+        /// Where the drag ended
+        to: Point,
+    },
+    /// Line through every position of the cursor
+    Pen(Vec<Point>),
+    /// Text, rendered with the bitmap font
+    Text {
+        /// Top-left corner of the text
+        position: Point,
+        /// What was typed
+        content: String,
+        /// Each pixel of the font is a `scale` x `scale` square
+        scale: u32,
+    },
+}
+
+impl Shape {
+    /// A shape of the `tool`, which starts at the `point`. Text is about `text_size` pixels tall
+    fn new(tool: Tool, point: Point, text_size: u32) -> Self {
+        match tool {
+            Tool::Arrow => Self::Arrow {
+                from: point,
+                to: point,
+            },
+            Tool::Rectangle => Self::Rectangle {
+                from: point,
+                to: point,
+            },
+            Tool::Pen => Self::Pen(vec![point]),
+            Tool::Text => Self::Text {
+                // so it is on the same pixels in the captured image
+                position: Point::new(point.x.round(), point.y.round()),
+                content: String::new(),
+                scale: (text_size / GLYPH_HEIGHT).max(1),
+            },
+        }
+    }
+
+    /// The cursor moved to `point` while drawing the shape
+    fn extend(&mut self, point: Point) {
+        match self {
+            Self::Arrow { to, .. } | Self::Rectangle { to, .. } => *to = point,
+            Self::Pen(points) => {
+                if points.last() != Some(&point) {
+                    points.push(point);
+                }
+            }
+            // text stays where it was placed
+            Self::Text { .. } => (),
+        }
+    }
+
+    /// The shape would not be visible, like an arrow without any length.
+    /// A single point of the pen is a dot, so it is visible
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Arrow { from, to } | Self::Rectangle { from, to } => from == to,
+            Self::Pen(points) => points.is_empty(),
+            Self::Text { content, .. } => content.trim().is_empty(),
+        }
+    }
+
+    /// Straight lines which make up the shape, when its lines are `width` pixels wide.
+    /// Text has no lines
+    pub fn segments(&self, width: f32) -> Vec<(Point, Point)> {
+        match self {
+            Self::Arrow { from, to } => {
+                let direction = *to - *from;
+                let length = direction.x.hypot(direction.y);
+                if length == 0.0 {
+                    return vec![(*from, *to)];
+                }
+
+                // the head gets larger with thicker lines, but is never longer than the arrow
+                let head = (width * 4.0).max(10.0).min(length);
+                let back = Vector::new(direction.x / length, direction.y / length) * -head;
+                // `back` rotated by the `angle`, from the tip
+                let side = |angle: f32| {
+                    let (sin, cos) = angle.sin_cos();
+                    *to + Vector::new(back.x * cos - back.y * sin, back.x * sin + back.y * cos)
+                };
+                // sides of the head are 30 degrees away from the line
+                let angle = std::f32::consts::FRAC_PI_6;
+
+                vec![(*from, *to), (*to, side(angle)), (*to, side(-angle))]
+            }
+            Self::Rectangle { from, to } => {
+                let top_right = Point::new(to.x, from.y);
+                let bottom_left = Point::new(from.x, to.y);
+
+                vec![
+                    (*from, top_right),
+                    (top_right, *to),
+                    (*to, bottom_left),
+                    (bottom_left, *from),
+                ]
+            }
+            Self::Pen(points) => match points.as_slice() {
+                [point] => vec![(*point, *point)],
+                points => points.windows(2).map(|pair| (pair[0], pair[1])).collect(),
+            },
+            Self::Text { .. } => vec![],
+        }
+    }
+
+    /// Squares which make up the text, as their top-left corners and the length of
+    /// their sides. Other shapes have none
+    pub fn text_squares(&self) -> impl Iterator<Item = (Point, f32)> + '_ {
+        let (position, content, scale) = match self {
+            Self::Text {
+                position,
+                content,
+                scale,
+            } => (*position, content.as_str(), *scale),
+            _ => (Point::ORIGIN, "", 1),
+        };
+
+        text_pixels(content, scale)
+            .map(move |(x, y)| (position + Vector::new(x as f32, y as f32), scale as f32))
+    }
+}
+
+/// How the shapes look
+#[derive(Debug, Clone, Copy)]
+pub struct Style {
+    /// Color of the lines
+    pub color: iced::Color,
+    /// Width of the lines
+    pub width: f32,
+    /// Color of the text
+    pub text_color: iced::Color,
+}
+
+impl From<&crate::Theme> for Style {
+    fn from(theme: &crate::Theme) -> Self {
+        Self {
+            color: theme.annotation,
+            width: theme.annotation_width as f32,
+            text_color: theme.annotation_text,
+        }
+    }
+}
+
+/// Shapes drawn on top of the screenshot
+#[derive(Debug, Default)]
+pub struct Annotations {
+    /// Finished shapes, in the order they were drawn
+    pub shapes: Vec<Shape>,
+    /// What dragging the mouse draws
+    pub tool: Tool,
+    /// The shape being drawn right now, while the mouse is held
+    pub drawing: Option<Shape>,
+}
+
+/// Annotations message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// The left mouse button was pressed at this position, to start drawing
+    Start(Point),
+    /// The cursor moved to this position while drawing
+    Move(Point),
+    /// The left mouse button was released, or typing the text is done,
+    /// so the shape is finished
+    Finish,
+    /// This was typed into the text
+    Type(String),
+    /// Remove the last character of the text
+    Backspace,
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        let annotations = &mut app.annotations;
+
+        match self {
+            Self::Start(point) => {
+                // like text which was still being typed
+                if let Some(shape) = annotations.drawing.take().filter(|shape| !shape.is_empty()) {
+                    annotations.shapes.push(shape);
+                }
+
+                annotations.drawing = Some(Shape::new(
+                    annotations.tool,
+                    point,
+                    app.config.theme.annotation_text_size,
+                ));
+            }
+            Self::Move(point) => {
+                if let Some(shape) = &mut annotations.drawing {
+                    shape.extend(point);
+                }
+            }
+            Self::Finish => {
+                if let Some(shape) = annotations.drawing.take().filter(|shape| !shape.is_empty()) {
+                    annotations.shapes.push(shape);
+                }
+            }
+            Self::Type(text) => {
+                if let Some(Shape::Text { content, .. }) = &mut annotations.drawing {
+                    content.push_str(&text);
+                }
+            }
+            Self::Backspace => {
+                if let Some(Shape::Text { content, .. }) = &mut annotations.drawing {
+                    content.pop();
+                }
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// Draw the shapes, including the one being drawn
+pub fn draw(frame: &mut canvas::Frame, app: &crate::App) {
+    let style = Style::from(&app.config.theme);
+
+    let stroke = canvas::Stroke::default()
+        .with_color(style.color)
+        .with_width(style.width)
+        .with_line_cap(canvas::LineCap::Round)
+        .with_line_join(canvas::LineJoin::Round);
+
+    for shape in app
+        .annotations
+        .shapes
+        .iter()
+        .chain(&app.annotations.drawing)
+    {
+        let path = canvas::Path::new(|builder| {
+            for (from, to) in shape.segments(style.width) {
+                builder.move_to(from);
+                builder.line_to(to);
+            }
+        });
+
+        frame.stroke(&path, stroke);
+
+        for (square, side) in shape.text_squares() {
+            frame.fill_rectangle(square, Size::new(side, side), style.text_color);
+        }
+    }
+
+    // the cursor after the text which is being typed
+    if let Some(Shape::Text {
+        position,
+        content,
+        scale,
+    }) = &app.annotations.drawing
+    {
+        // where the next character goes
+        let chars = content.chars().count() as u32;
+        let caret = Point::new(
+            position.x + (chars * (GLYPH_WIDTH + GLYPH_SPACING) * scale) as f32,
+            position.y,
+        );
+
+        frame.fill_rectangle(
+            caret,
+            Size::new(*scale as f32, (GLYPH_HEIGHT * scale) as f32),
+            style.text_color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn rectangle_is_made_of_4_edges() {
+        let shape = Shape::Rectangle {
+            from: Point::new(10.0, 10.0),
+            to: Point::new(30.0, 20.0),
+        };
+
+        assert_eq!(
+            shape.segments(2.0),
+            [
+                (Point::new(10.0, 10.0), Point::new(30.0, 10.0)),
+                (Point::new(30.0, 10.0), Point::new(30.0, 20.0)),
+                (Point::new(30.0, 20.0), Point::new(10.0, 20.0)),
+                (Point::new(10.0, 20.0), Point::new(10.0, 10.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn head_of_the_arrow_points_back() {
+        let shape = Shape::Arrow {
+            from: Point::ORIGIN,
+            to: Point::new(100.0, 0.0),
+        };
+        let segments = shape.segments(5.0);
+
+        assert_eq!(segments.len(), 3);
+        for (tip, end) in &segments[1..] {
+            assert_eq!(*tip, Point::new(100.0, 0.0));
+            assert!(end.x < 100.0);
+        }
+        // the sides of the head are on both sides of the line
+        assert!(segments[1].1.y * segments[2].1.y < 0.0);
+    }
+
+    #[test]
+    fn empty_shapes_are_not_kept() {
+        let mut shape = Shape::new(Tool::Arrow, Point::new(5.0, 5.0), 14);
+        assert!(shape.is_empty());
+
+        shape.extend(Point::new(6.0, 5.0));
+        assert!(!shape.is_empty());
+
+        // a click with the pen is a dot
+        assert!(!Shape::new(Tool::Pen, Point::ORIGIN, 14).is_empty());
+    }
+
+    #[test]
+    fn pen_skips_repeated_points() {
+        let mut shape = Shape::new(Tool::Pen, Point::ORIGIN, 14);
+        shape.extend(Point::ORIGIN);
+        shape.extend(Point::new(1.0, 1.0));
+
+        assert_eq!(shape, Shape::Pen(vec![Point::ORIGIN, Point::new(1.0, 1.0)]));
+    }
+
+    #[test]
+    fn text_is_scaled_to_the_size() {
+        let shape = Shape::new(Tool::Text, Point::new(10.0, 20.0), 21);
+        assert!(shape.is_empty());
+        assert!(shape.segments(4.0).is_empty());
+
+        let Shape::Text { scale, .. } = shape else {
+            panic!("the text tool makes text");
+        };
+        assert_eq!(scale, 3);
+
+        let text = Shape::Text {
+            position: Point::new(10.0, 20.0),
+            content: "1".to_string(),
+            scale,
+        };
+        // the top row of `1` only has the middle pixel
+        assert_eq!(
+            text.text_squares().next(),
+            Some((Point::new(16.0, 20.0), 3.0))
+        );
+    }
+}
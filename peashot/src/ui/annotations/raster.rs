@@ -0,0 +1,180 @@
+//! Draw the shapes onto the captured image
+//!
+//! Every segment of a shape is a line with round ends. Pixels covered by any of the lines
+//! are collected first, so where the lines overlap the color is not blended twice.
+//! Text is drawn on top of the lines
+
+use iced::{Point, Vector};
+use image::RgbaImage;
+
+use super::{Shape, Style};
+
+/// Distance from the `point` to the closest point of the segment between `from` and `to`
+fn distance_to_segment(point: Point, from: Point, to: Point) -> f32 {
+    let segment = to - from;
+    let length_squared = segment.x.mul_add(segment.x, segment.y * segment.y);
+
+    // how far along the segment the closest point is, from 0 to 1
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        let to_point = point - from;
+        (to_point.x.mul_add(segment.x, to_point.y * segment.y) / length_squared).clamp(0.0, 1.0)
+    };
+
+    point.distance(from + segment * t)
+}
+
+/// Draw the `shapes` onto the `image`, which was cropped out of the screenshot at `origin`
+pub fn composite(image: &mut RgbaImage, origin: Point, shapes: &[Shape], style: Style) {
+    let (image_width, image_height) = image.dimensions();
+    let radius = style.width.max(1.0) / 2.0;
+    let offset = Vector::new(origin.x, origin.y);
+
+    let mut is_covered = vec![false; image_width as usize * image_height as usize];
+
+    for (from, to) in shapes.iter().flat_map(|shape| shape.segments(style.width)) {
+        let (from, to) = (from - offset, to - offset);
+
+        // only the pixels around the segment can be covered by it
+        let x_range = from.x.min(to.x) - radius..from.x.max(to.x) + radius;
+        let y_range = from.y.min(to.y) - radius..from.y.max(to.y) + radius;
+
+        let xs =
+            x_range.start.max(0.0) as u32..(x_range.end.ceil().max(0.0) as u32).min(image_width);
+        let ys =
+            y_range.start.max(0.0) as u32..(y_range.end.ceil().max(0.0) as u32).min(image_height);
+
+        for y in ys {
+            for x in xs.clone() {
+                // the center of the pixel
+                let pixel = Point::new(x as f32 + 0.5, y as f32 + 0.5);
+
+                if distance_to_segment(pixel, from, to) <= radius {
+                    is_covered[(y * image_width + x) as usize] = true;
+                }
+            }
+        }
+    }
+
+    for (pixel, _) in image
+        .pixels_mut()
+        .zip(&is_covered)
+        .filter(|(_, covered)| **covered)
+    {
+        crate::image::stamp::blend(pixel, style.color);
+    }
+
+    for (square, side) in shapes.iter().flat_map(Shape::text_squares) {
+        let square = square - offset;
+        let side = side as u32;
+
+        // squares are never partially covered, as text is placed at whole pixels
+        let (x, y) = (square.x.round(), square.y.round());
+        if x < 0.0 || y < 0.0 {
+            continue;
+        }
+        let (x, y) = (x as u32, y as u32);
+
+        for y in y..(y + side).min(image_height) {
+            for x in x..(x + side).min(image_width) {
+                crate::image::stamp::blend(image.get_pixel_mut(x, y), style.text_color);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const WHITE: image::Rgba<u8> = image::Rgba([0xff, 0xff, 0xff, 0xff]);
+    const RED: image::Rgba<u8> = image::Rgba([0xff, 0x00, 0x00, 0xff]);
+
+    const BLUE: image::Rgba<u8> = image::Rgba([0x00, 0x00, 0xff, 0xff]);
+
+    fn composite_onto_white(origin: Point, shapes: &[Shape]) -> RgbaImage {
+        let mut image = RgbaImage::from_pixel(50, 50, WHITE);
+        composite(
+            &mut image,
+            origin,
+            shapes,
+            Style {
+                color: iced::Color::from_rgb8(0xff, 0x00, 0x00),
+                width: 4.0,
+                text_color: iced::Color::from_rgb8(0x00, 0x00, 0xff),
+            },
+        );
+        image
+    }
+
+    #[test]
+    fn distance_to_the_closest_point() {
+        let (from, to) = (Point::new(0.0, 0.0), Point::new(10.0, 0.0));
+
+        assert_eq!(distance_to_segment(Point::new(5.0, 3.0), from, to), 3.0);
+        // past the end of the segment
+        assert_eq!(distance_to_segment(Point::new(14.0, 3.0), from, to), 5.0);
+        // segment which is a single point
+        assert_eq!(distance_to_segment(Point::new(3.0, 4.0), from, from), 5.0);
+    }
+
+    #[test]
+    fn rectangle_is_an_outline() {
+        let image = composite_onto_white(
+            Point::ORIGIN,
+            &[Shape::Rectangle {
+                from: Point::new(10.0, 10.0),
+                to: Point::new(40.0, 40.0),
+            }],
+        );
+
+        assert_eq!(*image.get_pixel(10, 25), RED);
+        assert_eq!(*image.get_pixel(25, 39), RED);
+        assert_eq!(*image.get_pixel(25, 25), WHITE);
+        assert_eq!(*image.get_pixel(2, 2), WHITE);
+    }
+
+    #[test]
+    fn shapes_are_moved_by_the_origin_of_the_crop() {
+        let image = composite_onto_white(
+            Point::new(100.0, 100.0),
+            &[Shape::Pen(vec![Point::new(120.0, 110.0)])],
+        );
+
+        assert_eq!(*image.get_pixel(20, 10), RED);
+        assert_eq!(*image.get_pixel(30, 30), WHITE);
+    }
+
+    #[test]
+    fn shapes_outside_of_the_image_are_clipped() {
+        let image = composite_onto_white(
+            Point::ORIGIN,
+            &[Shape::Arrow {
+                from: Point::new(-100.0, 25.0),
+                to: Point::new(25.0, 25.0),
+            }],
+        );
+
+        assert_eq!(*image.get_pixel(0, 25), RED);
+        assert_eq!(*image.get_pixel(40, 25), WHITE);
+    }
+
+    #[test]
+    fn text_is_drawn_in_its_color() {
+        let image = composite_onto_white(
+            Point::new(100.0, 100.0),
+            &[Shape::Text {
+                position: Point::new(110.0, 120.0),
+                content: "1".to_string(),
+                scale: 2,
+            }],
+        );
+
+        // the top row of `1` only has the middle pixel
+        assert_eq!(*image.get_pixel(14, 20), BLUE);
+        assert_eq!(*image.get_pixel(15, 21), BLUE);
+        assert_eq!(*image.get_pixel(10, 20), WHITE);
+    }
+}
@@ -1,5 +1,6 @@
 //! Main logic for the application, handling of events and mutation of the state
 
+use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -9,7 +10,9 @@ use crate::Cli;
 use crate::Config;
 use crate::image::RgbaHandle;
 use crate::image::action::ImageData;
+use crate::image::stamp::Stamp;
 use crate::message::Message;
+use crate::replay::{RecordedEvent, Recorder, Replay};
 use crate::ui;
 use crate::ui::popup;
 use iced::Length::Fill;
@@ -22,7 +25,7 @@ use iced::widget::Canvas;
 use iced::widget::Stack;
 use iced::window;
 use iced::{
-    Rectangle,
+    Point, Rectangle, Size,
     widget::{Action, canvas},
 };
 use image::DynamicImage;
@@ -41,16 +44,42 @@ crate::declare_commands! {
     enum Command {
         /// Do nothing
         NoOp,
+        /// Remove the keybinding of the key, even one of the default config.
+        /// Inside of a `mode`, only the keybinding of that mode is removed
+        Unbind,
         /// Exit the application
         Exit,
+        /// Run the `primary-action` on the selection
+        Accept,
+        /// Undo one step at a time: close the popup, then clear the selection, then exit
+        SmartEscape,
     }
 }
 
 impl crate::command::Handler for Command {
-    fn handle(self, _app: &mut App, _count: u32) -> Task<Message> {
+    fn handle(self, app: &mut App, count: u32) -> Task<Message> {
         match self {
-            Self::NoOp => Task::none(),
-            Self::Exit => App::exit(),
+            Self::NoOp | Self::Unbind => Task::none(),
+            Self::Accept => crate::command::Handler::handle(app.config.primary_action, app, count),
+            Self::SmartEscape => {
+                if app.popup.is_some() {
+                    app.popup = None;
+                    Task::none()
+                } else if app.selection.is_some() {
+                    app.selection = None;
+                    Task::none()
+                } else {
+                    App::exit()
+                }
+            }
+            Self::Exit => {
+                if app.config.confirm_exit && app.selection.is_some() && app.popup.is_none() {
+                    app.popup = Some(Popup::ConfirmExit);
+                    Task::none()
+                } else {
+                    App::exit()
+                }
+            }
         }
     }
 }
@@ -63,11 +92,13 @@ pub struct App {
     /// When the application was launched
     pub time_started: Instant,
     /// How long has passed since starting ferrishot
+    ///
+    /// Only updated while an animation is running
     pub time_elapsed: Duration,
     /// Config of the app
     pub config: Arc<Config>,
     /// A list of messages which obtained while the debug overlay is active
-    pub logged_messages: Vec<Message>,
+    pub logged_messages: VecDeque<Message>,
     /// How many selections were created throughout the
     /// lifetime of the App
     pub selections_created: usize,
@@ -82,11 +113,63 @@ pub struct App {
     pub errors: Errors,
     /// Whether to show an overlay with additional information (F12)
     pub show_debug_overlay: bool,
+    /// State of the debug overlay
+    pub debug_overlay: ui::debug_overlay::State,
     /// Command line arguments passed
     pub cli: Arc<Cli>,
 
     /// Currently opened popup
     pub popup: Option<Popup>,
+    /// Whether system information will be stamped onto the captured image
+    pub is_stamp_enabled: bool,
+    /// Safe area guide shown inside of the selection
+    pub guide: ui::guides::Guide,
+    /// Editing the width or height in the size indicator keeps this aspect ratio, the width
+    /// divided by the height of the selection when it was linked
+    pub linked_aspect_ratio: Option<f32>,
+    /// Arithmetic typed into the size indicator, evaluated on Enter
+    pub size_indicator_draft: Option<ui::size_indicator::Draft>,
+    /// Corner of the selection which stays in place when resizing it with the keyboard
+    pub resize_anchor: crate::geometry::Corner,
+    /// The anchor was moved with `cycle-resize-anchor`, so `extend` and `shrink` keep it
+    /// in place too, and it is marked on the selection
+    pub is_resize_anchor_active: bool,
+    /// Crop suggested for the selection, shown until it is accepted or the selection changes
+    pub crop_suggestion: Option<ui::crop_suggestion::Suggestion>,
+    /// Outlines of the recently used regions, shown while nothing is selected
+    pub recent_regions: ui::recent_regions::RecentRegions,
+    /// Why the clipboard can't be used, if it can't. Copying is disabled then
+    pub clipboard_error: Option<String>,
+    /// Whether the app started with a selection, like with `--region`. Its border pulses
+    /// for a moment, so it is easy to spot
+    pub is_selection_preselected: bool,
+    /// Current mode, which decides the active keybindings
+    pub mode: ui::mode::Mode,
+    /// State of the panel with the histogram of the selection
+    pub histogram: ui::histogram::State,
+    /// Last position of the cursor in the `magnify` mode, where the magnifier is shown
+    pub magnifier: Option<Point>,
+    /// Line dragged in the `measure` mode, labeled with its length
+    pub ruler: Option<ui::measure::Ruler>,
+    /// Arrows, rectangles and freehand lines drawn on top of the screenshot
+    pub annotations: ui::annotations::Annotations,
+    /// Changes to the selection and the annotations, which can be undone
+    pub history: ui::history::History,
+    /// Frames of the animated image which is edited, `image` is one of them
+    pub frames: Option<ui::frames::Frames>,
+    /// Input events are written here, with `--record`
+    pub recorder: Option<Recorder>,
+    /// Recorded input events which are being replayed, with `--replay`
+    pub replay: Option<Replay>,
+    /// The latest drag of the selection which was not applied yet
+    ///
+    /// High polling rate mice move the cursor many times per frame. Only the latest
+    /// of these is applied once per frame
+    pub pending_drag: Option<Box<ui::selection::Message>>,
+    /// When the user last pressed a key or used the mouse, for `idle-timeout`
+    pub last_interaction: Instant,
+    /// Action of `--accept-on-select` which runs once `accept-on-select-delay` passes
+    pub accept_countdown: Option<ui::accept_countdown::AcceptCountdown>,
 }
 
 #[bon::bon]
@@ -100,12 +183,29 @@ impl App {
         region: Rectangle,
         image: Arc<RgbaHandle>,
         is_json: bool,
+        config: Arc<Config>,
     ) -> Result<Box<dyn Fn(Option<PathBuf>) -> String>, crate::image::action::Error> {
+        use crate::image::action::JsonOutput;
         use crate::image::action::Output as O;
 
         let (output, ImageData { height, width }) = image
-            .pipe(|img| Self::process_image(region, &img))
-            .pipe(|img| action.execute(img, region))
+            .pipe(|img| {
+                Self::process_image(
+                    region,
+                    &img,
+                    &[],
+                    config.stamp.enabled.then_some(&config.stamp),
+                    &config.theme,
+                )
+            })
+            .pipe(|img| {
+                action.execute(
+                    img,
+                    region,
+                    config.max_upload_size,
+                    config.skip_duplicate_captures,
+                )
+            })
             .await?;
 
         let green = anstyle::AnsiColor::Green
@@ -131,18 +231,14 @@ impl App {
                 let file_size = human_bytes::human_bytes(file_size_bytes as f64);
 
                 if is_json {
-                    formatdoc! {
-                        r#"
-                            {{
-                                "type": "save",
-                                "width": {width},
-                                "height": {height},
-                                "fileSize": "{file_size}",
-                                "fileSizeInBytes": {file_size_bytes},
-                                "savePath": "{save_path}"
-                            }}
-                        "#
+                    JsonOutput::Save {
+                        width,
+                        height,
+                        file_size,
+                        file_size_in_bytes: file_size_bytes,
+                        save_path,
                     }
+                    .to_string()
                 } else {
                     formatdoc! {
                         "
@@ -157,15 +253,7 @@ impl App {
             }),
             O::Copied => Box::new(move |_| {
                 if is_json {
-                    formatdoc! {
-                        r#"
-                            {{
-                                "type": "copy",
-                                "width": {width},
-                                "height": {height},
-                            }}
-                        "#
-                    }
+                    JsonOutput::Copy { width, height }.to_string()
                 } else {
                     formatdoc! {
                         "
@@ -187,19 +275,15 @@ impl App {
                 let file_size = human_bytes::human_bytes(file_size_bytes as f64);
 
                 if is_json {
-                    formatdoc! {
-                        r#"
-                            {{
-                                "type": "upload",
-                                "width": {width},
-                                "height": {height},
-                                "fileSize": "{file_size}",
-                                "fileSizeInBytes": {file_size_bytes},
-                                "link": "{link}",
-                                "expiresIn": "{expires}"
-                            }}
-                        "#
+                    JsonOutput::Upload {
+                        width,
+                        height,
+                        file_size,
+                        file_size_in_bytes: file_size_bytes,
+                        link,
+                        expires_in: expires,
                     }
+                    .to_string()
                 } else {
                     formatdoc! {
                         "
@@ -225,10 +309,41 @@ impl App {
         config: Arc<Config>,
         initial_region: Option<Rectangle>,
         image: Arc<RgbaHandle>,
+        frames: Option<Vec<RgbaHandle>>,
+        replay: Option<Replay>,
     ) -> Self {
+        let mut errors = Errors::default();
+
+        for conflict in &config.keys.conflicts {
+            errors.warn(conflict.to_string());
+        }
+
+        let recorder = cli.record.as_ref().and_then(|path| {
+            Recorder::create(path, &image)
+                .map_err(|err| errors.push(format!("Failed to start recording: {err}")))
+                .ok()
+        });
+
+        // nothing can be copied in `--present`, so the clipboard does not matter there
+        let clipboard_error = (!cli.present)
+            .then(crate::clipboard::check)
+            .and_then(Result::err)
+            .map(|err| {
+                let err = err.to_string();
+                errors.push(format!(
+                    "The clipboard is unavailable, save or upload the screenshot instead: {err}"
+                ));
+                err
+            });
+
+        let recent_regions =
+            ui::recent_regions::RecentRegions::read(image.bounds(), config.recent_regions as usize);
+
         Self {
             is_uploading_image: false,
             time_started: Instant::now(),
+            last_interaction: Instant::now(),
+            accept_countdown: None,
             time_elapsed: Duration::ZERO,
             selection: initial_region.map(|rect| Selection {
                 is_first: true,
@@ -237,19 +352,61 @@ impl App {
                 rect,
                 status: ui::selection::SelectionStatus::default(),
             }),
-            logged_messages: vec![],
+            logged_messages: VecDeque::new(),
             selections_created: 0,
             // FIXME: Currently the app cannot handle when the resolution is very small
             // if a path was passed and the path contains a valid image
             image,
-            errors: Errors::default(),
+            errors,
             show_debug_overlay: cli.debug,
+            debug_overlay: ui::debug_overlay::State::default(),
+            is_stamp_enabled: config.stamp.enabled,
+            guide: config.guide,
+            resize_anchor: config.resize_anchor,
+            is_resize_anchor_active: false,
+            linked_aspect_ratio: None,
+            size_indicator_draft: None,
+            crop_suggestion: None,
+            recent_regions,
+            clipboard_error,
+            is_selection_preselected: initial_region.is_some(),
+            mode: ui::mode::Mode::default(),
+            histogram: ui::histogram::State::default(),
+            magnifier: None,
+            ruler: None,
+            annotations: ui::annotations::Annotations::default(),
+            history: ui::history::History::default(),
+            frames: frames.and_then(ui::frames::Frames::new),
+            recorder,
+            replay,
+            pending_drag: None,
             config,
             cli,
             popup: None,
         }
     }
 
+    /// Run the commands of `on-start` in order
+    ///
+    /// Nothing can be captured in `--present`, so the commands do not run there
+    pub fn on_start(&self) -> Task<Message> {
+        if self.cli.present {
+            return Task::none();
+        }
+
+        self.config
+            .on_start
+            .commands
+            .iter()
+            .cloned()
+            .fold(Task::none(), |task, command| {
+                task.chain(Task::done(Message::Command {
+                    action: command.into_command(),
+                    count: 1,
+                }))
+            })
+    }
+
     /// Close the app
     ///
     /// This is like `iced::exit`, but it does not cause a segfault in special
@@ -264,12 +421,113 @@ impl App {
 
     /// This method is used to keep track of time / how much time has passed since start
     /// of the program, using this for animations.
+    ///
+    /// Frames are only listened to while an animation is running, otherwise ferrishot
+    /// would be redrawn every frame even when it is idle
     pub fn subscription(&self) -> Subscription<Message> {
-        window::frames().map(Message::Tick)
+        let frames = if ui::animation::Animation::any_running(self) {
+            window::frames().map(Message::Tick)
+        } else {
+            Subscription::none()
+        };
+
+        let mut subscriptions = vec![frames];
+
+        if self.recorder.is_some() {
+            subscriptions.push(iced::event::listen_with(|event, _status, _window| {
+                RecordedEvent::from_iced(&event).map(Message::Record)
+            }));
+        }
+
+        if self.config.control_socket {
+            subscriptions.push(Subscription::run(crate::control::listen));
+        }
+
+        if self.idle_timeout().is_some() {
+            subscriptions.push(iced::time::every(IDLE_CHECK_INTERVAL).map(Message::IdleCheck));
+            subscriptions.push(iced::event::listen_with(|event, _status, _window| {
+                matches!(event, iced::Event::Keyboard(_) | iced::Event::Mouse(_))
+                    .then_some(Message::Interaction)
+            }));
+        }
+
+        Subscription::batch(subscriptions)
+    }
+
+    /// Exit after this long without the user interacting with ferrishot.
+    /// `--timeout` takes priority over the `idle-timeout` option, and `0` disables it
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        Some(self.cli.timeout.unwrap_or(self.config.idle_timeout))
+            .filter(|&secs| secs != 0)
+            .map(|secs| Duration::from_secs(secs.into()))
+    }
+
+    /// Title of the window, which follows the selection with `selection-in-title`
+    pub fn title(&self) -> String {
+        let title = &self.cli.window_title;
+
+        match self.selection.filter(|_| self.config.selection_in_title) {
+            Some(sel) => format!("{title} {}", sel.rect.norm().as_str()),
+            None => title.clone(),
+        }
+    }
+
+    /// Keys of a sequence, like `gg`, must be pressed within this long of each other.
+    /// `0` in the `key-sequence-timeout` option means there is no limit
+    fn key_sequence_timeout(&self) -> Option<Duration> {
+        Some(self.config.key_sequence_timeout)
+            .filter(|&millis| millis != 0)
+            .map(|millis| Duration::from_millis(millis.into()))
+    }
+
+    /// Feed the recorded events which are due through the canvas, as if they
+    /// were received from the user
+    fn replay_events(&mut self) -> Task<Message> {
+        let Some(mut replay) = self.replay.take() else {
+            return Task::none();
+        };
+
+        let mut messages = vec![];
+
+        while let Some(event) = replay.next_due(self.time_elapsed) {
+            let cursor = replay
+                .cursor
+                .map_or(iced::advanced::mouse::Cursor::Unavailable, |pos| {
+                    iced::advanced::mouse::Cursor::Available(pos)
+                });
+
+            let action = <Self as canvas::Program<Message>>::update(
+                self,
+                &mut replay.state,
+                &event,
+                self.image.bounds(),
+                cursor,
+            );
+
+            if let Some((Some(message), _, _)) = action.map(Action::into_inner) {
+                messages.push(message);
+            }
+        }
+
+        if replay.is_finished() {
+            log::info!("Finished replaying the recording");
+        } else {
+            self.replay = Some(replay);
+        }
+
+        Task::batch(messages.into_iter().map(Task::done))
     }
 
     /// Renders the app
     pub fn view(&self) -> iced::Element<Message> {
+        if self.cli.present {
+            return ui::present::Presentation {
+                image: &self.image,
+                theme: &self.config.theme,
+            }
+            .view();
+        }
+
         Stack::new()
             // taken screenshot in the background
             .push(super::BackgroundImage {
@@ -284,6 +542,18 @@ impl App {
             )
             // errors
             .push(self.errors.view(self))
+            // current mode
+            .push_maybe(ui::mode::mode_indicator(self))
+            // countdown until the action of `--accept-on-select` runs
+            .push_maybe(
+                self.accept_countdown
+                    .as_ref()
+                    .map(|countdown| countdown.view(self)),
+            )
+            // histogram of the selection
+            .push_maybe(ui::histogram::histogram(self))
+            // frame of the animated image
+            .push_maybe(ui::frames::scrub_bar(self))
             // icons around the selection
             .push_maybe(
                 self.selection
@@ -311,7 +581,7 @@ impl App {
                 match popup {
                     Popup::Letters(state) => popup::Letters {
                         app: self,
-                        pick_corner: state.picking_corner,
+                        target: state.target,
                     }
                     .view(),
                     Popup::ImageUploaded(state) => popup::ImageUploaded {
@@ -323,6 +593,45 @@ impl App {
                     .view(),
                     Popup::KeyCheatsheet => popup::KeybindingsCheatsheet {
                         theme: &self.config.theme,
+                        primary_action: self.config.primary_action,
+                    }
+                    .view(),
+                    Popup::ConfirmExit => popup::ConfirmExit {
+                        theme: &self.config.theme,
+                        keys: &self.config.keys,
+                    }
+                    .view(),
+                    Popup::Guides => popup::Guides {
+                        theme: &self.config.theme,
+                        current: self.guide,
+                    }
+                    .view(),
+                    Popup::ConfirmPreview(state) => popup::ConfirmPreview {
+                        theme: &self.config.theme,
+                        keys: &self.config.keys,
+                        state,
+                    }
+                    .view(),
+                    Popup::Palette(state) => popup::Palette {
+                        theme: &self.config.theme,
+                        state,
+                    }
+                    .view(),
+                    Popup::Help(state) => popup::Help {
+                        theme: &self.config.theme,
+                        state,
+                    }
+                    .view(),
+                    Popup::MoreIcons { first } => popup::MoreIcons {
+                        app: self,
+                        first: *first,
+                    }
+                    .view(),
+                    Popup::RadialMenu(state) => popup::RadialMenu { app: self, state }.view(),
+                    Popup::UploadTooLarge(state) => popup::UploadTooLarge {
+                        theme: &self.config.theme,
+                        keys: &self.config.keys,
+                        state,
                     }
                     .view(),
                 }
@@ -332,29 +641,123 @@ impl App {
             .into()
     }
 
-    /// Convert the image into its final form, with crop (and in the future will also have
-    /// "decorations" such as arrow, circle, square)
+    /// Convert the image into its final form, with crop, the drawn `annotations` and
+    /// optionally the system information stamp
     ///
     /// # Panics
     ///
     /// The stored image is not a valid RGBA image
-    pub fn process_image(rect: Rectangle, image: &RgbaHandle) -> DynamicImage {
-        DynamicImage::from(
-            image::RgbaImage::from_raw(image.width(), image.height(), image.bytes().to_vec())
-                .expect("Image handle stores a valid image"),
-        )
-        .crop_imm(
+    pub fn process_image(
+        rect: Rectangle,
+        image: &RgbaHandle,
+        annotations: &[ui::annotations::Shape],
+        stamp: Option<&Stamp>,
+        theme: &crate::Theme,
+    ) -> DynamicImage {
+        // only the selected region is copied out of the shared pixels
+        let image = image::GenericImageView::view(
+            &image.as_image(),
             rect.x as u32,
             rect.y as u32,
             rect.width as u32,
             rect.height as u32,
         )
+        .to_image()
+        .pipe(DynamicImage::from);
+
+        if annotations.is_empty() && stamp.is_none() {
+            return image;
+        }
+
+        let mut image = image.into_rgba8();
+        ui::annotations::composite(&mut image, rect.top_left(), annotations, theme.into());
+        if let Some(stamp) = stamp {
+            stamp.draw(&mut image, theme);
+        }
+        DynamicImage::from(image)
     }
 
     /// Modifies the app's state
     pub fn update(&mut self, message: Message) -> Task<Message> {
         use crate::message::Handler as _;
 
+        // ticks happen every frame, they would drown out everything else
+        if self.show_debug_overlay
+            && !self.debug_overlay.is_paused
+            && !matches!(
+                message,
+                Message::Tick(_)
+                    | Message::DebugOverlay(_)
+                    | Message::Record(_)
+                    | Message::Interaction
+                    | Message::IdleCheck(_)
+            )
+        {
+            if self.logged_messages.len() >= ui::debug_overlay::MAX_LOGGED_MESSAGES {
+                self.logged_messages.pop_front();
+            }
+            self.logged_messages.push_back(message.clone());
+        }
+
+        // these happen all the time, and never change what can be undone
+        let is_frequent = matches!(
+            message,
+            Message::Tick(_) | Message::Record(_) | Message::Interaction | Message::IdleCheck(_)
+        );
+
+        if !is_frequent {
+            crate::crash_report::record_message(&message);
+        }
+
+        // pressing a key or clicking during the countdown cancels the action, and nothing else
+        if self.accept_countdown.is_some()
+            && matches!(
+                message,
+                Message::Command { .. } | Message::CommandInRegion { .. } | Message::Selection(_)
+            )
+        {
+            log::info!("Cancelled the action of accept-on-select");
+            self.accept_countdown = None;
+            // releasing the mouse after this click must not start the countdown again
+            if let Some(selection) = &mut self.selection {
+                selection.accept_on_select = None;
+            }
+            return Task::none();
+        }
+
+        let task = match message {
+            // drags of the selection are coalesced, and applied on the next frame
+            Message::Selection(selection) if selection.is_drag() => {
+                let flushed = match self.pending_drag.take() {
+                    Some(pending) if !selection.supersedes(&pending) => pending.handle(self),
+                    _ => Task::none(),
+                };
+                self.pending_drag = Some(selection);
+
+                flushed
+            }
+            message => {
+                // anything else could depend on the drag, so it must be applied first
+                let flushed = self
+                    .pending_drag
+                    .take()
+                    .map_or_else(Task::none, |pending| pending.handle(self));
+
+                Task::batch([flushed, self.dispatch(message)])
+            }
+        };
+
+        if !is_frequent && let Some(snapshot) = ui::history::Snapshot::of(self) {
+            self.history.record(snapshot);
+        }
+
+        task
+    }
+
+    /// Pass the message to whatever handles it
+    fn dispatch(&mut self, message: Message) -> Task<Message> {
+        use crate::message::Handler as _;
+
         match message {
             Message::Exit => return Self::exit(),
             Message::ClosePopup => {
@@ -362,10 +765,58 @@ impl App {
             }
             Message::Tick(instant) => {
                 self.time_elapsed = instant.duration_since(self.time_started);
+
+                let accepted = self
+                    .accept_countdown
+                    .take_if(|countdown| countdown.remaining(instant).is_zero())
+                    .map_or_else(Task::none, |countdown| {
+                        Task::done(Message::Command {
+                            action: countdown.action.into_key_action(),
+                            count: 1,
+                        })
+                    });
+
+                return Task::batch([self.replay_events(), accepted]);
+            }
+            Message::AcceptOnSelect(action) => {
+                let delay = Duration::from_millis(self.config.accept_on_select_delay.into());
+
+                if delay.is_zero() {
+                    return Task::done(Message::Command {
+                        action: action.into_key_action(),
+                        count: 1,
+                    });
+                }
+
+                self.accept_countdown =
+                    Some(ui::accept_countdown::AcceptCountdown::new(action, delay));
+            }
+            Message::Interaction => {
+                self.last_interaction = Instant::now();
+            }
+            Message::IdleCheck(now) => {
+                if let Some(timeout) = self.idle_timeout()
+                    && now.saturating_duration_since(self.last_interaction) >= timeout
+                {
+                    log::info!("Exiting, nothing was done for {timeout:?}");
+                    return Self::exit();
+                }
+            }
+            Message::Record(event) => {
+                if let Some(recorder) = &mut self.recorder {
+                    if let Err(err) = recorder.write(self.time_started.elapsed(), event) {
+                        log::error!("Failed to record event: {err}");
+                        self.recorder = None;
+                        self.errors.push(format!("Stopped recording: {err}"));
+                    }
+                }
             }
             Message::KeyCheatsheet(key_cheatsheet) => {
                 return key_cheatsheet.handle(self);
             }
+            Message::DebugOverlay(debug_overlay) => {
+                return debug_overlay.handle(self);
+            }
             Message::Selection(selection) => {
                 return selection.handle(self);
             }
@@ -378,10 +829,57 @@ impl App {
             Message::Letters(letters) => {
                 return letters.handle(self);
             }
+            Message::Palette(palette) => {
+                return palette.handle(self);
+            }
+            Message::Help(help) => {
+                return help.handle(self);
+            }
+            Message::SelectionIcons(selection_icons) => {
+                return selection_icons.handle(self);
+            }
+            Message::RadialMenu(radial_menu) => {
+                return radial_menu.handle(self);
+            }
+            Message::UploadTooLarge(upload_too_large) => {
+                return upload_too_large.handle(self);
+            }
+            Message::Histogram(histogram) => {
+                return histogram.handle(self);
+            }
+            Message::Frames(frames) => {
+                return frames.handle(self);
+            }
+            Message::Control(control) => {
+                return control.handle(self);
+            }
+            Message::Magnifier(magnifier) => {
+                return magnifier.handle(self);
+            }
+            Message::Measure(measure) => {
+                return measure.handle(self);
+            }
+            Message::Annotations(annotations) => {
+                return annotations.handle(self);
+            }
             Message::NoOp => (),
             Message::Command { action, count } => {
                 return <crate::Command as crate::command::Handler>::handle(action, self, count);
             }
+            Message::CommandInRegion {
+                action,
+                count,
+                region,
+            } => {
+                use crate::command::Handler as _;
+
+                let select = crate::Command::Selection(ui::selection::Command::SelectRegion {
+                    selection: region,
+                })
+                .handle(self, 1);
+
+                return Task::batch([select, action.handle(self, count)]);
+            }
             Message::Error(err) => {
                 self.errors.push(err);
             }
@@ -391,6 +889,107 @@ impl App {
     }
 }
 
+/// How the window covering the screen is created
+#[derive(ferrishot_knus::DecodeScalar, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WindowMode {
+    /// Ask the window manager for a fullscreen window
+    #[default]
+    Fullscreen,
+    /// A borderless window placed over the monitor. On X11 it bypasses the window
+    /// manager entirely, so it appears instantly without any transition
+    Borderless,
+}
+
+impl WindowMode {
+    /// Settings of the window showing the `image`
+    pub fn settings(self, image: &RgbaHandle) -> window::Settings {
+        match self {
+            // a fullscreen window can only cover a single monitor
+            Self::Fullscreen if crate::image::spans_monitors() => Self::Borderless.settings(image),
+            // the window becomes fullscreen on the monitor it is placed on, which has
+            // to be the one picked with `--monitor`
+            Self::Fullscreen => window::Settings {
+                level: window::Level::Normal,
+                fullscreen: true,
+                position: Self::position(Self::monitor().as_ref()),
+                ..Default::default()
+            },
+            Self::Borderless => {
+                let monitor = Self::monitor();
+
+                let scale_factor = monitor.as_ref().map_or(1.0, |monitor| monitor.scale_factor);
+
+                window::Settings {
+                    level: window::Level::AlwaysOnTop,
+                    decorations: false,
+                    resizable: false,
+                    size: Size::new(
+                        image.width() as f32 / scale_factor,
+                        image.height() as f32 / scale_factor,
+                    ),
+                    position: Self::position(monitor.as_ref()),
+                    #[cfg(target_os = "linux")]
+                    platform_specific: window::settings::PlatformSpecific {
+                        override_redirect: true,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+            }
+        }
+    }
+
+    /// The monitor to place the window on
+    fn monitor() -> Option<crate::image::MonitorInfo> {
+        crate::image::current_monitor()
+            .inspect_err(|err| {
+                log::warn!("Could not get the monitor to place the window on: {err}");
+            })
+            .ok()
+    }
+
+    /// Position of the window placed at the top left corner of the `monitor`
+    fn position(monitor: Option<&crate::image::MonitorInfo>) -> window::Position {
+        monitor.map_or(window::Position::Default, |monitor| {
+            window::Position::Specific(Point::new(
+                monitor.x as f32 / monitor.scale_factor,
+                monitor.y as f32 / monitor.scale_factor,
+            ))
+        })
+    }
+}
+
+/// Settings of the window for `ferrishot edit`, showing the `image` at its own size
+///
+/// The `image` is not a screenshot of the monitor, so covering the entire monitor would
+/// leave parts of the window without the image. Images larger than the monitor start
+/// out as large as the monitor, and the window can be resized
+pub fn edit_window_settings(image: &RgbaHandle) -> window::Settings {
+    let mut size = Size::new(image.width() as f32, image.height() as f32);
+
+    match crate::image::current_monitor() {
+        Ok(monitor) => {
+            size.width = size.width.min(monitor.width as f32 / monitor.scale_factor);
+            size.height = size
+                .height
+                .min(monitor.height as f32 / monitor.scale_factor);
+        }
+        Err(err) => log::warn!("Could not get the monitor to fit the window into: {err}"),
+    }
+
+    window::Settings {
+        size,
+        resizable: true,
+        ..Default::default()
+    }
+}
+
+/// How often to check whether `idle-timeout` has passed
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Maximum time between 2 clicks for them to count as a double click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
 /// Holds information about the mouse
 #[derive(Default, Debug, Clone)]
 pub struct AppKeysState {
@@ -398,8 +997,13 @@ pub struct AppKeysState {
     pub is_left_down: bool,
     /// How many times to execute the next motion
     pub motion_count: Option<u32>,
-    /// The last key that was pressed
-    pub last_key_pressed: Option<iced::keyboard::Key>,
+    /// The last key that was pressed, and when
+    pub last_key_pressed: Option<(iced::keyboard::Key, Instant)>,
+    /// When the left mouse was last pressed inside of the selection,
+    /// used to detect double clicks
+    pub last_click_in_selection: Option<Instant>,
+    /// The key which is currently held down, and since when
+    pub held_key: Option<(iced::keyboard::Key, Instant)>,
 }
 
 impl canvas::Program<Message> for App {
@@ -411,12 +1015,26 @@ impl canvas::Program<Message> for App {
         renderer: &Renderer,
         _theme: &Theme,
         bounds: Rectangle,
-        _cursor: iced::advanced::mouse::Cursor,
+        cursor: iced::advanced::mouse::Cursor,
     ) -> Vec<canvas::Geometry> {
         let mut frame = canvas::Frame::new(renderer, bounds.size());
 
         if let Some(sel) = self.selection.map(Selection::norm) {
-            sel.draw(&mut frame, bounds);
+            sel.draw(
+                &mut frame,
+                bounds,
+                self.config.selection_frame_style,
+                self.is_resize_anchor_active.then_some(self.resize_anchor),
+                self.config.handles,
+                cursor,
+            );
+            self.guide
+                .draw(&mut frame, sel.rect, self.config.theme.guide);
+            ui::crop_suggestion::draw(&mut frame, self);
+
+            if let Some(progress) = ui::animation::selection_pulse(self) {
+                sel.draw_pulse(&mut frame, progress);
+            }
         } else {
             // usually the selection is responsible for drawing shade around itself
             // However here we don't have selection, so just draw the shade on the entire screen
@@ -425,6 +1043,43 @@ impl canvas::Program<Message> for App {
                 bounds.size(),
                 self.config.theme.non_selected_region,
             );
+
+            self.recent_regions
+                .draw(&mut frame, cursor, &self.config.theme);
+
+            // crosshair lines, to aim at where the selection will start
+            if self.config.theme.crosshair_thickness > 0
+                && self.popup.is_none()
+                && let Some(cursor) = cursor.position_in(bounds)
+            {
+                let thickness = self.config.theme.crosshair_thickness as f32;
+
+                frame.fill_rectangle(
+                    Point::new(0.0, cursor.y - thickness / 2.0),
+                    Size::new(bounds.width, thickness),
+                    self.config.theme.crosshair,
+                );
+                frame.fill_rectangle(
+                    Point::new(cursor.x - thickness / 2.0, 0.0),
+                    Size::new(thickness, bounds.height),
+                    self.config.theme.crosshair,
+                );
+            }
+        }
+
+        ui::annotations::draw(&mut frame, self);
+
+        if self.mode == ui::mode::Mode::Magnify
+            && self.popup.is_none()
+            && let Some(cursor) = self.magnifier
+        {
+            ui::magnifier::draw(&mut frame, self, cursor, bounds);
+        }
+
+        if self.mode == ui::mode::Mode::Measure
+            && let Some(ruler) = self.ruler
+        {
+            ruler.draw(&mut frame, &self.config.theme);
         }
 
         vec![frame.into_geometry()]
@@ -438,34 +1093,217 @@ impl canvas::Program<Message> for App {
         cursor: iced::advanced::mouse::Cursor,
     ) -> Option<Action<Message>> {
         use iced::Event::{Keyboard, Mouse, Touch};
-        use iced::keyboard::Event::KeyPressed;
+        use iced::keyboard::Event::{KeyPressed, KeyReleased};
         use iced::keyboard::Key::Named;
         use iced::keyboard::Modifiers;
         use iced::keyboard::key::Named::{ArrowDown, ArrowLeft, ArrowRight, ArrowUp, Shift};
         use iced::mouse::Button::Left;
         use iced::mouse::Event::ButtonPressed;
         use iced::mouse::Event::ButtonReleased;
+        use iced::mouse::Event::CursorMoved;
         use iced::touch::Event::{FingerLifted, FingerPressed};
 
-        // Handle popups. Esc = close popup
+        // Handle popups. Esc = close popup, Enter = confirm it
+        //
+        // The popups asking for confirmation also take the keys of `smart-escape` and
+        // `accept`. The others don't, as they may be typed into
         //
         // Events will still be forwarded to the canvas even if we have a popup
-        if self.popup.is_some() {
-            if let Keyboard(KeyPressed {
-                key: Named(iced::keyboard::key::Named::Escape),
+        if let Some(popup) = &self.popup {
+            let Keyboard(KeyPressed {
+                key,
+                modified_key,
+                modifiers,
                 ..
             }) = event
+            else {
+                return None;
+            };
+
+            let bound = matches!(
+                popup,
+                Popup::ConfirmExit | Popup::ConfirmPreview(_) | Popup::UploadTooLarge(_)
+            )
+            .then(|| {
+                self.config.keys.get(
+                    modified_key.clone(),
+                    None,
+                    modifiers.difference(Modifiers::SHIFT),
+                    self.mode,
+                )
+            })
+            .flatten()
+            .map(|binding| &binding.command);
+
+            if *key == Named(iced::keyboard::key::Named::Escape)
+                || matches!(bound, Some(crate::Command::App(Command::SmartEscape)))
             {
                 return Some(Action::publish(Message::ClosePopup));
             }
 
-            return None;
+            if *key != Named(iced::keyboard::key::Named::Enter)
+                && !matches!(bound, Some(crate::Command::App(Command::Accept)))
+            {
+                return None;
+            }
+
+            return match popup {
+                // confirm exit
+                Popup::ConfirmExit => Some(Action::publish(Message::Exit)),
+                // run the action on the previewed image
+                Popup::ConfirmPreview(preview) => Some(Action::publish(Message::Command {
+                    action: preview.action.into_key_action(),
+                    count: 1,
+                })),
+                _ => None,
+            };
         }
 
         let (state, selection_state) = state;
 
+        // the magnifier follows the cursor
+        if self.mode == ui::mode::Mode::Magnify
+            && !state.is_left_down
+            && let Mouse(CursorMoved { position }) = event
+        {
+            return Some(Action::publish(Message::Magnifier(
+                ui::magnifier::Message::Move(*position),
+            )));
+        }
+
+        // typing writes the text, instead of running the keybindings
+        if let Some(ui::annotations::Shape::Text { .. }) = &self.annotations.drawing
+            && let Keyboard(KeyPressed { key, text, .. }) = event
+        {
+            use iced::keyboard::key::Named::{Backspace, Enter, Escape};
+
+            let message = match key {
+                Named(Enter | Escape) => Some(ui::annotations::Message::Finish),
+                Named(Backspace) => Some(ui::annotations::Message::Backspace),
+                _ => text
+                    .as_ref()
+                    .filter(|text| !text.chars().any(char::is_control))
+                    .map(|text| ui::annotations::Message::Type(text.to_string())),
+            };
+
+            return Some(match message {
+                Some(message) => Action::publish(Message::Annotations(message)).and_capture(),
+                None => Action::capture(),
+            });
+        }
+
+        // dragging the mouse draws, instead of changing the selection
+        if self.mode == ui::mode::Mode::Annotate {
+            let message = match event {
+                Mouse(ButtonPressed(Left)) => {
+                    Some(ui::annotations::Message::Start(cursor.position()?))
+                }
+                Mouse(CursorMoved { position }) if self.annotations.drawing.is_some() => {
+                    Some(ui::annotations::Message::Move(*position))
+                }
+                // text is finished once it is typed
+                Mouse(ButtonReleased(Left))
+                    if self.annotations.drawing.as_ref().is_some_and(|shape| {
+                        !matches!(shape, ui::annotations::Shape::Text { .. })
+                    }) =>
+                {
+                    Some(ui::annotations::Message::Finish)
+                }
+                _ => None,
+            };
+
+            if let Some(message) = message {
+                return Some(Action::publish(Message::Annotations(message)));
+            }
+        }
+
+        // dragging the mouse measures, instead of changing the selection
+        if self.mode == ui::mode::Mode::Measure {
+            let message = match event {
+                Mouse(ButtonPressed(Left)) => Some(ui::measure::Message::Start(cursor.position()?)),
+                Mouse(CursorMoved { position })
+                    if self.ruler.is_some_and(ui::measure::Ruler::is_dragging) =>
+                {
+                    Some(ui::measure::Message::Move(*position))
+                }
+                Mouse(ButtonReleased(Left)) => Some(ui::measure::Message::Finish),
+                _ => None,
+            };
+
+            if let Some(message) = message {
+                return Some(Action::publish(Message::Measure(message)));
+            }
+        }
+
+        // Double-click inside of the selection runs the configured action
+        if let (Some(sel), Mouse(ButtonPressed(Left))) = (self.selection, event) {
+            let is_inside = cursor.position().is_some_and(|cursor_pos| {
+                sel.corners()
+                    .side_at(cursor_pos, self.config.handles.interaction_area as f32)
+                    .is_none()
+                    && sel.norm().contains(cursor_pos)
+            });
+
+            if is_inside {
+                let is_double_click = state
+                    .last_click_in_selection
+                    .is_some_and(|last_click| last_click.elapsed() <= DOUBLE_CLICK_INTERVAL);
+
+                if is_double_click && let Some(action) = self.config.double_click_action {
+                    state.last_click_in_selection = None;
+                    return Some(Action::publish(Message::Command {
+                        action: action.into_key_action(),
+                        count: 1,
+                    }));
+                }
+
+                state.last_click_in_selection = Some(Instant::now());
+            } else {
+                state.last_click_in_selection = None;
+            }
+        }
+
+        // the radial menu opens around the cursor, instead of anything else the button does
+        if let Mouse(ButtonPressed(button)) = event
+            && self.config.radial_menu.is_opened_by(*button)
+            && let Some(cursor_pos) = cursor.position()
+        {
+            return Some(Action::publish(Message::RadialMenu(
+                ui::popup::radial_menu::Message::Open(cursor_pos),
+            )));
+        }
+
+        // keep track of how long a key is held, by ignoring its repeats
+        match event {
+            Keyboard(KeyPressed { key, repeat, .. })
+                if !*repeat
+                    || state
+                        .held_key
+                        .as_ref()
+                        .is_none_or(|(held_key, _)| held_key != key) =>
+            {
+                state.held_key = Some((key.clone(), Instant::now()));
+            }
+            Keyboard(KeyReleased { key, .. })
+                if state
+                    .held_key
+                    .as_ref()
+                    .is_some_and(|(held_key, _)| held_key == key) =>
+            {
+                state.held_key = None;
+            }
+            _ => (),
+        }
+
         if let Some(sel) = self.selection {
-            if let Some(action) = sel.update(selection_state, event, bounds, cursor) {
+            if let Some(action) = sel.update(
+                selection_state,
+                event,
+                bounds,
+                cursor,
+                self.config.handles,
+                self.config.scroll_resize_step,
+            ) {
                 return Some(action);
             }
         }
@@ -489,12 +1327,16 @@ impl canvas::Program<Message> for App {
         }
 
         // handle keybindings
+        //
+        // With `key-repeat #false`, holding a key runs its command once
         if let Keyboard(KeyPressed {
             modifiers,
             modified_key,
             key,
+            repeat,
             ..
         }) = event
+            && (self.config.key_repeat || !*repeat)
         {
             let mut modifiers = *modifiers;
 
@@ -508,17 +1350,28 @@ impl canvas::Program<Message> for App {
                 modifiers.remove(Modifiers::SHIFT);
             }
 
-            if let Some(action) = state
+            if let Some(binding) = state
                 .last_key_pressed
                 .as_ref()
-                .and_then(|last_key_pressed| {
+                // the first key of a sequence is forgotten after a while, so
+                // `g` pressed long before another `g` is not `gg`
+                .filter(|(_, pressed_at)| {
+                    self.key_sequence_timeout()
+                        .is_none_or(|timeout| pressed_at.elapsed() <= timeout)
+                })
+                .and_then(|(last_key_pressed, _)| {
                     self.config.keys.get(
                         last_key_pressed.clone(),
                         Some(modified_key.clone()),
                         modifiers,
+                        self.mode,
                     )
                 })
-                .or_else(|| self.config.keys.get(modified_key.clone(), None, modifiers))
+                .or_else(|| {
+                    self.config
+                        .keys
+                        .get(modified_key.clone(), None, modifiers, self.mode)
+                })
             {
                 // the last key pressed needs to be reset for it to be
                 // correct in future invocations
@@ -530,11 +1383,28 @@ impl canvas::Program<Message> for App {
                 // twice
                 state.last_key_pressed = None;
 
-                let count = state.motion_count.unwrap_or(1);
+                let mut count = state.motion_count.unwrap_or(1);
                 state.motion_count = None;
 
+                // holding the key of a nudge speeds it up
+                if let crate::Command::Selection(selection) = &binding.command
+                    && selection.is_nudge()
+                    && let Some((_, held_since)) = &state.held_key
+                {
+                    count = count
+                        .saturating_mul(self.config.key_acceleration.factor(held_since.elapsed()));
+                }
+
+                if let Some(region) = binding.region.filter(|_| self.selection.is_none()) {
+                    return Some(Action::publish(Message::CommandInRegion {
+                        action: binding.command.clone(),
+                        count,
+                        region,
+                    }));
+                }
+
                 return Some(Action::publish(Message::Command {
-                    action: action.clone(),
+                    action: binding.command.clone(),
                     count,
                 }));
             }
@@ -545,7 +1415,7 @@ impl canvas::Program<Message> for App {
             // - `last_key_pressed = Shift` then
             // - `last_key_pressed = 'G'`
             if *modified_key != Named(Shift) {
-                state.last_key_pressed = Some(modified_key.clone());
+                state.last_key_pressed = Some((modified_key.clone(), Instant::now()));
             }
         }
 
@@ -562,6 +1432,12 @@ impl canvas::Program<Message> for App {
                 state.is_left_down = false;
                 Message::NoOp
             }
+            // the crosshair follows the cursor
+            Mouse(CursorMoved { .. })
+                if self.selection.is_none() && self.config.theme.crosshair_thickness > 0 =>
+            {
+                return Some(Action::request_redraw());
+            }
             _ => return None,
         };
 
@@ -576,10 +1452,17 @@ impl canvas::Program<Message> for App {
     ) -> Interaction {
         if let Some(Popup::ImageUploaded(_)) = self.popup {
             Interaction::default()
+        } else if matches!(
+            self.mode,
+            ui::mode::Mode::Annotate | ui::mode::Mode::Measure
+        ) {
+            Interaction::Crosshair
         } else {
             self.selection
                 .map(Selection::norm)
-                .map_or(Interaction::Crosshair, |sel| sel.mouse_interaction(cursor))
+                .map_or(Interaction::Crosshair, |sel| {
+                    sel.mouse_interaction(cursor, self.config.handles)
+                })
         }
     }
 }
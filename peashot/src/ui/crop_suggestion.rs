@@ -0,0 +1,253 @@
+//! Suggest a slightly adjusted selection, with its edges on the edges of the content
+//!
+//! Each edge of the selection moves to the strongest nearby line between two rows or
+//! columns of pixels which differ a lot, such as where a window ends and the desktop begins.
+//! The suggestion is shown as a dashed rectangle until it is accepted, or the selection changes.
+
+use iced::{Rectangle, Task, widget::canvas};
+use image::{GenericImageView, Pixel as _};
+
+use crate::geometry::RectangleExt as _;
+
+/// How far from each edge of the selection to look for the content, as a fraction
+/// of the width or height of the selection
+const SEARCH_FRACTION: f32 = 0.1;
+
+/// Always look at least this many pixels away from each edge
+const MIN_SEARCH: u32 = 8;
+
+/// Never look further than this many pixels away from each edge
+const MAX_SEARCH: u32 = 64;
+
+/// Lines where neighbouring pixels differ less than this on average are not edges
+const MIN_STRENGTH: f32 = 24.0;
+
+/// Length of each dash of the suggested crop
+const DASH_LENGTH: f32 = 6.0;
+
+crate::declare_commands! {
+    enum Command {
+        /// Suggest a selection with its edges moved onto the nearby edges of the content
+        SuggestCrop,
+        /// Replace the selection with the suggested crop
+        AcceptCropSuggestion,
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
+        match self {
+            Self::SuggestCrop => {
+                let Some(from) = app.selection.map(|sel| sel.rect.norm()) else {
+                    app.errors.warn("Nothing is selected.");
+                    return Task::none();
+                };
+
+                let Some(rect) = suggest(&app.image.as_image(), from) else {
+                    app.errors
+                        .info("The selection already fits the content around it");
+                    return Task::none();
+                };
+
+                app.crop_suggestion = Some(Suggestion { from, rect });
+            }
+            Self::AcceptCropSuggestion => {
+                let Some((selection, suggestion)) = app
+                    .selection
+                    .as_mut()
+                    .zip(app.crop_suggestion.take())
+                    .filter(|(sel, suggestion)| sel.rect.norm() == suggestion.from)
+                else {
+                    app.errors.warn("There is no suggested crop");
+                    return Task::none();
+                };
+
+                *selection = selection
+                    .norm()
+                    .with_pos(|_| suggestion.rect.top_left())
+                    .with_size(|_| suggestion.rect.size());
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// A suggested crop
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Suggestion {
+    /// The selection, when the crop was suggested
+    pub from: Rectangle,
+    /// The suggested selection
+    pub rect: Rectangle,
+}
+
+/// Draw the suggested crop, if it was suggested for the current selection
+pub fn draw(frame: &mut canvas::Frame, app: &crate::App) {
+    let Some(suggestion) = app
+        .crop_suggestion
+        .filter(|suggestion| Some(suggestion.from) == app.selection.map(|sel| sel.rect.norm()))
+    else {
+        return;
+    };
+
+    frame.stroke_rectangle(
+        suggestion.rect.top_left(),
+        suggestion.rect.size(),
+        canvas::Stroke {
+            line_dash: canvas::LineDash {
+                segments: &[DASH_LENGTH],
+                offset: 0,
+            },
+            ..canvas::Stroke::default()
+                .with_color(app.config.theme.crop_suggestion)
+                .with_width(app.config.theme.frame_width())
+        },
+    );
+}
+
+/// Selection close to `rect` with its edges on the strongest nearby edges of the `image`
+///
+/// Returns `None` if no edge moves
+fn suggest<I: GenericImageView<Pixel = image::Rgba<u8>>>(
+    image: &I,
+    rect: Rectangle,
+) -> Option<Rectangle> {
+    let rect = rect.intersection(&Rectangle::new(
+        iced::Point::ORIGIN,
+        iced::Size::new(image.width() as f32, image.height() as f32),
+    ))?;
+
+    let (left, top) = (rect.x.round() as u32, rect.y.round() as u32);
+    let (right, bottom) = (
+        (rect.x + rect.width).round() as u32,
+        (rect.y + rect.height).round() as u32,
+    );
+
+    let search =
+        |len: u32| ((len as f32 * SEARCH_FRACTION).round() as u32).clamp(MIN_SEARCH, MAX_SEARCH);
+    let (search_x, search_y) = (search(right - left), search(bottom - top));
+
+    // how strongly the columns `x - 1` and `x` differ, inside of the selection
+    let column = |x: u32| {
+        (top..bottom)
+            .map(|y| difference(image.get_pixel(x - 1, y), image.get_pixel(x, y)))
+            .sum::<f32>()
+            / (bottom - top) as f32
+    };
+    // how strongly the rows `y - 1` and `y` differ, inside of the selection
+    let row = |y: u32| {
+        (left..right)
+            .map(|x| difference(image.get_pixel(x, y - 1), image.get_pixel(x, y)))
+            .sum::<f32>()
+            / (right - left) as f32
+    };
+
+    let new_left = snap(left, search_x, image.width(), column);
+    let new_right = snap(right, search_x, image.width(), column);
+    let new_top = snap(top, search_y, image.height(), row);
+    let new_bottom = snap(bottom, search_y, image.height(), row);
+
+    if new_right <= new_left
+        || new_bottom <= new_top
+        || (new_left, new_top, new_right, new_bottom) == (left, top, right, bottom)
+    {
+        return None;
+    }
+
+    Some(Rectangle {
+        x: new_left as f32,
+        y: new_top as f32,
+        width: (new_right - new_left) as f32,
+        height: (new_bottom - new_top) as f32,
+    })
+}
+
+/// Position of the strongest edge at most `radius` pixels away from `current`,
+/// preferring closer edges. Stays at `current` if there are no edges
+///
+/// An edge at `position` is between the pixels `position - 1` and `position`,
+/// which must both be less than `len`
+fn snap(current: u32, radius: u32, len: u32, strength: impl Fn(u32) -> f32) -> u32 {
+    std::iter::once(current)
+        .chain((1..=radius).flat_map(|distance| {
+            [current.checked_sub(distance), current.checked_add(distance)]
+                .into_iter()
+                .flatten()
+        }))
+        .filter(|position| (1..len).contains(position))
+        .map(|position| (position, strength(position)))
+        .filter(|(_, strength)| *strength >= MIN_STRENGTH)
+        // `max_by` would prefer the last of equally strong edges, which is further away
+        .fold(
+            None,
+            |best: Option<(u32, f32)>, (position, strength)| match best {
+                Some((_, best_strength)) if best_strength >= strength => best,
+                _ => Some((position, strength)),
+            },
+        )
+        .map_or(current, |(position, _)| position)
+}
+
+/// How different the colors of 2 pixels are, from `0` to `255`
+fn difference(a: image::Rgba<u8>, b: image::Rgba<u8>) -> f32 {
+    a.to_rgb()
+        .0
+        .iter()
+        .zip(b.to_rgb().0)
+        .map(|(a, b)| f32::from(a.abs_diff(b)))
+        .sum::<f32>()
+        / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::{Point, Size};
+    use image::{Rgba, RgbaImage};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn edges_move_onto_the_content() {
+        // black square on a white background
+        let image = RgbaImage::from_fn(40, 40, |x, y| {
+            if (10..20).contains(&x) && (10..20).contains(&y) {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+
+        assert_eq!(
+            suggest(
+                &image,
+                Rectangle::new(Point::new(8.0, 8.0), Size::new(15.0, 15.0))
+            ),
+            Some(Rectangle::new(
+                Point::new(10.0, 10.0),
+                Size::new(10.0, 10.0)
+            ))
+        );
+        // already on the edges
+        assert_eq!(
+            suggest(
+                &image,
+                Rectangle::new(Point::new(10.0, 10.0), Size::new(10.0, 10.0))
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn stays_without_edges() {
+        let image = RgbaImage::from_pixel(40, 40, Rgba([255, 255, 255, 255]));
+
+        assert_eq!(
+            suggest(
+                &image,
+                Rectangle::new(Point::new(8.0, 8.0), Size::new(15.0, 15.0))
+            ),
+            None
+        );
+    }
+}
@@ -1,11 +1,19 @@
 //! Shows useful information when pressing F12
 
+use std::{collections::VecDeque, fs, io::Write as _, path::PathBuf};
+
+use etcetera::BaseStrategy as _;
 use iced::{
     Background, Element,
     Length::Fill,
     Task, Theme,
-    widget::{Column, column, container, horizontal_space, row, scrollable, text, vertical_space},
+    widget::{
+        Column, button, column, container, horizontal_space, row, scrollable, text, vertical_space,
+    },
 };
+use strum::IntoEnumIterator as _;
+
+use crate::message::MessageKind;
 
 crate::declare_commands! {
     enum Command {
@@ -18,7 +26,7 @@ impl crate::command::Handler for Command {
     fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
         match self {
             Self::ToggleDebugOverlay => {
-                app.popup = Some(super::popup::Popup::KeyCheatsheet);
+                app.show_debug_overlay = !app.show_debug_overlay;
             }
         }
 
@@ -26,6 +34,82 @@ impl crate::command::Handler for Command {
     }
 }
 
+/// Debug overlay message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Stop or continue logging messages
+    TogglePause,
+    /// Only show the next kind of message
+    CycleFilter,
+    /// Write all of the logged messages to a file
+    Export,
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        match self {
+            Self::TogglePause => {
+                app.debug_overlay.is_paused = !app.debug_overlay.is_paused;
+            }
+            Self::CycleFilter => {
+                let mut kinds = MessageKind::iter();
+                app.debug_overlay.filter = match app.debug_overlay.filter {
+                    // all kinds -> first kind
+                    None => kinds.next(),
+                    // a kind -> the kind after it, or back to all kinds
+                    Some(current) => kinds.skip_while(|kind| *kind != current).nth(1),
+                };
+            }
+            Self::Export => match export(&app.logged_messages) {
+                Ok(path) => {
+                    log::info!("Exported logged messages to {}", path.display());
+                    app.debug_overlay.exported_to = Some(path);
+                }
+                Err(err) => {
+                    app.errors
+                        .push(format!("Failed to export logged messages: {err}"));
+                }
+            },
+        }
+
+        Task::none()
+    }
+}
+
+/// State of the debug overlay
+#[derive(Debug, Default)]
+pub struct State {
+    /// Do not log new messages
+    pub is_paused: bool,
+    /// Only show messages of this kind
+    pub filter: Option<MessageKind>,
+    /// Where the logged messages were last exported to
+    pub exported_to: Option<PathBuf>,
+}
+
+/// Name of the file that logged messages are exported to
+const EXPORT_FILENAME: &str = "ferrishot-messages.txt";
+
+/// Maximum number of logged messages to keep. Oldest are discarded first
+pub const MAX_LOGGED_MESSAGES: usize = 1000;
+
+/// How many of the latest messages to show
+const SHOWN_MESSAGES: usize = 50;
+
+/// Write all of the `messages` to a file in the cache directory
+fn export(messages: &VecDeque<crate::Message>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let path = etcetera::choose_base_strategy()?
+        .cache_dir()
+        .join(EXPORT_FILENAME);
+
+    let mut file = fs::File::create(&path)?;
+    for message in messages {
+        writeln!(file, "{message:#?}")?;
+    }
+
+    Ok(path)
+}
+
 /// Space between the label and what it represents
 const LABEL_SPACE: f32 = 25.0;
 
@@ -60,15 +144,49 @@ pub fn debug_overlay(app: &crate::App) -> Element<crate::Message> {
         .style(container_style),
         horizontal_space().width(Fill),
         container(
-            scrollable(column![
-                text("Latest Messages").color(app.config.theme.debug_label),
-                app.logged_messages
-                    .iter()
-                    .rev()
-                    .take(5)
-                    .map(|message| text!("{message:#?}").into())
-                    .collect::<Column<_>>()
-            ])
+            scrollable(
+                column![
+                    text("Latest Messages").color(app.config.theme.debug_label),
+                    row![
+                        button(text(if app.debug_overlay.is_paused {
+                            "Resume"
+                        } else {
+                            "Pause"
+                        }))
+                        .on_press(crate::Message::DebugOverlay(Message::TogglePause)),
+                        button(text!(
+                            "Show: {}",
+                            app.debug_overlay
+                                .filter
+                                .map_or_else(|| "All".to_string(), |kind| kind.to_string())
+                        ))
+                        .on_press(crate::Message::DebugOverlay(Message::CycleFilter)),
+                        button(text("Export"))
+                            .on_press(crate::Message::DebugOverlay(Message::Export)),
+                    ]
+                    .spacing(5.0),
+                ]
+                .push_maybe(
+                    app.debug_overlay
+                        .exported_to
+                        .as_ref()
+                        .map(|path| text!("Exported to {}", path.display()))
+                )
+                .push(vertical_space().height(LABEL_SPACE))
+                .push(
+                    app.logged_messages
+                        .iter()
+                        .rev()
+                        .filter(|message| {
+                            app.debug_overlay
+                                .filter
+                                .is_none_or(|kind| MessageKind::from(*message) == kind)
+                        })
+                        .take(SHOWN_MESSAGES)
+                        .map(|message| text!("{message:#?}").into())
+                        .collect::<Column<_>>()
+                )
+            )
             .width(400.0)
             .height(Fill),
         )
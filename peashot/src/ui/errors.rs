@@ -1,31 +1,202 @@
 //! Show errors to the user when something is wrong
+//!
+//! Every message has a severity, which decides its color and how long it is shown.
+//! Messages stay in the list after they disappear, so they can all be copied with `copy-errors`
+//!
+//! How long the messages are shown, how many of them at once and how they fade in and out
+//! is configured with `toasts`
 
 use std::{
     borrow::Cow,
+    fmt,
     time::{Duration, Instant},
 };
 
 use iced::{
-    Background, Element,
+    Background, Element, Task,
     widget::{self, Column, Space, container, row},
 };
 
+crate::declare_commands! {
+    enum Command {
+        /// Copy all of the errors, warnings and info messages shown so far
+        CopyErrors,
+        /// Hide the newest error, warning or info message which is shown
+        DismissError,
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
+        match self {
+            Self::CopyErrors => {
+                if app.errors.errors.is_empty() {
+                    app.errors.info("There are no errors to copy");
+                    return Task::none();
+                }
+
+                let count = app.errors.errors.len();
+                match crate::clipboard::set_text(&app.errors.to_string()) {
+                    Ok(()) => app.errors.info(format!(
+                        "Copied {count} message{}",
+                        if count == 1 { "" } else { "s" }
+                    )),
+                    Err(err) => app.errors.push(err.to_string()),
+                }
+            }
+            Self::DismissError => app.errors.dismiss(&app.config.toasts),
+        }
+
+        Task::none()
+    }
+}
+
+/// How serious a message is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Something happened which the user might want to know about
+    Info,
+    /// Something could not be done, but it is not a problem with the app
+    Warning,
+    /// Something went wrong
+    Error,
+}
+
+/// Default of `Toasts::info`
+const DEFAULT_INFO_DURATION: u32 = 2000;
+/// Default of `Toasts::warning`
+const DEFAULT_WARNING_DURATION: u32 = 4000;
+/// Default of `Toasts::error`
+const DEFAULT_ERROR_DURATION: u32 = 6000;
+/// Default of `Toasts::max_visible`
+const DEFAULT_MAX_VISIBLE: u32 = 3;
+/// Default of `Toasts::fade`
+const DEFAULT_FADE: u32 = 200;
+
+/// How the messages in the corner of the screen are shown
+///
+/// The durations are in milliseconds
+///
+/// ```kdl
+/// toasts info=2000 warning=4000 error=6000 max-visible=3 fade=200
+/// ```
+#[derive(ferrishot_knus::Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Toasts {
+    /// How long info messages are shown
+    #[ferrishot_knus(default = DEFAULT_INFO_DURATION, property)]
+    pub info: u32,
+    /// How long warnings are shown
+    #[ferrishot_knus(default = DEFAULT_WARNING_DURATION, property)]
+    pub warning: u32,
+    /// How long errors are shown
+    #[ferrishot_knus(default = DEFAULT_ERROR_DURATION, property)]
+    pub error: u32,
+    /// Most messages shown at once, the newest of them
+    #[ferrishot_knus(default = DEFAULT_MAX_VISIBLE, property)]
+    pub max_visible: u32,
+    /// How long the messages take to fade in, and to fade out. `0` disables fading
+    #[ferrishot_knus(default = DEFAULT_FADE, property)]
+    pub fade: u32,
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        Self {
+            info: DEFAULT_INFO_DURATION,
+            warning: DEFAULT_WARNING_DURATION,
+            error: DEFAULT_ERROR_DURATION,
+            max_visible: DEFAULT_MAX_VISIBLE,
+            fade: DEFAULT_FADE,
+        }
+    }
+}
+
+impl Severity {
+    /// How long a message with this severity is shown
+    const fn duration(self, toasts: &Toasts) -> Duration {
+        Duration::from_millis(match self {
+            Self::Info => toasts.info,
+            Self::Warning => toasts.warning,
+            Self::Error => toasts.error,
+        } as u64)
+    }
+
+    /// Colors of the text and the background of a message with this severity
+    const fn colors(self, theme: &crate::Theme) -> (iced::Color, iced::Color) {
+        match self {
+            Self::Info => (theme.info_fg, theme.info_bg),
+            Self::Warning => (theme.warning_fg, theme.warning_bg),
+            Self::Error => (theme.error_fg, theme.error_bg),
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Info => "Info",
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+        })
+    }
+}
+
 /// Show an error message to the user
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd)]
 pub struct ErrorMessage {
     /// Error message
     pub message: Cow<'static, str>,
-    /// When the error was created
+    /// How serious the message is
+    pub severity: Severity,
+    /// How many times the same message was shown
+    pub count: u32,
+    /// When the error was created, or last repeated
     pub timestamp: Instant,
+    /// Hidden with `dismiss-error` before its time was up
+    pub is_dismissed: bool,
 }
 
 impl ErrorMessage {
     /// Create a new error message
-    pub fn new<T: Into<Cow<'static, str>>>(message: T) -> Self {
+    pub fn new<T: Into<Cow<'static, str>>>(severity: Severity, message: T) -> Self {
         Self {
             message: message.into(),
+            severity,
+            count: 1,
             timestamp: Instant::now(),
+            is_dismissed: false,
+        }
+    }
+
+    /// Whether the message is still shown
+    fn is_visible(&self, toasts: &Toasts) -> bool {
+        !self.is_dismissed && self.timestamp.elapsed() < self.severity.duration(toasts)
+    }
+
+    /// Opacity of the message `elapsed` after it was shown, as it fades in and then out
+    fn opacity(&self, elapsed: Duration, toasts: &Toasts) -> f32 {
+        if toasts.fade == 0 {
+            return 1.0;
+        }
+
+        let fade = toasts.fade as f32 / 1000.0;
+        let remaining = self
+            .severity
+            .duration(toasts)
+            .saturating_sub(elapsed)
+            .as_secs_f32();
+
+        (elapsed.as_secs_f32().min(remaining) / fade).clamp(0.0, 1.0)
+    }
+}
+
+impl fmt::Display for ErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)?;
+        if self.count > 1 {
+            write!(f, " (x{})", self.count)?;
         }
+        Ok(())
     }
 }
 
@@ -34,41 +205,95 @@ use crate::message::Message;
 /// Width of error message
 const ERROR_WIDTH: u32 = 300;
 
-/// When the error appears, how long should it take until it will disappear
-const ERROR_DURATION: Duration = Duration::from_secs(4);
-
 /// Render errors on the screen
 #[derive(Default, Debug)]
 pub struct Errors {
-    /// A list of errors to show
+    /// A list of errors to show, oldest first
     pub errors: Vec<ErrorMessage>,
 }
 
 impl Errors {
     /// Add a new error to the list of errors
     pub fn push<T: Into<Cow<'static, str>> + std::fmt::Display>(&mut self, error: T) {
-        self.errors.push(ErrorMessage::new(error));
+        self.push_with(Severity::Error, error);
+    }
+
+    /// Add a new warning to the list of errors
+    pub fn warn<T: Into<Cow<'static, str>> + std::fmt::Display>(&mut self, warning: T) {
+        self.push_with(Severity::Warning, warning);
+    }
+
+    /// Add a new info message to the list of errors
+    pub fn info<T: Into<Cow<'static, str>> + std::fmt::Display>(&mut self, info: T) {
+        self.push_with(Severity::Info, info);
+    }
+
+    /// Add a message with the `severity` to the list of errors
+    ///
+    /// If the same message was already added, it is shown again and its count goes up
+    /// instead, so repeating an action which fails does not fill the screen
+    pub fn push_with<T: Into<Cow<'static, str>>>(&mut self, severity: Severity, message: T) {
+        let message = message.into();
+
+        let error = match self
+            .errors
+            .iter()
+            .position(|error| error.severity == severity && error.message == message)
+        {
+            Some(idx) => {
+                let mut error = self.errors.remove(idx);
+                error.count += 1;
+                error.timestamp = Instant::now();
+                error.is_dismissed = false;
+                error
+            }
+            None => ErrorMessage::new(severity, message),
+        };
+
+        self.errors.push(error);
+    }
+
+    /// Whether any of the errors is still shown
+    pub fn is_visible(&self, toasts: &Toasts) -> bool {
+        self.errors.iter().any(|error| error.is_visible(toasts))
+    }
+
+    /// Hide the newest message which is shown. It can still be copied with `copy-errors`
+    pub fn dismiss(&mut self, toasts: &Toasts) {
+        if let Some(error) = self
+            .errors
+            .iter_mut()
+            .rev()
+            .find(|error| error.is_visible(toasts))
+        {
+            error.is_dismissed = true;
+        }
     }
 
     /// Show errors on the screen
     pub fn view<'app>(&self, app: &'app super::App) -> Element<'app, Message> {
         let image_width = app.image.width();
+        let toasts = &app.config.toasts;
         let errors = self
             .errors
             .iter()
             .rev()
-            // don't display more than the most recent 3 errors
-            .take(3)
-            .filter(|&error| (error.timestamp.elapsed() < ERROR_DURATION))
+            .filter(|&error| error.is_visible(toasts))
+            // only the most recent errors are displayed
+            .take(toasts.max_visible as usize)
             .map(|error| {
-                container(widget::text!("Error: {}", error.message))
+                let opacity = error.opacity(error.timestamp.elapsed(), toasts);
+                let (fg, bg) = error.severity.colors(&app.config.theme);
+                let (fg, bg) = (fg.scale_alpha(opacity), bg.scale_alpha(opacity));
+
+                container(widget::text(error.to_string()))
                     .height(80)
                     .width(ERROR_WIDTH)
-                    .style(|_| container::Style {
-                        text_color: Some(app.config.theme.error_fg),
-                        background: Some(Background::Color(app.config.theme.error_bg)),
+                    .style(move |_| container::Style {
+                        text_color: Some(fg),
+                        background: Some(Background::Color(bg)),
                         border: iced::Border {
-                            color: app.config.theme.drop_shadow,
+                            color: app.config.theme.drop_shadow.scale_alpha(opacity),
                             width: 4.0,
                             radius: 2.0.into(),
                         },
@@ -84,3 +309,81 @@ impl Errors {
         row![Space::with_width(image_width - ERROR_WIDTH), errors].into()
     }
 }
+
+/// All of the messages, oldest first, one per line
+impl fmt::Display for Errors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for error in &self.errors {
+            writeln!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn repeated_messages_are_counted() {
+        let mut errors = Errors::default();
+
+        errors.push("Failed to upload");
+        errors.warn("Nothing is selected.");
+        errors.push("Failed to upload");
+        // same message, but a different severity
+        errors.info("Failed to upload");
+
+        assert_eq!(
+            errors.to_string(),
+            "Warning: Nothing is selected.\nError: Failed to upload (x2)\nInfo: Failed to upload\n"
+        );
+    }
+
+    #[test]
+    fn dismiss_the_newest_message() {
+        let toasts = Toasts::default();
+        let mut errors = Errors::default();
+
+        errors.push("Failed to upload");
+        errors.warn("Nothing is selected.");
+        errors.dismiss(&toasts);
+
+        let visible = |errors: &Errors| {
+            errors
+                .errors
+                .iter()
+                .filter(|error| error.is_visible(&toasts))
+                .map(|error| error.message.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(visible(&errors), ["Failed to upload"]);
+
+        // it can still be copied
+        assert_eq!(errors.errors.len(), 2);
+
+        // repeating the message shows it again
+        errors.warn("Nothing is selected.");
+        assert_eq!(
+            visible(&errors),
+            ["Failed to upload", "Nothing is selected."]
+        );
+    }
+
+    #[test]
+    fn messages_fade_in_and_out() {
+        let toasts = Toasts::default();
+        let error = ErrorMessage::new(Severity::Info, "Copied");
+        let opacity = |millis| error.opacity(Duration::from_millis(millis), &toasts);
+
+        assert_eq!(opacity(0), 0.0);
+        assert_eq!(opacity(100), 0.5);
+        assert_eq!(opacity(1000), 1.0);
+        assert_eq!(opacity(1900), 0.5);
+        assert_eq!(opacity(2000), 0.0);
+
+        let no_fade = Toasts { fade: 0, ..toasts };
+        assert_eq!(error.opacity(Duration::ZERO, &no_fade), 1.0);
+    }
+}
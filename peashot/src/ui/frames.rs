@@ -0,0 +1,163 @@
+//! Pick which frame of an animated image to edit
+//!
+//! Opening an animated GIF or APNG decodes all of its frames. A scrub bar at the bottom
+//! of the screen shows which of them is edited, and switches to another one
+
+use std::sync::Arc;
+
+use iced::{
+    Background, Element,
+    Length::Fill,
+    Task,
+    widget::{column, container, slider, text},
+};
+
+use crate::image::RgbaHandle;
+
+/// Width of the scrub bar
+const SCRUB_BAR_WIDTH: f32 = 400.0;
+
+crate::declare_commands! {
+    enum Command {
+        /// Edit the next frame of the animated image
+        NextFrame,
+        /// Edit the previous frame of the animated image
+        PreviousFrame,
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, count: u32) -> Task<crate::Message> {
+        let Some(frames) = &mut app.frames else {
+            app.errors.warn("The image is not animated");
+            return Task::none();
+        };
+
+        let count = count as usize;
+        let index = match self {
+            Self::NextFrame => frames.current.saturating_add(count),
+            Self::PreviousFrame => frames.current.saturating_sub(count),
+        };
+        app.image = frames.select(index);
+
+        Task::none()
+    }
+}
+
+/// Frames message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// The scrub bar was dragged to the frame at this index
+    Scrub(u32),
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        match self {
+            Self::Scrub(index) => {
+                if let Some(frames) = &mut app.frames {
+                    app.image = frames.select(index as usize);
+                }
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// Frames of an animated image
+#[derive(Debug)]
+pub struct Frames {
+    /// Each of the frames, in the order they are shown
+    frames: Vec<Arc<RgbaHandle>>,
+    /// Index of the frame which is edited
+    current: usize,
+}
+
+impl Frames {
+    /// Frames of an animated image, unless there is just a single frame
+    pub fn new(frames: Vec<RgbaHandle>) -> Option<Self> {
+        (frames.len() > 1).then(|| Self {
+            frames: frames.into_iter().map(Arc::new).collect(),
+            current: 0,
+        })
+    }
+
+    /// Edit the frame at `index`, or the last one if there are fewer frames
+    ///
+    /// # Returns
+    ///
+    /// The frame to edit
+    fn select(&mut self, index: usize) -> Arc<RgbaHandle> {
+        self.current = index.min(self.frames.len() - 1);
+        Arc::clone(&self.frames[self.current])
+    }
+}
+
+/// Scrub bar for picking the frame to edit, when the image is animated
+pub fn scrub_bar(app: &crate::App) -> Option<Element<crate::Message>> {
+    /// Space between the scrub bar and the bottom of the screen
+    const MARGIN: f32 = 10.0;
+
+    let frames = app.frames.as_ref()?;
+    let theme = &app.config.theme;
+    let last = frames.frames.len() as u32 - 1;
+
+    let bar = container(
+        column![
+            text(format!(
+                "Frame {} of {}",
+                frames.current + 1,
+                frames.frames.len()
+            )),
+            slider(0..=last, frames.current as u32, |index| {
+                crate::Message::Frames(Message::Scrub(index))
+            })
+            .width(Fill),
+        ]
+        .spacing(5.0)
+        .width(SCRUB_BAR_WIDTH),
+    )
+    .padding(10.0)
+    .style(|_| container::Style {
+        text_color: Some(theme.scrub_bar_fg),
+        background: Some(Background::Color(theme.scrub_bar_bg)),
+        border: theme.info_box_border(),
+        ..Default::default()
+    });
+
+    Some(
+        container(bar)
+            .center_x(Fill)
+            .align_bottom(Fill)
+            .padding(MARGIN)
+            .into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn frames(count: u32) -> Vec<RgbaHandle> {
+        (0..count)
+            .map(|width| RgbaHandle::from_image(image::RgbaImage::new(width + 1, 1)))
+            .collect()
+    }
+
+    #[test]
+    fn single_frame_is_not_animated() {
+        assert!(Frames::new(frames(1)).is_none());
+        assert!(Frames::new(frames(2)).is_some());
+    }
+
+    #[test]
+    fn select_stays_within_the_frames() {
+        let mut frames = Frames::new(frames(3)).unwrap();
+
+        assert_eq!(frames.select(1).width(), 2);
+        assert_eq!(frames.select(10).width(), 3);
+        assert_eq!(frames.current, 2);
+    }
+}
@@ -0,0 +1,155 @@
+//! Safe area guides rendered inside of the selection
+//!
+//! They help frame captures for a specific destination, such as
+//! a YouTube thumbnail or an Instagram post.
+
+use iced::{Color, Point, Rectangle, Size, Task, widget::canvas};
+
+use super::popup::Popup;
+
+crate::declare_commands! {
+    enum Command {
+        /// Show the given guide inside of the selection
+        SetGuide {
+            guide: Guide,
+        },
+        /// Open a popup to pick a guide
+        OpenGuides,
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
+        match self {
+            Self::SetGuide { guide } => {
+                app.guide = guide;
+                if matches!(app.popup, Some(Popup::Guides)) {
+                    app.popup = None;
+                }
+            }
+            Self::OpenGuides => {
+                app.popup = Some(Popup::Guides);
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// Width of the lines of the guide
+const GUIDE_WIDTH: f32 = 1.0;
+
+/// A guide for a specific destination of the capture
+#[derive(
+    ferrishot_knus::DecodeScalar, strum::EnumIter, Debug, Default, Clone, Copy, PartialEq, Eq,
+)]
+pub enum Guide {
+    /// Do not show any guide
+    #[default]
+    None,
+    /// Twitter / X card with a large image, `1.91:1`
+    TwitterCard,
+    /// YouTube thumbnail, `16:9`. The duration badge covers the bottom-right corner
+    YoutubeThumbnail,
+    /// Instagram square post, `1:1`
+    InstagramSquare,
+}
+
+impl Guide {
+    /// Name of the guide shown to the user
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::TwitterCard => "Twitter / X card",
+            Self::YoutubeThumbnail => "YouTube thumbnail",
+            Self::InstagramSquare => "Instagram square",
+        }
+    }
+
+    /// Width divided by height of the area the destination displays
+    const fn aspect_ratio(self) -> Option<f32> {
+        match self {
+            Self::None => None,
+            Self::TwitterCard => Some(1.91),
+            Self::YoutubeThumbnail => Some(16.0 / 9.0),
+            Self::InstagramSquare => Some(1.0),
+        }
+    }
+
+    /// Draw the guide inside of the `selection`
+    pub fn draw(self, frame: &mut canvas::Frame, selection: Rectangle, color: Color) {
+        let Some(aspect_ratio) = self.aspect_ratio() else {
+            return;
+        };
+
+        let area = fit(selection, aspect_ratio);
+
+        frame.stroke_rectangle(
+            area.position(),
+            area.size(),
+            canvas::Stroke::default()
+                .with_color(color)
+                .with_width(GUIDE_WIDTH),
+        );
+
+        if self == Self::YoutubeThumbnail {
+            // the duration of the video is displayed in the bottom-right corner,
+            // so anything important in there will be hidden
+            let badge = Size::new(area.width * 0.2, area.height * 0.15);
+
+            frame.fill_rectangle(
+                Point::new(
+                    area.x + area.width - badge.width,
+                    area.y + area.height - badge.height,
+                ),
+                badge,
+                color.scale_alpha(0.3),
+            );
+        }
+    }
+}
+
+/// Largest rectangle with the given `aspect_ratio` which fits in the `rect`, centered
+fn fit(rect: Rectangle, aspect_ratio: f32) -> Rectangle {
+    let size = if rect.width / rect.height > aspect_ratio {
+        Size::new(rect.height * aspect_ratio, rect.height)
+    } else {
+        Size::new(rect.width, rect.width / aspect_ratio)
+    };
+
+    Rectangle::new(
+        Point::new(
+            rect.x + (rect.width - size.width) / 2.0,
+            rect.y + (rect.height - size.height) / 2.0,
+        ),
+        size,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn fit_wide_rect() {
+        assert_eq!(
+            fit(
+                Rectangle::new(Point::new(0.0, 0.0), Size::new(400.0, 100.0)),
+                1.0
+            ),
+            Rectangle::new(Point::new(150.0, 0.0), Size::new(100.0, 100.0))
+        );
+    }
+
+    #[test]
+    fn fit_tall_rect() {
+        assert_eq!(
+            fit(
+                Rectangle::new(Point::new(10.0, 10.0), Size::new(160.0, 400.0)),
+                16.0 / 9.0
+            ),
+            Rectangle::new(Point::new(10.0, 165.0), Size::new(160.0, 90.0))
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! Histogram of the selection, for checking the exposure of captured frames
+//!
+//! Shown in a panel in the bottom-right corner of the screen, and updates as the selection changes
+
+use iced::{
+    Background, Color, Element,
+    Length::Fill,
+    Point, Rectangle, Renderer, Size, Task, Theme,
+    widget::{
+        button, canvas,
+        canvas::{Path, Stroke},
+        column, container, horizontal_space, row, text,
+    },
+};
+use image::GenericImageView as _;
+
+use crate::geometry::RectangleExt as _;
+
+crate::declare_commands! {
+    enum Command {
+        /// Show or hide the histogram of the selection
+        ToggleHistogram,
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
+        match self {
+            Self::ToggleHistogram => {
+                app.histogram.is_open = !app.histogram.is_open;
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// Histogram message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Collapse the panel to just its header, or expand it again
+    ToggleCollapsed,
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        match self {
+            Self::ToggleCollapsed => {
+                app.histogram.is_collapsed = !app.histogram.is_collapsed;
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// State of the histogram panel
+#[derive(Debug, Default)]
+pub struct State {
+    /// The panel is shown
+    pub is_open: bool,
+    /// Only the header of the panel is shown
+    pub is_collapsed: bool,
+}
+
+/// At most this many pixels are counted, so the histogram can follow the selection smoothly
+const MAX_SAMPLES: u64 = 50_000;
+
+/// Size of the graph
+const GRAPH_SIZE: Size = Size::new(256.0, 120.0);
+
+/// Number of pixels with each value, for each channel
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Histogram {
+    /// Perceived brightness
+    luminance: [u32; 256],
+    /// Red channel
+    red: [u32; 256],
+    /// Green channel
+    green: [u32; 256],
+    /// Blue channel
+    blue: [u32; 256],
+}
+
+impl Histogram {
+    /// Histogram of the `rect` inside of the `image`
+    fn new(image: &crate::image::RgbaHandle, rect: Rectangle) -> Self {
+        let mut histogram = Self {
+            luminance: [0; 256],
+            red: [0; 256],
+            green: [0; 256],
+            blue: [0; 256],
+        };
+
+        let image = image.as_image();
+        let rect = rect.intersection(&Rectangle::new(
+            Point::ORIGIN,
+            Size::new(image.width() as f32, image.height() as f32),
+        ));
+        let Some(rect) = rect else {
+            return histogram;
+        };
+
+        let view = image.view(
+            rect.x as u32,
+            rect.y as u32,
+            rect.width as u32,
+            rect.height as u32,
+        );
+
+        // only look at every `step`th row and column of large selections
+        let pixel_count = u64::from(view.width()) * u64::from(view.height());
+        let step = (pixel_count as f64 / MAX_SAMPLES as f64)
+            .sqrt()
+            .ceil()
+            .max(1.0) as usize;
+
+        for y in (0..view.height()).step_by(step) {
+            for x in (0..view.width()).step_by(step) {
+                let [r, g, b, _] = view.get_pixel(x, y).0;
+                histogram.red[usize::from(r)] += 1;
+                histogram.green[usize::from(g)] += 1;
+                histogram.blue[usize::from(b)] += 1;
+                histogram.luminance[usize::from(luminance(r, g, b))] += 1;
+            }
+        }
+
+        histogram
+    }
+
+    /// Largest count of any value in any channel
+    fn max(&self) -> u32 {
+        [&self.luminance, &self.red, &self.green, &self.blue]
+            .into_iter()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Perceived brightness of the color (Rec. 709)
+fn luminance(r: u8, g: u8, b: u8) -> u8 {
+    (0.2126 * f32::from(r) + 0.7152 * f32::from(g) + 0.0722 * f32::from(b)).round() as u8
+}
+
+/// Graph of the histogram
+struct Graph<'app> {
+    /// The histogram
+    histogram: Histogram,
+    /// Theme of the app
+    theme: &'app crate::Theme,
+}
+
+impl canvas::Program<crate::Message> for Graph<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: iced::advanced::mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+
+        let max = self.histogram.max();
+        if max == 0 {
+            return vec![frame.into_geometry()];
+        }
+
+        // point of the curve for the `count` of the `value`
+        let point = |value: usize, count: u32| {
+            Point::new(
+                value as f32 / 255.0 * bounds.width,
+                bounds.height - count as f32 / max as f32 * bounds.height,
+            )
+        };
+
+        let curve = |counts: &[u32; 256]| {
+            Path::new(|path| {
+                path.move_to(point(0, counts[0]));
+                for (value, &count) in counts.iter().enumerate().skip(1) {
+                    path.line_to(point(value, count));
+                }
+            })
+        };
+
+        // the luminance is filled in, the color channels are lines on top of it
+        let luminance = Path::new(|path| {
+            path.move_to(Point::new(0.0, bounds.height));
+            for (value, &count) in self.histogram.luminance.iter().enumerate() {
+                path.line_to(point(value, count));
+            }
+            path.line_to(Point::new(bounds.width, bounds.height));
+            path.close();
+        });
+        frame.fill(&luminance, self.theme.histogram_fg.scale_alpha(0.4));
+
+        for (counts, color) in [
+            (&self.histogram.red, Color::from_rgb8(255, 80, 80)),
+            (&self.histogram.green, Color::from_rgb8(80, 255, 80)),
+            (&self.histogram.blue, Color::from_rgb8(80, 140, 255)),
+        ] {
+            frame.stroke(
+                &curve(counts),
+                Stroke::default().with_color(color).with_width(1.0),
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Panel with the histogram of the selection, in the bottom-right corner of the screen
+pub fn histogram(app: &crate::App) -> Option<Element<crate::Message>> {
+    /// Space between the panel and the edges of the screen
+    const MARGIN: f32 = 10.0;
+
+    if !app.histogram.is_open {
+        return None;
+    }
+
+    let theme = &app.config.theme;
+
+    let header = row![
+        text("Histogram").font(theme.font),
+        horizontal_space().width(Fill),
+        button(
+            text(if app.histogram.is_collapsed { "+" } else { "-" })
+                .font(theme.font)
+                .color(theme.histogram_fg)
+        )
+        .padding([0.0, 6.0])
+        .on_press(crate::Message::Histogram(Message::ToggleCollapsed))
+        .style(|_, _| button::Style {
+            background: Some(Background::Color(Color::TRANSPARENT)),
+            ..Default::default()
+        }),
+    ];
+
+    let graph: Element<_> = match app.selection.map(|sel| sel.rect.norm()) {
+        _ if app.histogram.is_collapsed => column![].into(),
+        Some(rect) => canvas(Graph {
+            histogram: Histogram::new(&app.image, rect),
+            theme,
+        })
+        .width(GRAPH_SIZE.width)
+        .height(GRAPH_SIZE.height)
+        .into(),
+        None => container(text("Nothing is selected"))
+            .center_x(GRAPH_SIZE.width)
+            .center_y(GRAPH_SIZE.height)
+            .into(),
+    };
+
+    let panel = container(column![header, graph].spacing(5.0).width(GRAPH_SIZE.width))
+        .padding(10.0)
+        .style(|_| container::Style {
+            text_color: Some(theme.histogram_fg),
+            background: Some(Background::Color(theme.histogram_bg)),
+            border: theme.info_box_border(),
+            ..Default::default()
+        });
+
+    Some(
+        container(panel)
+            .align_right(Fill)
+            .align_bottom(Fill)
+            .padding(MARGIN)
+            .into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn counts_pixels_of_the_selection() {
+        let pixels = [
+            [255_u8, 0, 0, 255],
+            [255, 0, 0, 255],
+            [0, 0, 0, 255],
+            [255, 255, 255, 255],
+        ];
+        let image = crate::image::RgbaHandle::new(2, 2, pixels.concat());
+
+        // only the top row
+        let histogram = Histogram::new(&image, Rectangle::new(Point::ORIGIN, Size::new(2.0, 1.0)));
+
+        assert_eq!(histogram.red[255], 2);
+        assert_eq!(histogram.green[0], 2);
+        assert_eq!(histogram.luminance[usize::from(luminance(255, 0, 0))], 2);
+        assert_eq!(histogram.max(), 2);
+
+        let histogram = Histogram::new(&image, image.bounds());
+
+        assert_eq!(histogram.luminance[0], 1);
+        assert_eq!(histogram.luminance[255], 1);
+        assert_eq!(histogram.blue[0], 3);
+    }
+}
@@ -0,0 +1,191 @@
+//! Undo and redo changes to the selection and the annotations
+//!
+//! After every message the state is compared to the last one that was remembered.
+//! Dragging the selection or drawing a shape changes the state many times, so those
+//! are only remembered once they are finished, and undo as a single step.
+
+use std::collections::VecDeque;
+
+use iced::{Rectangle, Task};
+
+use crate::ui::annotations::Shape;
+use crate::ui::selection::{Selection, SelectionStatus};
+
+/// How many steps can be undone
+const MAX_UNDO: usize = 100;
+
+crate::declare_commands! {
+    enum Command {
+        /// Undo the last change to the selection or the annotations
+        Undo,
+        /// Redo the last change which was undone
+        Redo,
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, count: u32) -> Task<crate::Message> {
+        let mut restored = None;
+
+        for _ in 0..count {
+            let Some(snapshot) = (match self {
+                Self::Undo => app.history.undo(),
+                Self::Redo => app.history.redo(),
+            }) else {
+                break;
+            };
+            restored = Some(snapshot);
+        }
+
+        match restored {
+            Some(snapshot) => snapshot.restore(app),
+            None => app.errors.info(match self {
+                Self::Undo => "Nothing to undo",
+                Self::Redo => "Nothing to redo",
+            }),
+        }
+
+        Task::none()
+    }
+}
+
+/// The state which can be undone
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Snapshot {
+    /// Area of the selection
+    selection: Option<Rectangle>,
+    /// Finished annotations
+    shapes: Vec<Shape>,
+}
+
+impl Snapshot {
+    /// Snapshot of the `app`, unless it is in the middle of a change
+    pub fn of(app: &crate::App) -> Option<Self> {
+        let is_dragging = app
+            .selection
+            .is_some_and(|sel| sel.status != SelectionStatus::Idle);
+
+        (!is_dragging && app.annotations.drawing.is_none()).then(|| Self {
+            selection: app.selection.map(|sel| sel.rect),
+            shapes: app.annotations.shapes.clone(),
+        })
+    }
+
+    /// Put the `app` back into the state of this snapshot
+    fn restore(self, app: &mut crate::App) {
+        app.selection = self.selection.map(|rect| match app.selection {
+            Some(sel) => Selection {
+                rect,
+                status: SelectionStatus::Idle,
+                ..sel
+            },
+            None => Selection {
+                is_first: false,
+                accept_on_select: None,
+                theme: app.config.theme,
+                rect,
+                status: SelectionStatus::Idle,
+            },
+        });
+        app.annotations.shapes = self.shapes;
+    }
+}
+
+/// Changes which can be undone and redone
+#[derive(Debug, Default)]
+pub struct History {
+    /// The state before each change, the most recent one last
+    undo: VecDeque<Snapshot>,
+    /// Changes which were undone, the most recently undone one last
+    redo: Vec<Snapshot>,
+    /// The state that was remembered last, `None` before the first one
+    current: Option<Snapshot>,
+}
+
+impl History {
+    /// Remember the `snapshot`, if it differs from the current state
+    pub fn record(&mut self, snapshot: Snapshot) {
+        let Some(current) = self.current.take_if(|current| *current != snapshot) else {
+            // first state of the app, or nothing changed
+            self.current.get_or_insert(snapshot);
+            return;
+        };
+
+        if self.undo.len() >= MAX_UNDO {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(current);
+        self.redo.clear();
+        self.current = Some(snapshot);
+    }
+
+    /// Go back to the state before the last change
+    fn undo(&mut self) -> Option<Snapshot> {
+        let previous = self.undo.pop_back()?;
+        self.redo.extend(self.current.replace(previous.clone()));
+
+        Some(previous)
+    }
+
+    /// Go forward to the state before the last undo
+    fn redo(&mut self) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.undo.extend(self.current.replace(next.clone()));
+
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::{Point, Size};
+    use pretty_assertions::assert_eq;
+
+    fn selected(size: f32) -> Snapshot {
+        Snapshot {
+            selection: Some(Rectangle::new(Point::ORIGIN, Size::new(size, size))),
+            shapes: vec![],
+        }
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut history = History::default();
+        history.record(Snapshot::default());
+        history.record(selected(10.0));
+        history.record(selected(20.0));
+
+        assert_eq!(history.undo(), Some(selected(10.0)));
+        assert_eq!(history.undo(), Some(Snapshot::default()));
+        assert_eq!(history.undo(), None);
+
+        assert_eq!(history.redo(), Some(selected(10.0)));
+        assert_eq!(history.redo(), Some(selected(20.0)));
+        assert_eq!(history.redo(), None);
+    }
+
+    #[test]
+    fn unchanged_state_is_not_a_step() {
+        let mut history = History::default();
+        history.record(Snapshot::default());
+        history.record(Snapshot::default());
+        history.record(selected(10.0));
+        history.record(selected(10.0));
+
+        assert_eq!(history.undo(), Some(Snapshot::default()));
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn change_after_undo_clears_redo() {
+        let mut history = History::default();
+        history.record(Snapshot::default());
+        history.record(selected(10.0));
+        history.undo();
+
+        history.record(selected(30.0));
+        assert_eq!(history.redo(), None);
+        assert_eq!(history.undo(), Some(Snapshot::default()));
+    }
+}
@@ -0,0 +1,161 @@
+//! A magnifier over the screenshot, for looking at small details
+//!
+//! Shown in the `magnify` mode, centered on the cursor. `capture-magnifier`
+//! selects exactly the region the magnifier shows, and runs the `primary-action` on it.
+
+use iced::{
+    Point, Rectangle, Task,
+    widget::{canvas, image},
+};
+
+use crate::geometry::RectangleExt as _;
+use crate::ui::{mode::Mode, selection::Selection};
+
+/// Width and height of the magnifier on the screen
+const SIZE: f32 = 400.0;
+
+/// Width of the frame around the magnifier
+const FRAME_WIDTH: f32 = 2.0;
+
+crate::declare_commands! {
+    enum Command {
+        /// Select exactly what the magnifier shows, and run the `primary-action` on it
+        CaptureMagnifier,
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
+        match self {
+            Self::CaptureMagnifier => {
+                let Some(cursor) = app.magnifier.filter(|_| app.mode == Mode::Magnify) else {
+                    app.errors.warn("The magnifier is not shown");
+                    return Task::none();
+                };
+
+                let rect = region(cursor, app.config.magnifier_zoom, app.image.bounds());
+
+                app.selection = Some(
+                    Selection::new(
+                        rect.top_left(),
+                        &app.config.theme,
+                        app.selections_created == 0,
+                        None,
+                    )
+                    .with_size(|_| rect.size()),
+                );
+                app.selections_created += 1;
+                app.mode = Mode::Select;
+
+                crate::command::Handler::handle(app.config.primary_action, app, 1)
+            }
+        }
+    }
+}
+
+/// Magnifier message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// The cursor moved to this position
+    Move(Point),
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        match self {
+            Self::Move(position) => app.magnifier = Some(position),
+        }
+
+        Task::none()
+    }
+}
+
+/// Region of the image shown by the magnifier when the cursor is at `cursor`
+///
+/// The region is kept inside of the image, and has whole pixel coordinates
+/// so it can be captured exactly
+fn region(cursor: Point, zoom: u32, image: Rectangle) -> Rectangle {
+    let side = (SIZE / zoom.max(1) as f32)
+        .round()
+        .min(image.width)
+        .min(image.height);
+
+    Rectangle {
+        x: (cursor.x - side / 2.0)
+            .round()
+            .clamp(0.0, image.width - side),
+        y: (cursor.y - side / 2.0)
+            .round()
+            .clamp(0.0, image.height - side),
+        width: side,
+        height: side,
+    }
+}
+
+/// Draw the magnifier centered on the `cursor`
+pub fn draw(frame: &mut canvas::Frame, app: &crate::App, cursor: Point, bounds: Rectangle) {
+    let zoom = app.config.magnifier_zoom.max(1) as f32;
+    let region = region(cursor, app.config.magnifier_zoom, app.image.bounds());
+
+    // where the magnifier is on the screen
+    let lens = Rectangle {
+        x: (cursor.x - SIZE / 2.0).clamp(0.0, (bounds.width - SIZE).max(0.0)),
+        y: (cursor.y - SIZE / 2.0).clamp(0.0, (bounds.height - SIZE).max(0.0)),
+        width: region.width * zoom,
+        height: region.height * zoom,
+    };
+
+    frame.with_clip(lens, |frame| {
+        // the entire image is scaled, so the `region` ends up inside of the `lens`
+        frame.draw_image(
+            Rectangle {
+                x: -region.x * zoom,
+                y: -region.y * zoom,
+                width: app.image.width() as f32 * zoom,
+                height: app.image.height() as f32 * zoom,
+            },
+            canvas::Image::new(image::Handle::from(crate::image::RgbaHandle::clone(
+                &app.image,
+            )))
+            .filter_method(image::FilterMethod::Nearest),
+        );
+    });
+
+    frame.stroke_rectangle(
+        lens.top_left(),
+        lens.size(),
+        canvas::Stroke::default()
+            .with_color(app.config.theme.selection_frame)
+            .with_width(FRAME_WIDTH),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::Size;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn region_stays_inside_of_the_image() {
+        let image = Rectangle::new(Point::ORIGIN, Size::new(1000.0, 800.0));
+
+        assert_eq!(
+            region(Point::new(500.0, 400.0), 4, image),
+            Rectangle::new(Point::new(450.0, 350.0), Size::new(100.0, 100.0))
+        );
+        assert_eq!(
+            region(Point::new(5.0, 790.0), 4, image),
+            Rectangle::new(Point::new(0.0, 700.0), Size::new(100.0, 100.0))
+        );
+        // zoomed out further than the image
+        assert_eq!(
+            region(
+                Point::new(5.0, 5.0),
+                1,
+                Rectangle::new(Point::ORIGIN, Size::new(300.0, 200.0))
+            ),
+            Rectangle::new(Point::ORIGIN, Size::new(200.0, 200.0))
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! A ruler for measuring distances on the screenshot
+//!
+//! Shown in the `measure` mode. Dragging the mouse draws a line from where the drag started,
+//! labeled with its length and how far it goes horizontally and vertically, in pixels of
+//! the screenshot. It stays until the next drag.
+
+use iced::{
+    Point, Task, Vector,
+    widget::canvas::{self, Path},
+};
+
+/// Radius of the dots at both ends of the ruler
+const END_RADIUS: f32 = 3.0;
+
+/// Distance of the label from the end of the ruler
+const LABEL_OFFSET: f32 = 12.0;
+
+/// Size of the text of the label
+const LABEL_SIZE: f32 = 16.0;
+
+/// Line between 2 pixels of the screenshot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ruler {
+    /// Where the drag started
+    from: Point,
+    /// Where the drag is, or where it ended
+    to: Point,
+    /// Whether the mouse is still being dragged
+    is_dragging: bool,
+}
+
+impl Ruler {
+    /// Whether the mouse is still being dragged
+    pub const fn is_dragging(self) -> bool {
+        self.is_dragging
+    }
+
+    /// Length of the ruler, then how far it goes horizontally and vertically
+    fn label(self) -> String {
+        let Vector { x, y } = self.to - self.from;
+
+        format!("{:.0} px ({:.0}x{:.0})", x.hypot(y), x.abs(), y.abs())
+    }
+
+    /// Draw the ruler, with its label next to where it ends
+    pub fn draw(self, frame: &mut canvas::Frame, theme: &crate::Theme) {
+        frame.stroke(
+            &Path::line(self.from, self.to),
+            canvas::Stroke::default()
+                .with_color(theme.ruler)
+                .with_width(theme.frame_width()),
+        );
+
+        for end in [self.from, self.to] {
+            frame.fill(&Path::circle(end, END_RADIUS), theme.ruler);
+        }
+
+        let position = self.to + Vector::new(LABEL_OFFSET, LABEL_OFFSET);
+
+        // the shadow keeps the label readable on top of any screenshot
+        for (offset, color) in [(1.0, theme.drop_shadow), (0.0, theme.ruler)] {
+            frame.fill_text(canvas::Text {
+                content: self.label(),
+                position: position + Vector::new(offset, offset),
+                font: theme.font.into(),
+                color,
+                size: LABEL_SIZE.into(),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+/// Measure message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// The mouse was pressed at this position, so a new ruler starts there
+    Start(Point),
+    /// The mouse was dragged to this position
+    Move(Point),
+    /// The mouse was released
+    Finish,
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        // measured between whole pixels of the screenshot
+        let pixel = |point: Point| Point::new(point.x.round(), point.y.round());
+
+        match self {
+            Self::Start(point) => {
+                app.ruler = Some(Ruler {
+                    from: pixel(point),
+                    to: pixel(point),
+                    is_dragging: true,
+                });
+            }
+            Self::Move(point) => {
+                if let Some(ruler) = app.ruler.as_mut().filter(|ruler| ruler.is_dragging) {
+                    ruler.to = pixel(point);
+                }
+            }
+            Self::Finish => {
+                if let Some(ruler) = &mut app.ruler {
+                    ruler.is_dragging = false;
+                }
+            }
+        }
+
+        Task::none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn label_of_the_ruler() {
+        let ruler = |to| Ruler {
+            from: Point::new(100.0, 100.0),
+            to,
+            is_dragging: false,
+        };
+
+        assert_eq!(ruler(Point::new(130.0, 140.0)).label(), "50 px (30x40)");
+        // the direction does not matter
+        assert_eq!(ruler(Point::new(70.0, 60.0)).label(), "50 px (30x40)");
+        assert_eq!(ruler(Point::new(100.0, 100.0)).label(), "0 px (0x0)");
+    }
+}
@@ -2,12 +2,25 @@
 
 use iced::Element;
 
+pub mod accept_countdown;
+pub mod animation;
+pub mod annotations;
 pub mod app;
 mod background_image;
+pub mod crop_suggestion;
 pub mod debug_overlay;
-mod errors;
+pub mod errors;
+pub mod frames;
 mod grid;
-mod selection_icons;
+pub mod guides;
+pub mod histogram;
+pub mod history;
+pub mod magnifier;
+pub mod measure;
+pub mod mode;
+pub mod present;
+pub mod recent_regions;
+pub mod selection_icons;
 mod welcome_message;
 
 pub mod selection;
@@ -0,0 +1,85 @@
+//! Modes of ferrishot
+//!
+//! Each mode can have its own keybindings, declared with `mode "name" { ... }` inside
+//! of `keys`. This lets the same key do different things depending on what the user
+//! is doing, instead of every feature competing for the few remaining keys.
+
+use iced::{
+    Background, Element,
+    Length::Fill,
+    Task,
+    widget::{container, text},
+};
+
+crate::declare_commands! {
+    enum Command {
+        /// Switch to the given mode
+        SetMode {
+            mode: Mode,
+        },
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
+        match self {
+            Self::SetMode { mode } => app.mode = mode,
+        }
+
+        Task::none()
+    }
+}
+
+/// What the user is currently doing
+#[derive(ferrishot_knus::DecodeScalar, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Create and transform the selection
+    #[default]
+    Select,
+    /// Draw on top of the screenshot
+    Annotate,
+    /// Measure distances on the screenshot by dragging the mouse
+    Measure,
+    /// Magnify the screenshot around the cursor
+    Magnify,
+}
+
+impl Mode {
+    /// Name of the mode shown to the user
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Select => "SELECT",
+            Self::Annotate => "ANNOTATE",
+            Self::Measure => "MEASURE",
+            Self::Magnify => "MAGNIFY",
+        }
+    }
+}
+
+/// Shows the current mode in the bottom-left corner of the screen
+///
+/// Nothing is shown in the default `Select` mode
+pub fn mode_indicator(app: &crate::App) -> Option<Element<crate::Message>> {
+    /// Space between the indicator and the edges of the screen
+    const MARGIN: f32 = 10.0;
+
+    (app.mode != Mode::Select).then(|| {
+        let indicator = container(
+            text(app.mode.label())
+                .font(app.config.theme.font)
+                .color(app.config.theme.mode_indicator_fg),
+        )
+        .padding([2.0, 8.0])
+        .style(|_| container::Style {
+            background: Some(Background::Color(app.config.theme.mode_indicator_bg)),
+            border: app.config.theme.info_box_border(),
+            ..Default::default()
+        });
+
+        container(indicator)
+            .align_left(Fill)
+            .align_bottom(Fill)
+            .padding(MARGIN)
+            .into()
+    })
+}
@@ -0,0 +1,69 @@
+//! Ask the user to confirm exiting while a selection exists
+//!
+//! Shown instead of exiting when `confirm-exit` is enabled, so that a stray
+//! `Esc` doesn't throw away a carefully adjusted selection
+
+use iced::{
+    Background, Element,
+    Length::Fill,
+    Size,
+    widget::{button, column, container, horizontal_space, row, text},
+};
+
+/// Confirmation popup for exiting the app
+#[derive(Debug, Copy, Clone)]
+pub struct ConfirmExit<'app> {
+    /// Theme of the app
+    pub theme: &'app crate::Theme,
+    /// Keybindings of the app
+    pub keys: &'app crate::config::key_map::KeyMap,
+}
+
+impl<'app> ConfirmExit<'app> {
+    /// Render the confirmation popup
+    pub fn view(self) -> Element<'app, crate::Message> {
+        let size = Size::new(400.0, 150.0);
+
+        let choice = |label, message| {
+            button(text(label).color(self.theme.icon_fg))
+                .on_press(message)
+                .style(|_, _| button::Style {
+                    background: Some(Background::Color(self.theme.icon_bg)),
+                    border: self.theme.button_border(),
+                    ..Default::default()
+                })
+        };
+
+        super::popup(
+            size,
+            container(
+                column![
+                    text("Exit without capturing the selection?").size(20.0),
+                    row![
+                        horizontal_space().width(Fill),
+                        choice(
+                            format!("Cancel ({})", super::cancel_keys(self.keys)),
+                            crate::Message::ClosePopup
+                        ),
+                        choice(
+                            format!("Exit ({})", super::confirm_keys(self.keys)),
+                            crate::Message::Exit
+                        ),
+                    ]
+                    .spacing(10.0)
+                ]
+                .spacing(30.0),
+            )
+            .padding(20.0)
+            .style(|_| container::Style {
+                text_color: Some(self.theme.confirm_exit_fg),
+                background: Some(Background::Color(self.theme.confirm_exit_bg)),
+                border: self.theme.popup_border(self.theme.confirm_exit_fg),
+                ..Default::default()
+            })
+            .width(size.width)
+            .height(size.height),
+            self.theme,
+        )
+    }
+}
@@ -0,0 +1,104 @@
+//! Preview exactly what will be captured, before running the action
+//!
+//! Shown when `confirm-preview` is enabled. The preview is the final image,
+//! cropped and with the stamp applied, so there are no surprises about what
+//! ends up in the clipboard.
+
+use iced::{
+    Background, Element,
+    Length::Fill,
+    Size,
+    widget::{button, column, container, horizontal_space, image, row, text},
+};
+
+use crate::image::action;
+
+/// State of the preview popup
+#[derive(Debug)]
+pub struct State {
+    /// Action to run once the preview is confirmed
+    pub action: action::Command,
+    /// The final image
+    pub image: image::Handle,
+    /// Width of the final image
+    pub width: u32,
+    /// Height of the final image
+    pub height: u32,
+}
+
+/// Popup with a preview of the captured image
+#[derive(Debug, Copy, Clone)]
+pub struct ConfirmPreview<'app> {
+    /// Theme of the app
+    pub theme: &'app crate::Theme,
+    /// Keybindings of the app
+    pub keys: &'app crate::config::key_map::KeyMap,
+    /// State of the popup
+    pub state: &'app State,
+}
+
+impl<'app> ConfirmPreview<'app> {
+    /// Render the preview popup
+    pub fn view(self) -> Element<'app, crate::Message> {
+        let size = Size::new(700.0, 550.0);
+
+        let choice = |label, message| {
+            button(text(label).color(self.theme.icon_fg))
+                .on_press(message)
+                .style(|_, _| button::Style {
+                    background: Some(Background::Color(self.theme.icon_bg)),
+                    border: self.theme.button_border(),
+                    ..Default::default()
+                })
+        };
+
+        let action = match self.state.action {
+            action::Command::CopyToClipboard => "Copy",
+            action::Command::SaveScreenshot => "Save",
+            action::Command::SaveAsPdf => "Save as PDF",
+            action::Command::UploadScreenshot => "Upload",
+        };
+
+        super::popup(
+            size,
+            container(
+                column![
+                    text(format!(
+                        "This will be captured ({} x {} px)",
+                        self.state.width, self.state.height
+                    ))
+                    .size(20.0),
+                    container(image(self.state.image.clone()))
+                        .center(Fill)
+                        .height(Fill),
+                    row![
+                        horizontal_space().width(Fill),
+                        choice(
+                            format!("Cancel ({})", super::cancel_keys(self.keys)),
+                            crate::Message::ClosePopup
+                        ),
+                        choice(
+                            format!("{action} ({})", super::confirm_keys(self.keys)),
+                            crate::Message::Command {
+                                action: self.state.action.into_key_action(),
+                                count: 1,
+                            }
+                        ),
+                    ]
+                    .spacing(10.0)
+                ]
+                .spacing(20.0),
+            )
+            .padding(20.0)
+            .style(|_| container::Style {
+                text_color: Some(self.theme.confirm_preview_fg),
+                background: Some(Background::Color(self.theme.confirm_preview_bg)),
+                border: self.theme.popup_border(self.theme.confirm_preview_fg),
+                ..Default::default()
+            })
+            .width(size.width)
+            .height(size.height),
+            self.theme,
+        )
+    }
+}
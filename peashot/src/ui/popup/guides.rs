@@ -0,0 +1,69 @@
+//! Pick a safe area guide to show inside of the selection
+
+use iced::{
+    Background, Element,
+    Length::Fill,
+    Size,
+    widget::{Column, button, column, container, text},
+};
+use strum::IntoEnumIterator as _;
+
+use crate::ui::guides::{self, Guide};
+
+/// Popup listing all of the guides
+#[derive(Debug, Copy, Clone)]
+pub struct Guides<'app> {
+    /// Theme of the app
+    pub theme: &'app crate::Theme,
+    /// The guide which is currently shown
+    pub current: Guide,
+}
+
+impl<'app> Guides<'app> {
+    /// Render the list of guides
+    pub fn view(self) -> Element<'app, crate::Message> {
+        let size = Size::new(400.0, 300.0);
+
+        let guides = Guide::iter().map(|guide| {
+            let (fg, bg) = if guide == self.current {
+                (self.theme.icon_fg, self.theme.icon_bg)
+            } else {
+                (self.theme.guides_fg, iced::Color::TRANSPARENT)
+            };
+
+            button(text(guide.label()).color(fg))
+                .width(Fill)
+                .on_press(crate::Message::Command {
+                    action: crate::Command::Guides(guides::Command::SetGuide { guide }),
+                    count: 1,
+                })
+                .style(move |_, _| button::Style {
+                    background: Some(Background::Color(bg)),
+                    border: self.theme.button_border(),
+                    ..Default::default()
+                })
+                .into()
+        });
+
+        super::popup(
+            size,
+            container(
+                column![
+                    text("Guides").size(24.0),
+                    Column::with_children(guides).spacing(5.0)
+                ]
+                .spacing(20.0),
+            )
+            .padding(20.0)
+            .style(|_| container::Style {
+                text_color: Some(self.theme.guides_fg),
+                background: Some(Background::Color(self.theme.guides_bg)),
+                border: self.theme.popup_border(self.theme.guides_fg),
+                ..Default::default()
+            })
+            .width(size.width)
+            .height(size.height),
+            self.theme,
+        )
+    }
+}
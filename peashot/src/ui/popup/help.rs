@@ -0,0 +1,291 @@
+//! Searchable help for every command and config option
+//!
+//! Commands are described by their doc comments, and show the keys which run them.
+//! Config options are listed next to them, so searching for a feature also finds
+//! the option which changes how it behaves.
+
+use std::collections::HashMap;
+
+use iced::{
+    Background, Element,
+    Length::Fill,
+    Size, Task,
+    widget::{Column, column, container, row, scrollable, text, text_input},
+};
+
+use super::Popup;
+
+crate::declare_commands! {
+    enum Command {
+        /// Search the commands and config options, with their keybindings
+        OpenHelp,
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
+        match self {
+            Self::OpenHelp => {
+                app.popup = Some(Popup::Help(State::new(&app.config)));
+            }
+        }
+
+        text_input::focus(SEARCH_ID)
+    }
+}
+
+/// Id of the search input, to focus it when the help opens
+const SEARCH_ID: &str = "help-search";
+
+/// Show at most this many results. The rest are found by narrowing down the search
+const MAX_RESULTS: usize = 50;
+
+/// What an entry of the help describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A command, which can be bound to keys
+    Command,
+    /// An option of the config file
+    Option,
+}
+
+/// A command or a config option
+#[derive(Debug)]
+pub struct Entry {
+    /// What the entry describes
+    kind: Kind,
+    /// Name as written in the config file, like `select-region`
+    name: String,
+    /// What it does, from its doc comment
+    description: String,
+    /// Keys which run the command
+    keys: Vec<String>,
+}
+
+impl Entry {
+    /// How well the entry matches the `query`. Matches in the name count for more
+    fn score(&self, query: &str) -> Option<u32> {
+        let name = fuzzy_score(query, &self.name).map(|score| score * 2);
+        let description = fuzzy_score(query, &self.description);
+
+        name.max(description)
+    }
+}
+
+/// State of the help popup
+#[derive(Debug)]
+pub struct State {
+    /// What the user searched for
+    pub query: String,
+    /// Every command and config option, sorted by name
+    entries: Vec<Entry>,
+}
+
+impl State {
+    /// Collect the commands and options, with the keys bound in the `config`
+    pub fn new(config: &crate::Config) -> Self {
+        let mut keys = HashMap::<String, Vec<String>>::new();
+        for (mode, key, binding) in config.keys.bindings() {
+            let key = match mode {
+                Some(mode) => format!("{key} ({})", mode.label().to_lowercase()),
+                None => key,
+            };
+            keys.entry(binding.command.name()).or_default().push(key);
+        }
+
+        let commands = crate::Command::docs()
+            .into_iter()
+            .map(|(name, description)| {
+                let mut keys = keys.remove(&name).unwrap_or_default();
+                keys.sort();
+
+                Entry {
+                    kind: Kind::Command,
+                    name,
+                    description,
+                    keys,
+                }
+            });
+        let options = crate::Config::docs()
+            .into_iter()
+            .map(|(name, description)| Entry {
+                kind: Kind::Option,
+                name,
+                description,
+                keys: Vec::new(),
+            });
+
+        let mut entries = commands.chain(options).collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Self {
+            query: String::new(),
+            entries,
+        }
+    }
+
+    /// Entries matching the query, best match first
+    fn results(&self) -> Vec<&Entry> {
+        let mut results = self
+            .entries
+            .iter()
+            .filter_map(|entry| Some((entry.score(&self.query)?, entry)))
+            .collect::<Vec<_>>();
+        // stable, so entries which match equally well stay sorted by name
+        results.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        results.into_iter().map(|(_, entry)| entry).collect()
+    }
+}
+
+/// How well the `query` matches the `text`, if all of its characters appear in order,
+/// ignoring case. Consecutive characters, and characters at the start of a word score higher
+fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let mut text = text.chars().map(|ch| ch.to_ascii_lowercase());
+    let mut previous = None::<char>;
+    let mut is_previous_matched = false;
+    let mut score = 0;
+
+    for query_ch in query
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(|ch| ch.to_ascii_lowercase())
+    {
+        loop {
+            let ch = text.next()?;
+            let is_word_start = previous.is_none_or(|previous| !previous.is_alphanumeric());
+            previous = Some(ch);
+
+            if ch == query_ch {
+                score +=
+                    1 + if is_previous_matched { 2 } else { 0 } + if is_word_start { 3 } else { 0 };
+                is_previous_matched = true;
+                break;
+            }
+
+            is_previous_matched = false;
+        }
+    }
+
+    Some(score)
+}
+
+/// Help message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// The search query changed
+    Search(String),
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        match self {
+            Self::Search(query) => {
+                if let Some(state) = app.popup.as_mut().and_then(|p| p.try_as_help_mut()) {
+                    state.query = query;
+                }
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// Popup to search the commands and config options
+#[derive(Debug, Copy, Clone)]
+pub struct Help<'app> {
+    /// Theme of the app
+    pub theme: &'app crate::Theme,
+    /// State of the popup
+    pub state: &'app State,
+}
+
+impl<'app> Help<'app> {
+    /// Render the help
+    pub fn view(self) -> Element<'app, crate::Message> {
+        let size = Size::new(800.0, 700.0);
+        let theme = self.theme;
+        let dimmed = theme.cheatsheet_fg.scale_alpha(0.6);
+
+        let results = self.state.results();
+        let hidden = results.len().saturating_sub(MAX_RESULTS);
+
+        let entries = results.into_iter().take(MAX_RESULTS).map(|entry| {
+            let keys = match entry.kind {
+                Kind::Command => entry.keys.join(", "),
+                Kind::Option => "option".to_string(),
+            };
+
+            column![
+                row![
+                    text(entry.name.as_str()).font(theme.font).width(Fill),
+                    text(keys).font(theme.font).color(dimmed),
+                ]
+                .spacing(16.0),
+                text(entry.description.as_str()).size(14.0).color(dimmed),
+            ]
+            .spacing(4.0)
+            .into()
+        });
+
+        let search = text_input("Search commands and options", &self.state.query)
+            .id(SEARCH_ID)
+            .on_input(|query| crate::Message::Help(Message::Search(query)))
+            .padding(8.0)
+            .style(move |_, _| text_input::Style {
+                background: Background::Color(theme.cheatsheet_bg),
+                border: theme.button_border().width(1.0).color(dimmed),
+                icon: theme.cheatsheet_fg,
+                placeholder: dimmed,
+                value: theme.cheatsheet_fg,
+                selection: theme.text_selection,
+            });
+
+        super::popup(
+            size,
+            container(
+                column![
+                    text("Help").size(24.0),
+                    search,
+                    scrollable(Column::with_children(entries).spacing(16.0).push_maybe(
+                        (hidden > 0).then(|| {
+                            text(format!("{hidden} more, narrow down the search")).color(dimmed)
+                        })
+                    ))
+                    .height(Fill),
+                ]
+                .spacing(20.0),
+            )
+            .padding(20.0)
+            .style(move |_| container::Style {
+                text_color: Some(theme.cheatsheet_fg),
+                background: Some(Background::Color(theme.cheatsheet_bg)),
+                border: theme.popup_border(theme.cheatsheet_fg),
+                ..Default::default()
+            })
+            .width(size.width)
+            .height(size.height),
+            self.theme,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn fuzzy_score_prefers_word_starts() {
+        assert_eq!(fuzzy_score("", "select-region"), Some(0));
+        assert_eq!(fuzzy_score("xz", "halve-width"), None);
+        // `s` and `r` both start a word of `select-region`
+        assert!(fuzzy_score("sr", "select-region") > fuzzy_score("sr", "suggest-crop"));
+        // consecutive characters
+        assert!(fuzzy_score("sel", "select-region") > fuzzy_score("sel", "save-screenshot-else"));
+        assert_eq!(
+            fuzzy_score("Select Region", "select-region"),
+            fuzzy_score("selectregion", "select-region")
+        );
+    }
+}
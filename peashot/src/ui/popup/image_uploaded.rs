@@ -55,7 +55,31 @@ impl crate::message::Handler for Message {
                 }
             }
             Self::CopyLink(url) => {
-                if let Err(err) = crate::clipboard::set_text(&url) {
+                // rich text editors and chat apps paste the image itself, linking to the upload.
+                // Image editors paste the image, and everything else pastes the link
+                let html = format!(
+                    r#"<a href="{url}"><img src="{url}" alt="Screenshot"></a>"#,
+                    url = escape_html(&url)
+                );
+                let image = app
+                    .popup
+                    .as_ref()
+                    .and_then(|p| p.try_as_image_uploaded_ref())
+                    .map(|state| state.url.1.encoded_image.clone());
+
+                let offer = match image {
+                    Some(image) => crate::clipboard::Offer::HtmlWithImage {
+                        html: html.into(),
+                        alt_text: url.as_str().into(),
+                        image: image.to_vec().into(),
+                    },
+                    None => crate::clipboard::Offer::Html {
+                        html: html.into(),
+                        alt_text: url.as_str().into(),
+                    },
+                };
+
+                if let Err(err) = crate::clipboard::set(offer) {
                     app.errors.push(err.to_string());
                 } else {
                     if let Some(image_uploaded) = app
@@ -92,6 +116,14 @@ impl crate::message::Handler for Message {
     }
 }
 
+/// Escape characters which are special inside of an HTML attribute
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 /// Data of the uploaded image
 #[derive(Clone, Debug)]
 pub struct ImageUploadedData {
@@ -99,6 +131,8 @@ pub struct ImageUploadedData {
     pub image_uploaded: crate::image::upload::ImageUploaded,
     /// the uploaded image
     pub uploaded_image: iced::widget::image::Handle,
+    /// the uploaded image, encoded as it was uploaded
+    pub encoded_image: iced::advanced::image::Bytes,
     /// The height of the image
     pub height: u32,
     /// The width of the image
@@ -250,6 +284,11 @@ impl<'app> ImageUploaded<'app> {
             .style(|_| container::Style {
                 text_color: Some(self.app.config.theme.image_uploaded_fg),
                 background: Some(Background::Color(self.app.config.theme.image_uploaded_bg)),
+                border: self
+                    .app
+                    .config
+                    .theme
+                    .popup_border(self.app.config.theme.image_uploaded_fg),
                 ..Default::default()
             })
             .padding(30.0),
@@ -5,7 +5,7 @@ use iced::{
     Length::Fill,
     Pixels, Point, Rectangle, Renderer, Size, Task, Theme, Vector,
     advanced::{graphics::geometry, svg::Svg},
-    font::{self, Family, Weight},
+    font::{self, Weight},
     widget::{
         canvas,
         canvas::{LineCap, LineJoin, Stroke},
@@ -17,7 +17,10 @@ use iced::{
 use crate::{
     geometry::{PointExt as _, RectangleExt as _, SizeExt as _, VectorExt as _},
     icons::Icon,
-    ui::{grid::Grid, selection::Selection},
+    ui::{
+        grid::Grid,
+        selection::{Handles, Selection},
+    },
 };
 
 use super::Popup;
@@ -66,6 +69,8 @@ impl crate::message::Handler for Message {
 pub struct KeybindingsCheatsheet<'app> {
     /// Theme of the app
     pub theme: &'app crate::Theme,
+    /// Action that runs on `Enter`
+    pub primary_action: crate::image::action::Command,
 }
 
 impl<'app> KeybindingsCheatsheet<'app> {
@@ -77,6 +82,7 @@ impl<'app> KeybindingsCheatsheet<'app> {
             container(column![canvas(self).width(Fill).height(Fill)])
                 .style(|_| container::Style {
                     background: Some(Background::Color(self.theme.cheatsheet_bg)),
+                    border: self.theme.popup_border(self.theme.cheatsheet_fg),
                     ..Default::default()
                 })
                 .width(size.width)
@@ -288,12 +294,12 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
 
                         // draw selection AFTER transformation
                         new_sel.draw_border(frame);
-                        new_sel.draw_corners(frame);
+                        new_sel.draw_corners(frame, Handles::default().corner_radius as f32);
                     })
                     .label(canvas::Text {
                         content: key.to_string(),
                         color: self.theme.cheatsheet_fg,
-                        font: Font::MONOSPACE,
+                        font: self.theme.font.into(),
                         shaping: Shaping::Advanced,
                         ..Default::default()
                     })
@@ -301,10 +307,9 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
                         content: label.to_string(),
                         color: self.theme.selection_frame,
                         font: Font {
-                            family: Family::Monospace,
                             weight: Weight::Normal,
                             style: font::Style::Italic,
-                            ..Default::default()
+                            ..self.theme.font.into()
                         },
                         shaping: Shaping::Advanced,
                         ..Default::default()
@@ -322,7 +327,7 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
                 geometry::Text {
                     content: "Transform region by 1px:".to_string(),
                     color: self.theme.cheatsheet_fg,
-                    font: Font::MONOSPACE,
+                    font: self.theme.font.into(),
                     size: Pixels(30.0),
                     shaping: Shaping::Advanced,
                     ..Default::default()
@@ -335,7 +340,7 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
                         .to_string(),
                     color: self.theme.cheatsheet_fg,
                     size: Pixels(20.0),
-                    font: Font::MONOSPACE,
+                    font: self.theme.font.into(),
                     shaping: Shaping::Advanced,
                     ..Default::default()
                 },
@@ -348,6 +353,16 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
 
         basic_bindings.draw(&mut frame);
 
+        frame.fill_text(canvas::Text {
+            content: format!("Enter: {}", self.primary_action.description()),
+            position: Point::new(60.0, basic_bindings_size.height + 40.0),
+            color: self.theme.cheatsheet_fg,
+            size: Pixels(20.0),
+            font: self.theme.font.into(),
+            shaping: Shaping::Advanced,
+            ..Default::default()
+        });
+
         let region_movement_bindings_data: &[(&str, &str, SelectionTransformer)] = &[
             (
                 "gk or g🡱",
@@ -425,7 +440,7 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
                     content: "Move region:".to_string(),
                     size: 30.0.into(),
                     color: self.theme.cheatsheet_fg,
-                    font: Font::MONOSPACE,
+                    font: self.theme.font.into(),
                     shaping: Shaping::Advanced,
                     ..Default::default()
                 },
@@ -458,7 +473,8 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
                                         .with_theme(self.theme);
 
                                 new_sel.draw_border(frame);
-                                new_sel.draw_corners(frame);
+                                new_sel
+                                    .draw_corners(frame, Handles::default().corner_radius as f32);
                             })
                             .stroke(Stroke {
                                 style: geometry::Style::Solid(self.theme.cheatsheet_fg),
@@ -473,7 +489,7 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
                             .label(canvas::Text {
                                 content: (*key).to_string(),
                                 color: self.theme.cheatsheet_fg,
-                                font: Font::MONOSPACE,
+                                font: self.theme.font.into(),
                                 shaping: Shaping::Advanced,
                                 ..Default::default()
                             })
@@ -481,10 +497,9 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
                                 content: (*desc).to_string(),
                                 color: self.theme.selection_frame,
                                 font: Font {
-                                    family: Family::Monospace,
                                     weight: Weight::Normal,
                                     style: font::Style::Italic,
-                                    ..Default::default()
+                                    ..self.theme.font.into()
                                 },
                                 shaping: Shaping::Advanced,
                                 ..Default::default()
@@ -505,7 +520,7 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
                     content: "Pick top and then bottom corners".into(),
                     color: self.theme.cheatsheet_fg,
                     size: Pixels(30.0),
-                    font: Font::MONOSPACE,
+                    font: self.theme.font.into(),
                     ..Default::default()
                 },
                 15.0,
@@ -515,7 +530,7 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
                     content: "select any area of the screen in 8 keystrokes!".into(),
                     color: self.theme.cheatsheet_fg,
                     size: Pixels(20.0),
-                    font: Font::MONOSPACE,
+                    font: self.theme.font.into(),
                     ..Default::default()
                 },
                 15.0,
@@ -532,7 +547,7 @@ impl canvas::Program<crate::Message> for KeybindingsCheatsheet<'_> {
                                 .with_size(|_| sel_size);
 
                         sel.draw_border(frame);
-                        sel.draw_corners(frame);
+                        sel.draw_corners(frame, Handles::default().corner_radius as f32);
 
                         let dotted_stroke = Stroke {
                             style: canvas::Style::Solid(self.theme.selection_frame),
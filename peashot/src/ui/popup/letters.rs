@@ -3,14 +3,14 @@
 use std::iter;
 
 use iced::{
-    Element, Event, Font,
+    Element, Event,
     Length::Fill,
-    Point, Size, Task,
-    font::Weight,
+    Point, Rectangle, Size, Task,
     keyboard::Key,
     widget::{
         Action, Canvas,
         canvas::{self, Path, Stroke},
+        image,
     },
 };
 
@@ -23,7 +23,12 @@ crate::declare_commands! {
         /// Open a grid of letters to pick the top left corner in 3 keystrokes
         PickTopLeftCorner,
         /// Open a grid of letters to pick the bottom right corner in 3 keystrokes
-        PickBottomRightCorner
+        PickBottomRightCorner,
+        /// Open a grid of letters to move the magnifier in 3 keystrokes, without the mouse
+        PickMagnifierPosition,
+        /// Open a grid of letters to drop a marker onto the screenshot in 3 keystrokes.
+        /// The marker is a dot drawn with the pen
+        PickMarkerPosition,
     }
 }
 
@@ -32,12 +37,22 @@ impl crate::command::Handler for Command {
         match self {
             Self::PickTopLeftCorner => {
                 app.popup = Some(Popup::Letters(State {
-                    picking_corner: PickCorner::TopLeft,
+                    target: PickTarget::TopLeft,
                 }));
             }
             Self::PickBottomRightCorner => {
                 app.popup = Some(Popup::Letters(State {
-                    picking_corner: PickCorner::BottomRight,
+                    target: PickTarget::BottomRight,
+                }));
+            }
+            Self::PickMagnifierPosition => {
+                app.popup = Some(Popup::Letters(State {
+                    target: PickTarget::Magnifier,
+                }));
+            }
+            Self::PickMarkerPosition => {
+                app.popup = Some(Popup::Letters(State {
+                    target: PickTarget::Marker,
                 }));
             }
         }
@@ -51,7 +66,7 @@ impl crate::command::Handler for Command {
 pub struct State {
     /// Shows a grid of letters on the screen, pressing 3 letters in a row
     /// allows accessing 25 * 25 * 25 = 15,625 different locations
-    pub picking_corner: PickCorner,
+    pub target: PickTarget,
 }
 
 /// Letters message
@@ -63,56 +78,65 @@ pub enum Message {
     Pick {
         /// the center of the region clicked on the 3rd level of `Letters`
         point: Point,
-        /// The corner which was picked
-        corner: PickCorner,
+        /// What the point was picked for
+        target: PickTarget,
     },
 }
 
 impl crate::message::Handler for Message {
     fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
-        match self {
-            Self::Pick { point, corner } => {
-                let sel = app.selection.map_or_else(
-                    || {
-                        // Intentionally do not increment `app.selections`, because
-                        // when selectiong a `0,0` point we do not want to active `--accept-on-select`
-                        Selection::new(
-                            Point::default(),
-                            &app.config.theme,
-                            false,
-                            app.cli.accept_on_select,
-                        )
-                    },
-                    Selection::norm,
-                );
-                let x = point.x;
-                let y = point.y;
-                let new_sel = match corner {
-                    PickCorner::TopLeft => {
-                        sel.with_x(|_| x)
-                            .with_y(|_| y)
-                            // make sure that the selection is not going to be out of bounds
-                            .with_width(|w| w.min(app.image.width() as f32 - x))
-                            .with_height(|h| h.min(app.image.height() as f32 - y))
-                    }
-                    PickCorner::BottomRight => sel
-                        .with_height(|_| y - sel.rect.y)
-                        .with_width(|_| x - sel.rect.x),
-                };
-                app.selection = Some(new_sel);
-
-                if let Some(on_select) = app.cli.accept_on_select {
-                    if new_sel.size() != Size::ZERO {
-                        if app.selections_created == 0 {
-                            return Task::done(crate::Message::Command {
-                                action: on_select.into_key_action(),
-                                count: 1,
-                            });
-                        }
-                        app.selections_created += 1;
-                    }
+        let Self::Pick { point, target } = self;
+        app.popup = None;
+
+        match target {
+            PickTarget::Magnifier => {
+                app.magnifier = Some(point);
+                app.mode = crate::ui::mode::Mode::Magnify;
+                return Task::none();
+            }
+            PickTarget::Marker => {
+                app.annotations
+                    .shapes
+                    .push(crate::ui::annotations::Shape::Pen(vec![point]));
+                return Task::none();
+            }
+            // the corners of the selection, below
+            PickTarget::TopLeft | PickTarget::BottomRight => (),
+        }
+
+        let sel = app.selection.map_or_else(
+            || {
+                // Intentionally do not increment `app.selections`, because
+                // when selectiong a `0,0` point we do not want to active `--accept-on-select`
+                Selection::new(
+                    Point::default(),
+                    &app.config.theme,
+                    false,
+                    app.cli.accept_on_select,
+                )
+            },
+            Selection::norm,
+        );
+        let x = point.x;
+        let y = point.y;
+        let new_sel = if target == PickTarget::TopLeft {
+            sel.with_x(|_| x)
+                .with_y(|_| y)
+                // make sure that the selection is not going to be out of bounds
+                .with_width(|w| w.min(app.image.width() as f32 - x))
+                .with_height(|h| h.min(app.image.height() as f32 - y))
+        } else {
+            sel.with_height(|_| y - sel.rect.y)
+                .with_width(|_| x - sel.rect.x)
+        };
+        app.selection = Some(new_sel);
+
+        if let Some(on_select) = app.cli.accept_on_select {
+            if new_sel.size() != Size::ZERO {
+                if app.selections_created == 0 {
+                    return Task::done(crate::Message::AcceptOnSelect(on_select));
                 }
-                app.popup = None;
+                app.selections_created += 1;
             }
         }
 
@@ -129,17 +153,6 @@ const UNICODE_CODEPOINT_LOWERCASE_A_START: u32 = 97;
 /// A tiny error margin for doing less than / greater than calculations
 const ERROR_MARGIN: f32 = 0.001;
 
-/// How large the font should be
-#[derive(PartialEq, PartialOrd, Clone, Copy)]
-enum FontSize {
-    /// A fixed font size in pixels
-    Fixed(f32),
-    /// The font size will fill the entire area
-    ///
-    /// Use this when the font size will be very small so it needs to be easy to see
-    Fill,
-}
-
 /// Draw letters in a box
 #[expect(clippy::too_many_arguments, reason = "todo: refactor")]
 fn draw_boxes(
@@ -148,7 +161,7 @@ fn draw_boxes(
     width: f32,
     height: f32,
     frame: &mut canvas::Frame,
-    font_size: FontSize,
+    font_size: f32,
     line_width: f32,
     app: &crate::App,
 ) {
@@ -177,19 +190,9 @@ fn draw_boxes(
                     x: x + box_width / 2.0 - line_offset,
                     y: y + box_height / 2.0 - line_offset,
                 },
-                font: {
-                    let mut font = Font::MONOSPACE;
-                    if font_size == FontSize::Fill {
-                        font.weight = Weight::Bold;
-                    }
-                    font
-                },
+                font: app.config.theme.font.into(),
                 color: app.config.theme.letters_fg,
-                size: match font_size {
-                    FontSize::Fixed(px) => px,
-                    FontSize::Fill => box_height,
-                }
-                .into(),
+                size: font_size.into(),
                 align_x: iced::alignment::Horizontal::Center,
                 align_y: iced::alignment::Vertical::Center,
                 ..Default::default()
@@ -267,6 +270,9 @@ fn draw_boxes(
 /// - Level 3: The region picked in Level 2 is further divided into 25 even tinier regions. Now, once we
 ///   pick any of the tiny regions the center of that region will be sent as a `Message` to the main
 ///   `App`.
+///
+/// In levels 2 and 3 the picked region is zoomed in to fill the screen, so the letters stay
+/// readable and what is under them can be seen.
 #[derive(PartialEq, Clone, Copy, Debug, Default)]
 pub enum LetterLevel {
     /// First level
@@ -286,15 +292,51 @@ pub enum LetterLevel {
     },
 }
 
+impl LetterLevel {
+    /// Area of the screen of size `screen` which the letters of this level divide
+    fn area(self, screen: Size) -> Rectangle {
+        let (point, level) = match self {
+            Self::First => (Point::ORIGIN, 0),
+            Self::Second { point } => (point, 1),
+            Self::Third { point } => (point, 2),
+        };
+
+        Rectangle::new(
+            point,
+            Size::new(
+                screen.width / HORIZONTAL_COUNT.powi(level),
+                screen.height / VERTICAL_COUNT.powi(level),
+            ),
+        )
+    }
+}
+
+/// State of the canvas of the letters
+#[derive(Default)]
+pub struct CanvasState {
+    /// Level of the grid which is shown
+    level: LetterLevel,
+    /// Letters and lines of the grid
+    ///
+    /// Every level zooms the picked area in to fill the screen, so the grid looks
+    /// the same on each of them. It is only drawn again when the size of the screen changes
+    grid: canvas::Cache,
+}
+
 /// When a position is picked, what does that signify?
 ///
 /// This enum represents the possible outcomes that can happen when we pick a position.
 #[derive(PartialEq, Eq, PartialOrd, Clone, Copy, Debug)]
-pub enum PickCorner {
+pub enum PickTarget {
     /// Picking position for the top-left corner of the selection
     TopLeft,
     /// Picking position for the bottom-right corner of the selection
     BottomRight,
+    /// Picking position for the magnifier, which then shows the screenshot around it
+    /// like it does around the cursor
+    Magnifier,
+    /// Picking position for a marker, drawn onto the screenshot like the other annotations
+    Marker,
 }
 
 /// Letters
@@ -302,8 +344,8 @@ pub enum PickCorner {
 pub struct Letters<'app> {
     /// The App
     pub app: &'app crate::App,
-    /// Corner to pick the position for
-    pub pick_corner: PickCorner,
+    /// What to pick the position for
+    pub target: PickTarget,
 }
 
 impl<'app> Letters<'app> {
@@ -314,7 +356,7 @@ impl<'app> Letters<'app> {
 }
 
 impl canvas::Program<crate::Message> for Letters<'_> {
-    type State = LetterLevel;
+    type State = CanvasState;
 
     fn draw(
         &self,
@@ -326,51 +368,47 @@ impl canvas::Program<crate::Message> for Letters<'_> {
     ) -> Vec<canvas::Geometry> {
         let mut frame = canvas::Frame::new(renderer, bounds.size());
 
-        frame.fill_rectangle(
-            bounds.position(),
-            bounds.size(),
-            self.app.config.theme.letters_bg,
-        );
+        // the area picked so far is zoomed in to fill the screen, so the letters
+        // of every level are just as large and easy to read
+        let area = state.level.area(bounds.size());
+        if state.level != LetterLevel::First {
+            let zoom_x = bounds.width / area.width;
+            let zoom_y = bounds.height / area.height;
+
+            frame.draw_image(
+                Rectangle {
+                    x: -area.x * zoom_x,
+                    y: -area.y * zoom_y,
+                    width: self.app.image.width() as f32 * zoom_x,
+                    height: self.app.image.height() as f32 * zoom_y,
+                },
+                canvas::Image::new(image::Handle::from(crate::image::RgbaHandle::clone(
+                    &self.app.image,
+                )))
+                .filter_method(image::FilterMethod::Nearest),
+            );
+        }
 
-        let x_start = 0.0;
-        let y_start = 0.0;
-        let width = frame.width();
-        let height = frame.height();
-
-        match state {
-            LetterLevel::First => draw_boxes(
-                x_start,
-                y_start,
-                width,
-                height,
-                &mut frame,
-                FontSize::Fixed(48.0),
-                1.0,
-                self.app,
-            ),
-            LetterLevel::Second { point } => draw_boxes(
-                point.x,
-                point.y,
-                width / HORIZONTAL_COUNT,
-                height / VERTICAL_COUNT,
-                &mut frame,
-                FontSize::Fixed(32.0),
+        let grid = state.grid.draw(renderer, bounds.size(), |frame| {
+            frame.fill_rectangle(
+                Point::ORIGIN,
+                frame.size(),
+                self.app.config.theme.letters_bg,
+            );
+
+            draw_boxes(
+                0.0,
+                0.0,
+                frame.width(),
+                frame.height(),
+                frame,
+                48.0,
                 1.0,
                 self.app,
-            ),
-            LetterLevel::Third { point } => draw_boxes(
-                point.x,
-                point.y,
-                width / HORIZONTAL_COUNT.powi(2),
-                height / VERTICAL_COUNT.powi(2),
-                &mut frame,
-                FontSize::Fill,
-                0.2,
-                self.app,
-            ),
-        }
+            );
+        });
 
-        vec![frame.into_geometry()]
+        vec![frame.into_geometry(), grid]
     }
 
     fn update(
@@ -389,12 +427,12 @@ impl canvas::Program<crate::Message> for Letters<'_> {
                 let ch = ch as u32 - UNICODE_CODEPOINT_LOWERCASE_A_START;
                 let vertical_steps = (ch % VERTICAL_COUNT as u32) as f32;
                 let horizontal_steps = (ch / HORIZONTAL_COUNT as u32) as f32;
-                match state {
+                match state.level {
                     LetterLevel::First => {
                         let box_width = bounds.width / HORIZONTAL_COUNT;
                         let box_height = bounds.height / VERTICAL_COUNT;
 
-                        *state = LetterLevel::Second {
+                        state.level = LetterLevel::Second {
                             point: Point {
                                 x: horizontal_steps * box_width,
                                 y: vertical_steps * box_height,
@@ -407,7 +445,7 @@ impl canvas::Program<crate::Message> for Letters<'_> {
                         let box_width = bounds.width / HORIZONTAL_COUNT.powi(2);
                         let box_height = bounds.height / VERTICAL_COUNT.powi(2);
 
-                        *state = LetterLevel::Third {
+                        state.level = LetterLevel::Third {
                             point: Point {
                                 x: horizontal_steps * box_width + point.x,
                                 y: vertical_steps * box_height + point.y,
@@ -427,7 +465,7 @@ impl canvas::Program<crate::Message> for Letters<'_> {
                                 x: horizontal_steps * box_width + point.x + box_width / 2.0,
                                 y: vertical_steps * box_height + point.y + box_height / 2.0,
                             },
-                            corner: self.pick_corner,
+                            target: self.target,
                         })));
                     }
                 }
@@ -444,3 +482,33 @@ impl canvas::Program<crate::Message> for Letters<'_> {
         Some(Action::capture())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn each_level_divides_the_previous_area() {
+        let screen = Size::new(1000.0, 500.0);
+
+        assert_eq!(
+            LetterLevel::First.area(screen),
+            Rectangle::new(Point::ORIGIN, screen)
+        );
+        assert_eq!(
+            LetterLevel::Second {
+                point: Point::new(200.0, 100.0)
+            }
+            .area(screen),
+            Rectangle::new(Point::new(200.0, 100.0), Size::new(200.0, 100.0))
+        );
+        assert_eq!(
+            LetterLevel::Third {
+                point: Point::new(240.0, 120.0)
+            }
+            .area(screen),
+            Rectangle::new(Point::new(240.0, 120.0), Size::new(40.0, 20.0))
+        );
+    }
+}
@@ -18,6 +18,42 @@ use iced::widget::{
 pub mod letters;
 pub use letters::Letters;
 
+pub mod confirm_exit;
+pub use confirm_exit::ConfirmExit;
+
+pub mod guides;
+pub use guides::Guides;
+
+pub mod confirm_preview;
+pub use confirm_preview::ConfirmPreview;
+
+pub mod palette;
+pub use palette::Palette;
+
+pub mod help;
+pub use help::Help;
+
+pub mod more_icons;
+pub use more_icons::MoreIcons;
+
+pub mod radial_menu;
+pub use radial_menu::RadialMenu;
+
+pub mod upload_too_large;
+pub use upload_too_large::UploadTooLarge;
+
+/// Keys which confirm a popup: the keys of `accept`, or `Enter` which always does
+pub fn confirm_keys(keys: &crate::config::key_map::KeyMap) -> String {
+    keys.keys_of(&crate::Command::App(crate::ui::app::Command::Accept))
+        .unwrap_or_else(|| "<enter>".to_string())
+}
+
+/// Keys which close a popup: the keys of `smart-escape`, or `Esc` which always does
+pub fn cancel_keys(keys: &crate::config::key_map::KeyMap) -> String {
+    keys.keys_of(&crate::Command::App(crate::ui::app::Command::SmartEscape))
+        .unwrap_or_else(|| "<esc>".to_string())
+}
+
 /// Popup are overlaid on top and they block any events. allowing only Escape to close
 /// the popup.
 #[derive(Debug, strum::EnumTryAs)]
@@ -28,6 +64,25 @@ pub enum Popup {
     ImageUploaded(image_uploaded::State),
     /// Shows available commands
     KeyCheatsheet,
+    /// Asks for confirmation before exiting with a selection
+    ConfirmExit,
+    /// Pick a safe area guide
+    Guides,
+    /// Preview of the captured image, before running the action on it
+    ConfirmPreview(confirm_preview::State),
+    /// Most common colors of the selection
+    Palette(palette::State),
+    /// Search the commands and config options
+    Help(help::State),
+    /// Icons which did not fit around the selection
+    MoreIcons {
+        /// Index of the first icon which did not fit
+        first: usize,
+    },
+    /// Ring of actions around the cursor
+    RadialMenu(radial_menu::State),
+    /// The screenshot is larger than `max-upload-size`, offers to make it smaller
+    UploadTooLarge(upload_too_large::State),
 }
 
 /// Elements inside of a `popup` render in the center of the screen
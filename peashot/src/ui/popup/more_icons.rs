@@ -0,0 +1,89 @@
+//! List of the icons which did not fit around the selection
+
+use iced::{
+    Background, Element,
+    Length::Fill,
+    Size,
+    widget::{Column, button, column, container, horizontal_space, row, text},
+};
+
+use crate::ui::selection_icons::{self, actions};
+
+/// Popup listing the icons which did not fit around the selection
+#[derive(Debug, Copy, Clone)]
+pub struct MoreIcons<'app> {
+    /// The App
+    pub app: &'app crate::App,
+    /// Index of the first icon which did not fit
+    pub first: usize,
+}
+
+impl<'app> MoreIcons<'app> {
+    /// Render the list of icons, with their labels and keys
+    pub fn view(self) -> Element<'app, crate::Message> {
+        let theme = &self.app.config.theme;
+        let size = Size::new(450.0, 300.0);
+
+        let actions = actions(self.app)
+            .into_iter()
+            .skip(self.first)
+            .map(|action| {
+                button(
+                    row![
+                        action
+                            .icon
+                            .style(move |_, _| iced::widget::svg::Style {
+                                color: Some(theme.guides_fg),
+                            })
+                            .width(20.0)
+                            .height(20.0),
+                        text(action.label),
+                        horizontal_space().width(Fill),
+                    ]
+                    .push_maybe(
+                        action
+                            .keys
+                            .map(|keys| text(keys).color(theme.guides_fg.scale_alpha(0.6))),
+                    )
+                    .spacing(10.0),
+                )
+                .width(Fill)
+                .on_press_maybe(action.command.map(|action| {
+                    crate::Message::SelectionIcons(selection_icons::Message::Run(action))
+                }))
+                .style(move |_, status| button::Style {
+                    text_color: theme.guides_fg,
+                    background: Some(Background::Color(match status {
+                        button::Status::Hovered | button::Status::Pressed => theme.icon_bg,
+                        button::Status::Active | button::Status::Disabled => {
+                            iced::Color::TRANSPARENT
+                        }
+                    })),
+                    border: theme.button_border(),
+                    ..Default::default()
+                })
+                .into()
+            });
+
+        super::popup(
+            size,
+            container(
+                column![
+                    text("More").size(24.0),
+                    Column::with_children(actions).spacing(5.0)
+                ]
+                .spacing(20.0),
+            )
+            .padding(20.0)
+            .style(|_| container::Style {
+                text_color: Some(theme.guides_fg),
+                background: Some(Background::Color(theme.guides_bg)),
+                border: theme.popup_border(theme.guides_fg),
+                ..Default::default()
+            })
+            .width(size.width)
+            .height(size.height),
+            theme,
+        )
+    }
+}
@@ -0,0 +1,182 @@
+//! Show the dominant colors of the selection, to grab colors from mockups
+
+use iced::{
+    Background, Color, Element,
+    Length::{self, Fill},
+    Size, Task,
+    alignment::Vertical,
+    widget::{Column, button, column, container, row, svg, text, tooltip},
+};
+
+use crate::{geometry::RectangleExt as _, icon, ui::selection_icons::icon_tooltip};
+
+use super::Popup;
+
+crate::declare_commands! {
+    enum Command {
+        /// Show the most common colors of the selection, with their hex codes
+        ExtractPalette {
+            colors: u32 = 8,
+        },
+    }
+}
+
+impl crate::command::Handler for Command {
+    fn handle(self, app: &mut crate::App, _count: u32) -> Task<crate::Message> {
+        match self {
+            Self::ExtractPalette { colors } => {
+                let Some(rect) = app.selection.map(|sel| sel.rect.norm()) else {
+                    app.errors
+                        .push("There is no selection to extract colors from");
+                    return Task::none();
+                };
+
+                let image =
+                    crate::App::process_image(rect, &app.image, &[], None, &app.config.theme)
+                        .into_rgba8();
+
+                app.popup = Some(Popup::Palette(State {
+                    colors: crate::image::palette::dominant_colors(&image, colors as usize),
+                    copied: None,
+                }));
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// State of the palette popup
+#[derive(Debug)]
+pub struct State {
+    /// Colors of the selection, most common first
+    pub colors: Vec<[u8; 3]>,
+    /// Index of the color which was copied last
+    pub copied: Option<usize>,
+}
+
+/// Palette message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Copy hex code of the color at this index to the clipboard
+    CopyColor(usize),
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        match self {
+            Self::CopyColor(index) => {
+                let Some(state) = app.popup.as_mut().and_then(|p| p.try_as_palette_mut()) else {
+                    return Task::none();
+                };
+
+                let Some(&color) = state.colors.get(index) else {
+                    return Task::none();
+                };
+
+                if let Err(err) = crate::clipboard::set_text(&hex(color)) {
+                    app.errors.push(err.to_string());
+                } else {
+                    state.copied = Some(index);
+                }
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// Hex code of the color, like `#ab6137`
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+/// Popup listing the colors of the selection
+#[derive(Debug, Copy, Clone)]
+pub struct Palette<'app> {
+    /// Theme of the app
+    pub theme: &'app crate::Theme,
+    /// State of the popup
+    pub state: &'app State,
+}
+
+impl<'app> Palette<'app> {
+    /// Render the palette
+    pub fn view(self) -> Element<'app, crate::Message> {
+        let size = Size::new(350.0, 120.0 + 40.0 * self.state.colors.len() as f32);
+
+        let colors = self.state.colors.iter().enumerate().map(|(index, &color)| {
+            let [r, g, b] = color;
+
+            let (copy_icon, copy_icon_color, label) = if self.state.copied == Some(index) {
+                (icon!(Check), self.theme.success, "Copied!")
+            } else {
+                (icon!(Clipboard), self.theme.color_palette_fg, "Copy")
+            };
+
+            row![
+                //
+                // Swatch
+                //
+                container("")
+                    .width(32.0)
+                    .height(32.0)
+                    .style(move |_| container::Style {
+                        background: Some(Background::Color(Color::from_rgb8(r, g, b))),
+                        border: self
+                            .theme
+                            .button_border()
+                            .width(1.0)
+                            .color(self.theme.color_palette_fg),
+                        ..Default::default()
+                    }),
+                text(hex(color)).width(Fill),
+                //
+                // Copy to clipboard button
+                //
+                icon_tooltip(
+                    button(
+                        copy_icon
+                            .style(move |_, _| svg::Style {
+                                color: Some(copy_icon_color),
+                            })
+                            .width(Length::Fixed(22.0))
+                            .height(Length::Fixed(22.0)),
+                    )
+                    .on_press(crate::Message::Palette(Message::CopyColor(index)))
+                    .style(|_, _| button::Style {
+                        background: Some(Background::Color(Color::TRANSPARENT)),
+                        ..Default::default()
+                    }),
+                    text(label),
+                    tooltip::Position::Left,
+                    self.theme,
+                ),
+            ]
+            .spacing(15.0)
+            .align_y(Vertical::Center)
+            .into()
+        });
+
+        super::popup(
+            size,
+            container(
+                column![
+                    text("Palette").size(24.0),
+                    Column::with_children(colors).spacing(8.0)
+                ]
+                .spacing(20.0),
+            )
+            .padding(20.0)
+            .style(|_| container::Style {
+                text_color: Some(self.theme.color_palette_fg),
+                background: Some(Background::Color(self.theme.color_palette_bg)),
+                border: self.theme.popup_border(self.theme.color_palette_fg),
+                ..Default::default()
+            })
+            .width(size.width)
+            .height(size.height),
+            self.theme,
+        )
+    }
+}
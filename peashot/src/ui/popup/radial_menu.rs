@@ -0,0 +1,240 @@
+//! Ring of actions around the cursor, opened by pressing a mouse button
+//!
+//! A faster alternative to the icons around the selection, which does not
+//! need the mouse to travel to the edge of the selection
+
+use std::f32::consts::TAU;
+
+use iced::{
+    Background, Border, Element,
+    Length::Fill,
+    Point, Task,
+    widget::{Space, button, column, mouse_area, row, stack, text},
+};
+
+use crate::config::commands::StartupCommand;
+
+use super::Popup;
+
+/// Radius of the ring with only a few actions
+const MIN_RADIUS: f32 = 90.0;
+/// Width of each action
+const ITEM_WIDTH: f32 = 150.0;
+/// Height of each action
+const ITEM_HEIGHT: f32 = 30.0;
+
+/// Mouse button which opens the radial menu
+#[derive(ferrishot_knus::DecodeScalar, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    /// Right mouse button. Clicking it no longer resizes the selection to the cursor
+    Right,
+    /// Middle mouse button, or pressing the scroll wheel
+    Middle,
+    /// Side button which goes back
+    Back,
+    /// Side button which goes forward
+    Forward,
+}
+
+impl From<MouseButton> for iced::mouse::Button {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Right => Self::Right,
+            MouseButton::Middle => Self::Middle,
+            MouseButton::Back => Self::Back,
+            MouseButton::Forward => Self::Forward,
+        }
+    }
+}
+
+/// Actions of the radial menu, and the mouse button which opens it
+///
+/// ```kdl
+/// radial-menu button=middle {
+///   copy-to-clipboard
+///   save-screenshot
+/// }
+/// ```
+#[derive(ferrishot_knus::Decode, Debug, Clone, Default)]
+pub struct Settings {
+    /// Opens the menu. `None` disables the menu
+    #[ferrishot_knus(property, default)]
+    pub button: Option<MouseButton>,
+    /// Actions in the ring, clockwise from the top
+    #[ferrishot_knus(children)]
+    pub commands: Vec<StartupCommand>,
+}
+
+impl Settings {
+    /// Whether pressing the `button` opens the menu
+    pub fn is_opened_by(&self, button: iced::mouse::Button) -> bool {
+        !self.commands.is_empty() && self.button.map(Into::into) == Some(button)
+    }
+}
+
+/// State of the radial menu
+#[derive(Debug, Clone, Copy)]
+pub struct State {
+    /// Where the mouse button was pressed
+    center: Point,
+}
+
+/// Radial menu message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Open the menu around the cursor
+    Open(Point),
+    /// Run the action at this index of the menu
+    Run(usize),
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        match self {
+            Self::Open(center) => {
+                app.popup = Some(Popup::RadialMenu(State { center }));
+            }
+            Self::Run(index) => {
+                app.popup = None;
+
+                if let Some(command) = app.config.radial_menu.commands.get(index) {
+                    return Task::done(crate::Message::Command {
+                        action: command.clone().into_command(),
+                        count: 1,
+                    });
+                }
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// Center of each of `count` actions in a ring around the `center`, clockwise from the top
+fn positions(center: Point, count: usize) -> impl Iterator<Item = Point> {
+    // so the actions do not overlap, each of them gets twice its height of the circumference
+    let radius = (count as f32 * ITEM_HEIGHT * 2.0 / TAU).max(MIN_RADIUS);
+
+    (0..count).map(move |index| {
+        let angle = TAU * index as f32 / count as f32 - TAU / 4.0;
+
+        Point::new(
+            radius.mul_add(angle.cos(), center.x),
+            radius.mul_add(angle.sin(), center.y),
+        )
+    })
+}
+
+/// Ring of actions around the cursor
+#[derive(Debug, Copy, Clone)]
+pub struct RadialMenu<'app> {
+    /// The App
+    pub app: &'app crate::App,
+    /// State of the menu
+    pub state: &'app State,
+}
+
+impl<'app> RadialMenu<'app> {
+    /// Render the actions around the place where the menu was opened
+    pub fn view(self) -> Element<'app, crate::Message> {
+        let theme = &self.app.config.theme;
+        let commands = &self.app.config.radial_menu.commands;
+
+        let actions = commands
+            .iter()
+            .zip(positions(self.state.center, commands.len()))
+            .enumerate()
+            .map(|(index, (command, position))| {
+                // with the arguments, as two entries can run the same command differently
+                let label = command.clone().into_command().to_string();
+
+                column![
+                    Space::with_height(position.y - ITEM_HEIGHT / 2.0),
+                    row![
+                        Space::with_width(position.x - ITEM_WIDTH / 2.0),
+                        button(text(label).center().width(Fill))
+                            .width(ITEM_WIDTH)
+                            .height(ITEM_HEIGHT)
+                            .on_press(crate::Message::RadialMenu(Message::Run(index)))
+                            .style(move |_, status| button::Style {
+                                text_color: theme.icon_fg,
+                                background: Some(Background::Color(match status {
+                                    button::Status::Hovered | button::Status::Pressed => {
+                                        theme.icon_bg
+                                    }
+                                    button::Status::Active | button::Status::Disabled => {
+                                        theme.icon_bg.scale_alpha(0.8)
+                                    }
+                                })),
+                                border: Border::default().rounded(ITEM_HEIGHT / 2.0),
+                                shadow: iced::Shadow {
+                                    color: theme.drop_shadow,
+                                    blur_radius: 3.0,
+                                    offset: iced::Vector::ZERO,
+                                },
+                                ..Default::default()
+                            })
+                    ]
+                ]
+                .into()
+            });
+
+        // clicking anywhere else closes the menu
+        let background = mouse_area(Space::new(Fill, Fill))
+            .on_press(crate::Message::ClosePopup)
+            .on_right_press(crate::Message::ClosePopup)
+            .on_middle_press(crate::Message::ClosePopup);
+
+        stack(std::iter::once(background.into()).chain(actions)).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn positions_go_clockwise_from_the_top() {
+        let center = Point::new(500.0, 500.0);
+        let positions = positions(center, 4)
+            .map(|position| (position.x.round(), position.y.round()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            positions,
+            [
+                (500.0, 410.0),
+                (590.0, 500.0),
+                (500.0, 590.0),
+                (410.0, 500.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn opened_by_its_button() {
+        use iced::mouse::Button::{Middle, Right};
+
+        let settings = |kdl| {
+            ferrishot_knus::parse::<Vec<Settings>>("<test>", kdl)
+                .unwrap()
+                .remove(0)
+        };
+
+        let menu = settings("radial-menu button=middle { save-screenshot; exit; }");
+        assert!(menu.is_opened_by(Middle));
+        assert!(!menu.is_opened_by(Right));
+        // there is nothing to open without actions
+        assert!(!settings("radial-menu button=right").is_opened_by(Right));
+        assert!(!settings("radial-menu { exit; }").is_opened_by(Right));
+    }
+
+    #[test]
+    fn ring_grows_with_more_actions() {
+        let center = Point::ORIGIN;
+        let top = positions(center, 40).next().unwrap();
+
+        assert!(top.y < -MIN_RADIUS);
+    }
+}
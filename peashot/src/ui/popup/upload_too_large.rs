@@ -0,0 +1,256 @@
+//! Offer to make the screenshot smaller, when it is larger than `max-upload-size`
+//!
+//! The services refuse images which are too large, but only after the entire image
+//! was uploaded. So the image is checked before uploading, and can be downscaled or
+//! converted to JPEG instead
+
+use iced::{
+    Background, Element,
+    Length::Fill,
+    Rectangle, Size, Task,
+    widget::{button, column, container, horizontal_space, row, text},
+};
+use image::DynamicImage;
+
+use crate::image::action::{self, ImageData};
+
+use super::Popup;
+
+/// Each downscale aims for this much of `max-upload-size`, as the size of the
+/// encoded image is not exactly proportional to the amount of pixels
+const DOWNSCALE_MARGIN: f32 = 0.9;
+
+/// An attempt to upload the screenshot
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attempt {
+    /// Region of the screenshot which was uploaded
+    pub region: Rectangle,
+    /// How much each side of the image was scaled, from `0` to `1`
+    pub scale: f32,
+    /// Format the image was encoded in
+    pub format: image::ImageFormat,
+}
+
+impl Attempt {
+    /// The next attempt, with the image made smaller by `shrink`
+    fn shrink(self, shrink: Shrink, size: u64, limit: u64) -> Self {
+        match shrink {
+            Shrink::Downscale => Self {
+                scale: downscale(self.scale, size, limit),
+                ..self
+            },
+            Shrink::ConvertToJpeg => Self {
+                format: image::ImageFormat::Jpeg,
+                ..self
+            },
+        }
+    }
+}
+
+/// How to make the image smaller
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shrink {
+    /// Scale the image down, so it has less pixels
+    Downscale,
+    /// Encode the image as JPEG, which is lossy
+    ConvertToJpeg,
+}
+
+/// State of the popup
+#[derive(Debug, Clone, Copy)]
+pub struct State {
+    /// Size of the encoded image, in bytes
+    pub size: u64,
+    /// The `max-upload-size`, in bytes
+    pub limit: u64,
+    /// The attempt which was too large
+    pub attempt: Attempt,
+}
+
+/// Upload too large message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// The image was not uploaded, because it is too large
+    TooLarge(State),
+    /// Make the image smaller, then upload it again
+    Shrink(Shrink),
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        match self {
+            Self::TooLarge(state) => {
+                app.is_uploading_image = false;
+                app.popup = Some(Popup::UploadTooLarge(state));
+            }
+            Self::Shrink(shrink) => {
+                let Some(Popup::UploadTooLarge(state)) = app.popup.take() else {
+                    return Task::none();
+                };
+                let attempt = state.attempt.shrink(shrink, state.size, state.limit);
+
+                let image = crate::App::process_image(
+                    attempt.region,
+                    &app.image,
+                    &app.annotations.shapes,
+                    app.is_stamp_enabled.then_some(&app.config.stamp),
+                    &app.config.theme,
+                );
+                let image = scaled(image, attempt.scale);
+                let image_data = ImageData {
+                    width: image.width(),
+                    height: image.height(),
+                };
+                let max_upload_size = app.config.max_upload_size;
+                let skip_duplicates = app.config.skip_duplicate_captures;
+
+                app.is_uploading_image = true;
+
+                return Task::future(async move {
+                    action::outcome(
+                        action::upload(&image, attempt.format, max_upload_size, skip_duplicates)
+                            .await
+                            .map(|output| (output, image_data)),
+                        attempt,
+                    )
+                });
+            }
+        }
+
+        Task::none()
+    }
+}
+
+/// Scale of each side, so that the image which was `size` bytes at `scale`
+/// fits into `limit` bytes
+fn downscale(scale: f32, size: u64, limit: u64) -> f32 {
+    // the amount of pixels goes down with the square of the scale
+    let ratio = (limit as f32 / size.max(1) as f32).sqrt();
+
+    (scale * ratio * DOWNSCALE_MARGIN).min(scale)
+}
+
+/// The `image` with each of its sides scaled by `scale`
+fn scaled(image: DynamicImage, scale: f32) -> DynamicImage {
+    if scale >= 1.0 {
+        return image;
+    }
+
+    image.resize_exact(
+        ((image.width() as f32 * scale).round() as u32).max(1),
+        ((image.height() as f32 * scale).round() as u32).max(1),
+        image::imageops::FilterType::Triangle,
+    )
+}
+
+/// Popup offering to make the screenshot smaller
+#[derive(Debug, Copy, Clone)]
+pub struct UploadTooLarge<'app> {
+    /// Theme of the app
+    pub theme: &'app crate::Theme,
+    /// Keybindings of the app
+    pub keys: &'app crate::config::key_map::KeyMap,
+    /// State of the popup
+    pub state: &'app State,
+}
+
+impl<'app> UploadTooLarge<'app> {
+    /// Render the popup
+    pub fn view(self) -> Element<'app, crate::Message> {
+        let size = Size::new(550.0, 200.0);
+
+        let choice = |label, message| {
+            button(text(label).color(self.theme.icon_fg))
+                .on_press(message)
+                .style(|_, _| button::Style {
+                    background: Some(Background::Color(self.theme.icon_bg)),
+                    border: self.theme.button_border(),
+                    ..Default::default()
+                })
+        };
+        let shrink = |shrink| crate::Message::UploadTooLarge(Message::Shrink(shrink));
+
+        let description = format!(
+            "The screenshot is {}, larger than the `max-upload-size` of {}",
+            human_bytes::human_bytes(self.state.size as f64),
+            human_bytes::human_bytes(self.state.limit as f64),
+        );
+
+        super::popup(
+            size,
+            container(
+                column![
+                    text("Screenshot is too large to upload").size(20.0),
+                    text(description),
+                    row![
+                        horizontal_space().width(Fill),
+                        choice(
+                            format!("Cancel ({})", super::cancel_keys(self.keys)),
+                            crate::Message::ClosePopup
+                        )
+                    ]
+                    .push_maybe(
+                        (self.state.attempt.format != image::ImageFormat::Jpeg).then(|| choice(
+                            "Convert to JPEG".to_string(),
+                            shrink(Shrink::ConvertToJpeg)
+                        ))
+                    )
+                    .push(choice("Downscale".to_string(), shrink(Shrink::Downscale)))
+                    .spacing(10.0)
+                ]
+                .spacing(20.0),
+            )
+            .padding(20.0)
+            .style(|_| container::Style {
+                text_color: Some(self.theme.upload_too_large_fg),
+                background: Some(Background::Color(self.theme.upload_too_large_bg)),
+                border: self.theme.popup_border(self.theme.upload_too_large_fg),
+                ..Default::default()
+            })
+            .width(size.width)
+            .height(size.height),
+            self.theme,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn downscale_fits_into_the_limit() {
+        // 4 times too large, so each side is halved
+        assert_eq!(downscale(1.0, 4_000, 1_000), 0.5 * DOWNSCALE_MARGIN);
+        // scaled down further than the previous attempt
+        assert_eq!(downscale(0.5, 4_000, 1_000), 0.25 * DOWNSCALE_MARGIN);
+        // never scales up
+        assert_eq!(downscale(0.5, 1_000, 4_000), 0.5);
+    }
+
+    #[test]
+    fn converting_to_jpeg_keeps_the_scale() {
+        let attempt = Attempt {
+            region: Rectangle::new(iced::Point::ORIGIN, Size::new(100.0, 100.0)),
+            scale: 0.5,
+            format: image::ImageFormat::Png,
+        };
+
+        assert_eq!(
+            attempt.shrink(Shrink::ConvertToJpeg, 4_000, 1_000),
+            Attempt {
+                format: image::ImageFormat::Jpeg,
+                ..attempt
+            }
+        );
+    }
+
+    #[test]
+    fn scaled_image_has_at_least_a_pixel() {
+        let image = DynamicImage::new_rgba8(100, 10);
+
+        let image = scaled(image, 0.05);
+        assert_eq!((image.width(), image.height()), (5, 1));
+    }
+}
@@ -0,0 +1,303 @@
+//! Presentation mode, with `--present`
+//!
+//! Shows the screenshot with a laser pointer following the cursor, to point at things
+//! while sharing the screen. The screenshot can be zoomed with the scroll wheel and panned
+//! by dragging it with the left or middle mouse button. Nothing can be captured in this mode.
+
+use iced::{
+    Element,
+    Length::Fill,
+    Point, Rectangle, Renderer, Size, Theme, Vector,
+    advanced::mouse::{self, Cursor},
+    keyboard,
+    mouse::Interaction,
+    widget::{
+        Action, Canvas,
+        canvas::{self, Path},
+        image,
+    },
+};
+
+use crate::image::RgbaHandle;
+
+/// Smallest zoom of the screenshot
+const MIN_ZOOM: f32 = 1.0;
+
+/// Largest zoom of the screenshot
+const MAX_ZOOM: f32 = 32.0;
+
+/// How much a single line of scrolling zooms in or out
+const ZOOM_PER_LINE: f32 = 1.2;
+
+/// Scrolling this many pixels is like scrolling a line, for touchpads
+const PIXELS_PER_LINE: f32 = 50.0;
+
+/// Radius of the dot of the laser pointer
+const LASER_RADIUS: f32 = 6.0;
+
+/// Radius of the glow around the dot of the laser pointer
+const LASER_GLOW_RADIUS: f32 = 16.0;
+
+/// The screenshot, shown for a presentation
+#[derive(Debug)]
+pub struct Presentation<'app> {
+    /// The screenshot
+    pub image: &'app RgbaHandle,
+    /// Theme of the app
+    pub theme: &'app crate::Theme,
+}
+
+/// Where the screenshot is shown
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct View {
+    /// How many times the screenshot is enlarged
+    zoom: f32,
+    /// Position of the top-left corner of the screenshot on the screen
+    offset: Vector,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            zoom: MIN_ZOOM,
+            offset: Vector::ZERO,
+        }
+    }
+}
+
+impl View {
+    /// Zoom in or out by `factor`, keeping the point of the screenshot under the `cursor` in place
+    fn zoom_at(self, cursor: Point, factor: f32) -> Self {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let cursor = Vector::new(cursor.x, cursor.y);
+
+        Self {
+            zoom,
+            offset: cursor - (cursor - self.offset) * (zoom / self.zoom),
+        }
+    }
+
+    /// Keep the screenshot of `size` at the smallest zoom on the `screen`
+    ///
+    /// Along each axis where the screenshot is larger than the screen there are no gaps
+    /// at the edges, and where it is smaller it is centered
+    fn clamp(self, screen: Size, size: Size) -> Self {
+        let size = size * self.zoom;
+        let clamp_axis = |offset: f32, screen: f32, size: f32| {
+            if size <= screen {
+                (screen - size) / 2.0
+            } else {
+                offset.clamp(screen - size, 0.0)
+            }
+        };
+
+        Self {
+            zoom: self.zoom,
+            offset: Vector::new(
+                clamp_axis(self.offset.x, screen.width, size.width),
+                clamp_axis(self.offset.y, screen.height, size.height),
+            ),
+        }
+    }
+}
+
+/// State of the presentation
+#[derive(Debug, Default)]
+pub struct State {
+    /// Where the screenshot is shown
+    view: View,
+    /// Last position of the cursor while the screenshot is being dragged
+    drag: Option<Point>,
+}
+
+impl Presentation<'_> {
+    /// Size of the screenshot at the smallest zoom, as large as fits on the `screen`
+    fn fitted_size(&self, screen: Size) -> Size {
+        let image = Size::new(self.image.width() as f32, self.image.height() as f32);
+
+        image * (screen.width / image.width).min(screen.height / image.height)
+    }
+}
+
+impl canvas::Program<crate::Message> for Presentation<'_> {
+    type State = State;
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let size = self.fitted_size(bounds.size());
+        let view = state.view.clamp(bounds.size(), size);
+
+        frame.draw_image(
+            Rectangle::new(Point::ORIGIN + view.offset, size * view.zoom),
+            canvas::Image::new(image::Handle::from(self.image.clone()))
+                .filter_method(image::FilterMethod::Nearest),
+        );
+
+        if let Some(cursor) = cursor.position_in(bounds) {
+            frame.fill(
+                &Path::circle(cursor, LASER_GLOW_RADIUS),
+                self.theme.laser_pointer.scale_alpha(0.3),
+            );
+            frame.fill(
+                &Path::circle(cursor, LASER_RADIUS),
+                self.theme.laser_pointer,
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &iced::Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> Option<Action<crate::Message>> {
+        use iced::Event::{Keyboard, Mouse};
+        use iced::mouse::Event::{ButtonPressed, ButtonReleased, CursorMoved, WheelScrolled};
+
+        let screen = bounds.size();
+        let size = self.fitted_size(screen);
+
+        match event {
+            Keyboard(keyboard::Event::KeyPressed {
+                key: keyboard::Key::Named(keyboard::key::Named::Escape),
+                ..
+            }) => return Some(Action::publish(crate::Message::Exit)),
+            // back to the screenshot as it was
+            Keyboard(keyboard::Event::KeyPressed { key, .. })
+                if *key == keyboard::Key::Character("0".into()) =>
+            {
+                state.view = View::default().clamp(screen, size);
+            }
+            Mouse(WheelScrolled { delta }) => {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => *y,
+                    mouse::ScrollDelta::Pixels { y, .. } => *y / PIXELS_PER_LINE,
+                };
+                let cursor = cursor.position_in(bounds)?;
+
+                state.view = state
+                    .view
+                    .zoom_at(cursor, ZOOM_PER_LINE.powf(lines))
+                    .clamp(screen, size);
+            }
+            Mouse(ButtonPressed(mouse::Button::Left | mouse::Button::Middle)) => {
+                state.drag = cursor.position_in(bounds);
+            }
+            Mouse(ButtonReleased(mouse::Button::Left | mouse::Button::Middle)) => {
+                state.drag = None;
+            }
+            // redraws, so the laser pointer follows the cursor
+            Mouse(CursorMoved { .. }) => {
+                if let Some(last) = state.drag
+                    && let Some(cursor) = cursor.position_in(bounds)
+                {
+                    state.view = View {
+                        offset: state.view.offset + (cursor - last),
+                        ..state.view
+                    }
+                    .clamp(screen, size);
+                    state.drag = Some(cursor);
+                }
+            }
+            _ => return None,
+        }
+
+        Some(Action::request_redraw().and_capture())
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        _bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Interaction {
+        if state.drag.is_some() {
+            Interaction::Grabbing
+        } else {
+            // the laser pointer replaces the cursor
+            Interaction::Hidden
+        }
+    }
+}
+
+impl<'app> Presentation<'app> {
+    /// Render the presentation
+    pub fn view(self) -> Element<'app, crate::Message> {
+        Canvas::new(self).width(Fill).height(Fill).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn zoom_keeps_point_under_cursor() {
+        let cursor = Point::new(100.0, 50.0);
+        let view = View::default().zoom_at(cursor, 2.0);
+
+        assert_eq!(
+            view,
+            View {
+                zoom: 2.0,
+                offset: Vector::new(-100.0, -50.0)
+            }
+        );
+        // back to where it was
+        assert_eq!(view.zoom_at(cursor, 0.5), View::default());
+    }
+
+    #[test]
+    fn screenshot_covers_the_screen() {
+        let screen = Size::new(200.0, 100.0);
+        let view = View {
+            zoom: 2.0,
+            offset: Vector::new(50.0, -500.0),
+        };
+
+        assert_eq!(
+            view.clamp(screen, screen),
+            View {
+                zoom: 2.0,
+                offset: Vector::new(0.0, -100.0)
+            }
+        );
+    }
+
+    #[test]
+    fn narrow_screenshot_is_centered() {
+        let screen = Size::new(200.0, 100.0);
+        let size = Size::new(50.0, 100.0);
+
+        assert_eq!(
+            View::default().clamp(screen, size),
+            View {
+                zoom: 1.0,
+                offset: Vector::new(75.0, 0.0)
+            }
+        );
+        // zoomed in, it is still narrower than the screen but taller
+        assert_eq!(
+            View {
+                zoom: 2.0,
+                offset: Vector::new(0.0, -500.0),
+            }
+            .clamp(screen, size),
+            View {
+                zoom: 2.0,
+                offset: Vector::new(50.0, -100.0)
+            }
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! Outlines of the recently used regions, shown while nothing is selected
+//!
+//! Clicking inside of an outline, without dragging, selects that region again
+
+use iced::{
+    Point, Rectangle,
+    advanced::mouse::Cursor,
+    widget::canvas::{self, Path},
+};
+
+/// Moving the cursor at most this many pixels between pressing and releasing is a click
+const CLICK_DISTANCE: f32 = 4.0;
+
+/// Length of each dash of the outlines
+const DASH_LENGTH: f32 = 8.0;
+
+/// Recently used regions, and the one which was clicked
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RecentRegions {
+    /// The regions, newest first
+    regions: Vec<Rectangle>,
+    /// Region under the cursor when the selection started being created
+    clicked: Option<Rectangle>,
+}
+
+impl RecentRegions {
+    /// Read at most `count` of the recently used regions
+    pub fn read(image_bounds: Rectangle, count: usize) -> Self {
+        if count == 0 {
+            return Self::default();
+        }
+
+        let regions = match crate::last_region::history(image_bounds) {
+            Ok(mut regions) => {
+                regions.truncate(count);
+                regions
+            }
+            Err(crate::last_region::Error::Io(err))
+                if err.kind() == std::io::ErrorKind::NotFound =>
+            {
+                Vec::new()
+            }
+            Err(err) => {
+                log::warn!("Failed to read the recently used regions: {err}");
+                Vec::new()
+            }
+        };
+
+        Self {
+            regions,
+            clicked: None,
+        }
+    }
+
+    /// The smallest of the regions at the `point`, so nested regions can all be picked
+    fn at(&self, point: Point) -> Option<Rectangle> {
+        self.regions
+            .iter()
+            .filter(|region| region.contains(point))
+            .min_by(|a, b| a.area().total_cmp(&b.area()))
+            .copied()
+    }
+
+    /// A selection started being created at `point`, while the outlines are visible
+    pub fn press(&mut self, point: Point) {
+        self.clicked = self.at(point);
+    }
+
+    /// The created selection is `rect`. If it was a click on an outline,
+    /// rather than a drag, returns the region of that outline
+    pub fn release(&mut self, rect: Rectangle) -> Option<Rectangle> {
+        self.clicked
+            .take()
+            .filter(|_| rect.width.max(rect.height) <= CLICK_DISTANCE)
+    }
+
+    /// Draw the outlines of the regions, highlighting the one under the `cursor`
+    pub fn draw(&self, frame: &mut canvas::Frame, cursor: Cursor, theme: &crate::Theme) {
+        let color = theme.recent_region;
+        let hovered = cursor.position().and_then(|cursor| self.at(cursor));
+
+        for region in &self.regions {
+            let is_hovered = Some(*region) == hovered;
+            let segments: &[f32] = if is_hovered { &[] } else { &[DASH_LENGTH] };
+
+            if is_hovered {
+                frame.fill_rectangle(region.position(), region.size(), color.scale_alpha(0.15));
+            }
+
+            frame.stroke(
+                &Path::rectangle(region.position(), region.size()),
+                canvas::Stroke {
+                    line_dash: canvas::LineDash {
+                        segments,
+                        offset: 0,
+                    },
+                    ..canvas::Stroke::default()
+                        .with_color(if is_hovered {
+                            color
+                        } else {
+                            color.scale_alpha(0.5)
+                        })
+                        .with_width(theme.frame_width())
+                },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::Size;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn click_picks_the_smallest_region() {
+        let large = Rectangle::new(Point::new(0.0, 0.0), Size::new(500.0, 500.0));
+        let small = Rectangle::new(Point::new(100.0, 100.0), Size::new(50.0, 50.0));
+        let mut recent = RecentRegions {
+            regions: vec![large, small],
+            clicked: None,
+        };
+
+        recent.press(Point::new(120.0, 120.0));
+        assert_eq!(
+            recent.release(Rectangle::new(
+                Point::new(120.0, 120.0),
+                Size::new(1.0, 2.0)
+            )),
+            Some(small)
+        );
+
+        recent.press(Point::new(300.0, 300.0));
+        assert_eq!(
+            recent.release(Rectangle::new(Point::new(300.0, 300.0), Size::ZERO)),
+            Some(large)
+        );
+    }
+
+    #[test]
+    fn drag_is_not_a_click() {
+        let region = Rectangle::new(Point::new(0.0, 0.0), Size::new(500.0, 500.0));
+        let mut recent = RecentRegions {
+            regions: vec![region],
+            clicked: None,
+        };
+
+        recent.press(Point::new(10.0, 10.0));
+        assert_eq!(
+            recent.release(Rectangle::new(
+                Point::new(10.0, 10.0),
+                Size::new(200.0, 3.0)
+            )),
+            None
+        );
+        // outside of every region
+        recent.press(Point::new(600.0, 600.0));
+        assert_eq!(
+            recent.release(Rectangle::new(Point::new(600.0, 600.0), Size::ZERO)),
+            None
+        );
+    }
+}
@@ -1,9 +1,11 @@
 //! A `Selection` is the structure representing a selected area in the background image
+use crate::geometry::Corner;
 use crate::geometry::Corners;
 use crate::geometry::Direction;
 use crate::geometry::RectangleExt as _;
 use crate::geometry::Side;
 use crate::geometry::SideOrCorner;
+use crate::geometry::SizeExt as _;
 use crate::lazy_rect::LazyRectangle;
 use delegate::delegate;
 use iced::Task;
@@ -40,6 +42,72 @@ pub enum Place {
     Bottom,
 }
 
+/// Format of the geometry of the selection, when copying it
+#[derive(ferrishot_knus::DecodeScalar, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GeometryFormat {
+    /// `<width>x<height>+<x>+<y>`, the same format as `ferrishot --region`
+    #[default]
+    Region,
+    /// A JSON object with `x`, `y`, `width` and `height`
+    Json,
+}
+
+/// Geometry of the selection, as copied with [`GeometryFormat::Json`]
+#[derive(serde::Serialize)]
+struct JsonGeometry {
+    /// Left edge (pixels)
+    x: u32,
+    /// Top edge (pixels)
+    y: u32,
+    /// Width (pixels)
+    width: u32,
+    /// Height (pixels)
+    height: u32,
+}
+
+impl GeometryFormat {
+    /// The geometry of the `rect` in this format
+    fn format(self, rect: Rectangle) -> String {
+        match self {
+            Self::Region => rect.as_str(),
+            Self::Json => serde_json::to_string(&JsonGeometry {
+                x: rect.x as u32,
+                y: rect.y as u32,
+                width: rect.width as u32,
+                height: rect.height as u32,
+            })
+            .expect("the geometry is always valid JSON"),
+        }
+    }
+}
+
+/// How to render the frame around the selection
+///
+/// The alternatives to `Solid` are easier to see for people who
+/// can't distinguish the color of the frame from the desktop underneath
+#[derive(ferrishot_knus::DecodeScalar, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FrameStyle {
+    /// A single line
+    #[default]
+    Solid,
+    /// 2 lines of contrasting colors next to each other
+    DoubleLine,
+    /// Dashes alternating between contrasting colors
+    Dashed,
+    /// A single line, with diagonal lines drawn over the region outside of the selection
+    Hatched,
+}
+
+/// What to do with a new selection which is smaller than `min-selection-size`
+#[derive(ferrishot_knus::DecodeScalar, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TinySelection {
+    /// Remove the selection, and explain why
+    #[default]
+    Reject,
+    /// Grow the selection around its center until it is large enough
+    Expand,
+}
+
 crate::declare_commands! {
     enum Command {
         /// Set the width to whatever number is currently pressed
@@ -53,17 +121,27 @@ crate::declare_commands! {
         },
         /// Remove the selection
         ClearSelection,
+        /// Copy position and size of the selection to the clipboard, for use in scripts
+        CopyGeometry {
+            format: GeometryFormat = GeometryFormat::Region,
+        },
         /// Shift the selection in the given direction by pixels
         Move {
             direction: Direction,
             amount: u32 = u32::MAX,
         },
         /// Increase the size of the selection in the given direction by pixels
+        ///
+        /// Once the anchor was moved with `cycle-resize-anchor`, the direction only picks
+        /// the width or height, and the anchor stays in place
         Extend {
             direction: Direction,
             amount: u32 = u32::MAX,
         },
         /// Decrease the size of the selection in the given direction by pixels
+        ///
+        /// Once the anchor was moved with `cycle-resize-anchor`, the direction only picks
+        /// the width or height, and the anchor stays in place
         Shrink {
             direction: Direction,
             amount: u32 = u32::MAX,
@@ -71,7 +149,35 @@ crate::declare_commands! {
         /// Move rectangle to a place
         Goto {
             place: Place,
-        }
+        },
+        /// Halve the width of the selection, keeping the `resize-anchor` corner in place
+        HalveWidth,
+        /// Halve the height of the selection, keeping the `resize-anchor` corner in place
+        HalveHeight,
+        /// Double the width of the selection, keeping the `resize-anchor` corner in place
+        DoubleWidth,
+        /// Double the height of the selection, keeping the `resize-anchor` corner in place
+        DoubleHeight,
+        /// Make the next corner clockwise stay in place when halving or doubling the selection
+        CycleResizeAnchor,
+        /// Select the region of the same size right next to the selection, like the next
+        /// of two equal panes. The new selection is kept inside of the image
+        DuplicateSelection {
+            direction: Direction = Direction::Right,
+        },
+    }
+}
+
+impl Command {
+    /// Moves or resizes the selection by a fixed number of pixels, such as `move left 1`
+    ///
+    /// These commands speed up while their key is held (see `key-acceleration`)
+    pub const fn is_nudge(self) -> bool {
+        matches!(
+            self,
+            Self::Move { amount, .. } | Self::Extend { amount, .. } | Self::Shrink { amount, .. }
+                if amount != u32::MAX
+        )
     }
 }
 
@@ -80,7 +186,7 @@ impl crate::command::Handler for Command {
         match self {
             Self::SetWidth => {
                 let Some(selection) = app.selection.as_mut() else {
-                    app.errors.push("Nothing is selected.");
+                    app.errors.warn("Nothing is selected.");
                     return Task::none();
                 };
                 let image_width = app.image.width() as f32;
@@ -90,7 +196,7 @@ impl crate::command::Handler for Command {
             }
             Self::SetHeight => {
                 let Some(selection) = app.selection.as_mut() else {
-                    app.errors.push("Nothing is selected.");
+                    app.errors.warn("Nothing is selected.");
                     return Task::none();
                 };
                 let image_height = app.image.height() as f32;
@@ -114,9 +220,19 @@ impl crate::command::Handler for Command {
             Self::ClearSelection => {
                 app.selection = None;
             }
+            Self::CopyGeometry { format } => {
+                let Some(rect) = app.selection.map(|sel| sel.rect.norm()) else {
+                    app.errors.warn("Nothing is selected.");
+                    return Task::none();
+                };
+
+                if let Err(err) = crate::clipboard::set_text(&format.format(rect)) {
+                    app.errors.push(err.to_string());
+                }
+            }
             Self::Move { direction, amount } => {
                 let Some(selection) = app.selection.as_mut() else {
-                    app.errors.push("Nothing is selected.");
+                    app.errors.warn("Nothing is selected.");
                     return Task::none();
                 };
                 let image_width = app.image.width() as f32;
@@ -135,9 +251,39 @@ impl crate::command::Handler for Command {
                     }
                 }
             }
+            Self::Extend { direction, amount } | Self::Shrink { direction, amount }
+                if app.is_resize_anchor_active =>
+            {
+                let Some(selection) = app.selection.as_mut() else {
+                    app.errors.warn("Nothing is selected.");
+                    return Task::none();
+                };
+                let sel = selection.norm();
+                let amount = amount as f32 * count as f32;
+                let amount = if matches!(self, Self::Shrink { .. }) {
+                    -amount
+                } else {
+                    amount
+                };
+
+                let Size { width, height } = sel.rect.size();
+                let size = match direction {
+                    Direction::Up | Direction::Down => Size::new(width, (height + amount).max(0.0)),
+                    Direction::Left | Direction::Right => {
+                        Size::new((width + amount).max(0.0), height)
+                    }
+                };
+
+                let rect = sel
+                    .rect
+                    .resized_from(app.resize_anchor, size)
+                    .clipped_in_bounds_of(app.image.bounds());
+
+                *selection = sel.with_pos(|_| rect.top_left()).with_size(|_| rect.size());
+            }
             Self::Extend { direction, amount } => {
                 let Some(selection) = app.selection.as_mut() else {
-                    app.errors.push("Nothing is selected.");
+                    app.errors.warn("Nothing is selected.");
                     return Task::none();
                 };
                 let image_height = app.image.height() as f32;
@@ -162,7 +308,7 @@ impl crate::command::Handler for Command {
             }
             Self::Shrink { direction, amount } => {
                 let Some(selection) = app.selection.as_mut() else {
-                    app.errors.push("Nothing is selected.");
+                    app.errors.warn("Nothing is selected.");
                     return Task::none();
                 };
                 let sel = selection.norm();
@@ -179,9 +325,59 @@ impl crate::command::Handler for Command {
                     Direction::Right => sel.with_width(|w| (w - amount).max(0.0)),
                 }
             }
+            Self::HalveWidth | Self::HalveHeight | Self::DoubleWidth | Self::DoubleHeight => {
+                let Some(selection) = app.selection.as_mut() else {
+                    app.errors.warn("Nothing is selected.");
+                    return Task::none();
+                };
+                let sel = selection.norm();
+                // pressing `3` beforehand halves or doubles 3 times
+                let factor = 2_f32.powi(count.min(16) as i32);
+
+                let Size { width, height } = sel.rect.size();
+                let (width, height) = match self {
+                    Self::HalveWidth => ((width / factor).floor(), height),
+                    Self::HalveHeight => (width, (height / factor).floor()),
+                    Self::DoubleWidth => (width * factor, height),
+                    _ => (width, height * factor),
+                };
+                let size = Size::new(width.max(1.0), height.max(1.0));
+
+                let rect = sel
+                    .rect
+                    .resized_from(app.resize_anchor, size)
+                    .clipped_in_bounds_of(app.image.bounds());
+
+                *selection = sel.with_pos(|_| rect.top_left()).with_size(|_| rect.size());
+            }
+            Self::CycleResizeAnchor => {
+                app.is_resize_anchor_active = true;
+                for _ in 0..count % 4 {
+                    app.resize_anchor = app.resize_anchor.clockwise();
+                }
+            }
+            Self::DuplicateSelection { direction } => {
+                let Some(selection) = app.selection.as_mut() else {
+                    app.errors.warn("Nothing is selected.");
+                    return Task::none();
+                };
+                let sel = selection.norm();
+                let Size { width, height } = sel.rect.size();
+                let count = count as f32;
+
+                let rect = match direction {
+                    Direction::Up => sel.rect.with_y(|y| y - height * count),
+                    Direction::Down => sel.rect.with_y(|y| y + height * count),
+                    Direction::Left => sel.rect.with_x(|x| x - width * count),
+                    Direction::Right => sel.rect.with_x(|x| x + width * count),
+                }
+                .moved_inside(app.image.bounds());
+
+                *selection = sel.with_pos(|_| rect.top_left());
+            }
             Self::Goto { place } => {
                 let Some(selection) = app.selection.as_mut() else {
-                    app.errors.push("Nothing is selected.");
+                    app.errors.warn("Nothing is selected.");
                     return Task::none();
                 };
                 let image_height = app.image.height() as f32;
@@ -274,9 +470,22 @@ pub enum Message {
     },
     /// Enter idle mode
     EnterIdle,
+    /// The mouse was released after creating a new selection
+    FinishCreate {
+        /// Action to run when the selection is large enough, with `--accept-on-select`
+        then: Option<crate::image::action::Command>,
+    },
     /// When we have not yet released the left mouse button
     /// and are dragging the selection to extend it
     ExtendNewSelection(Point),
+    /// Scrolled over the selection, which grows it around its center by `delta`.
+    /// A negative `delta` shrinks it
+    ScrollResize {
+        /// How much larger the selection gets
+        delta: Size,
+        /// A key to obtain `&mut Selection` from `Option<Selection>`
+        sel_is_some: SelectionIsSome,
+    },
     /// Holding right-click, the selection will move the
     /// nearest corner to the cursor
     ResizeToCursor {
@@ -290,10 +499,56 @@ pub enum Message {
     },
 }
 
+impl Message {
+    /// Whether this message makes it unnecessary to handle the `previous` message
+    ///
+    /// Dragging produces a message for every movement of the cursor. Each of them
+    /// holds the whole state of the drag, so only the latest one matters.
+    /// The exception is a message where the speed changed, it also updates
+    /// the status of the selection
+    pub fn supersedes(&self, previous: &Self) -> bool {
+        let speed_changed = Speed::Slow {
+            has_speed_changed: true,
+        };
+
+        match (self, previous) {
+            (Self::ExtendNewSelection(_), Self::ExtendNewSelection(_)) => true,
+            (
+                Self::Resize { speed, .. },
+                Self::Resize {
+                    speed: previous, ..
+                },
+            )
+            | (
+                Self::MoveSelection { speed, .. },
+                Self::MoveSelection {
+                    speed: previous, ..
+                },
+            ) => *speed != speed_changed && *previous != speed_changed,
+            _ => false,
+        }
+    }
+
+    /// Whether this message is produced continuously while dragging the selection
+    pub const fn is_drag(&self) -> bool {
+        matches!(
+            self,
+            Self::ExtendNewSelection(_) | Self::Resize { .. } | Self::MoveSelection { .. }
+        )
+    }
+}
+
 impl crate::message::Handler for Message {
     fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        let is_drag = self.is_drag();
+
         match self {
             Self::CreateSelection(point) => {
+                // the outlines of the recent regions are only visible without a selection
+                if app.selection.is_none() {
+                    app.recent_regions.press(point);
+                }
+
                 app.selection = Some(
                     Selection::new(
                         point,
@@ -314,6 +569,44 @@ impl crate::message::Handler for Message {
                     selection.status = SelectionStatus::Idle;
                 }
             }
+            Self::FinishCreate { then } => {
+                let Some(selection) = app.selection.as_mut() else {
+                    return Task::none();
+                };
+                selection.status = SelectionStatus::Idle;
+
+                let min_size = app.config.min_selection_size as f32;
+                let mut rect = selection.rect.norm();
+
+                // clicked on the outline of a recently used region
+                if let Some(region) = app.recent_regions.release(rect) {
+                    selection.rect = region;
+                    rect = region;
+                }
+
+                // accidental micro-drags produce useless captures
+                if rect.width < min_size || rect.height < min_size {
+                    match app.config.tiny_selection {
+                        TinySelection::Reject => {
+                            app.selection = None;
+                            app.errors.warn(format!(
+                                "The selection must be at least {min} x {min} px. Drag to create a larger one",
+                                min = app.config.min_selection_size
+                            ));
+                            return Task::none();
+                        }
+                        TinySelection::Expand => {
+                            selection.rect = rect
+                                .expanded_to(Size::square(min_size))
+                                .moved_inside(app.image.bounds());
+                        }
+                    }
+                }
+
+                if let Some(action) = then {
+                    return Task::done(crate::Message::AcceptOnSelect(action));
+                }
+            }
             Self::ExtendNewSelection(new_mouse_position) => {
                 app.selection = app.selection.take().map(|selected_region| {
                     let width = new_mouse_position.x - selected_region.rect.x;
@@ -322,6 +615,18 @@ impl crate::message::Handler for Message {
                     selected_region.with_size(|_| Size { width, height })
                 });
             }
+            Self::ScrollResize { delta, sel_is_some } => {
+                let bounds = app.image.bounds();
+                let sel = app.selection.unlock(sel_is_some);
+                let rect = sel.rect.norm();
+
+                sel.rect = rect
+                    .resized_around_center(Size::new(
+                        (rect.width + delta.width).clamp(1.0, bounds.width),
+                        (rect.height + delta.height).clamp(1.0, bounds.height),
+                    ))
+                    .moved_inside(bounds);
+            }
             Self::ResizeToCursor {
                 cursor_pos,
                 selection,
@@ -428,16 +733,68 @@ impl crate::message::Handler for Message {
             }
         }
 
+        // the resize math accumulates float errors, which shows up as
+        // the selection being 1px off
+        if is_drag && app.config.snap_to_pixel > 0 {
+            if let Some(sel) = app.selection.as_mut() {
+                sel.rect = sel.rect.snapped(app.config.snap_to_pixel as f32);
+            }
+        }
+
         Task::none()
     }
 }
 
-/// The size of the lines of the frame of the selection
-pub const FRAME_WIDTH: f32 = 2.0;
+/// Scrolling this many pixels on a touchpad is like scrolling a line
+const PIXELS_PER_LINE: f32 = 50.0;
+
+/// How far the pulse of a pre-selected selection grows away from it
+const PULSE_SPREAD: f32 = 24.0;
+
+/// Length of each dash of the frame, when using `FrameStyle::Dashed`
+const FRAME_DASH_LENGTH: f32 = 8.0;
 
-/// Size of the button for the icon, which includes the
-/// icon itself and space around it (bigger than `ICON_SIZE`)
-pub const ICON_BUTTON_SIZE: f32 = 37.0;
+/// Default of `Handles::interaction_area`
+const DEFAULT_INTERACTION_AREA: u32 = 35;
+
+/// Default of `Handles::corner_radius`
+const DEFAULT_CORNER_RADIUS: u32 = 6;
+
+/// Default of `Handles::icon_button`
+const DEFAULT_ICON_BUTTON: u32 = 37;
+
+/// Sizes of the parts of the selection which are grabbed or clicked
+///
+/// The sizes are in logical pixels, which are multiplied by the scale factor of the monitor.
+/// They grow with it on HiDPI monitors, and can be enlarged further for touchscreens
+///
+/// ```kdl
+/// handles interaction-area=35 corner-radius=6 icon-button=37
+/// ```
+#[derive(ferrishot_knus::Decode, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Handles {
+    /// Width of the area around each side and corner of the selection,
+    /// which can be grabbed to resize it
+    #[ferrishot_knus(default = DEFAULT_INTERACTION_AREA, property)]
+    pub interaction_area: u32,
+    /// Radius of the circles on the corners of the selection
+    #[ferrishot_knus(default = DEFAULT_CORNER_RADIUS, property)]
+    pub corner_radius: u32,
+    /// Size of the buttons around the selection, which includes the
+    /// icon itself and space around it
+    #[ferrishot_knus(default = DEFAULT_ICON_BUTTON, property)]
+    pub icon_button: u32,
+}
+
+impl Default for Handles {
+    fn default() -> Self {
+        Self {
+            interaction_area: DEFAULT_INTERACTION_AREA,
+            corner_radius: DEFAULT_CORNER_RADIUS,
+            icon_button: DEFAULT_ICON_BUTTON,
+        }
+    }
+}
 
 /// How fast the selection resizes
 #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
@@ -569,32 +926,188 @@ impl Selection {
         self
     }
 
-    /// Draw the `Selection`
-    pub fn draw(&self, frame: &mut canvas::Frame, bounds: Rectangle) {
+    /// Draw the `Selection`, marking the `anchor` corner which stays in place
+    /// when resizing with the keyboard, and highlighting the side under the `cursor`
+    pub fn draw(
+        &self,
+        frame: &mut canvas::Frame,
+        bounds: Rectangle,
+        style: FrameStyle,
+        anchor: Option<Corner>,
+        handles: Handles,
+        cursor: Cursor,
+    ) {
         self.draw_shade(frame, bounds);
-        self.draw_border(frame);
-        self.draw_corners(frame);
+
+        match style {
+            FrameStyle::Solid => self.draw_border(frame),
+            FrameStyle::DoubleLine => {
+                self.draw_border(frame);
+                // second line is just inside of the first one
+                let inner = self.rect.norm().shrink(self.theme.frame_width());
+                frame.stroke_rectangle(
+                    inner.position(),
+                    inner.size(),
+                    canvas::Stroke::default()
+                        .with_color(self.theme.selection_frame_alt)
+                        .with_width(self.theme.frame_width()),
+                );
+            }
+            FrameStyle::Dashed => {
+                frame.stroke_rectangle(
+                    self.pos(),
+                    self.size(),
+                    canvas::Stroke::default()
+                        .with_color(self.theme.selection_frame_alt)
+                        .with_width(self.theme.frame_width()),
+                );
+                frame.stroke_rectangle(
+                    self.pos(),
+                    self.size(),
+                    canvas::Stroke {
+                        line_dash: canvas::LineDash {
+                            segments: &[FRAME_DASH_LENGTH],
+                            offset: 0,
+                        },
+                        ..canvas::Stroke::default()
+                            .with_color(self.theme.selection_frame)
+                            .with_width(self.theme.frame_width())
+                    },
+                );
+            }
+            FrameStyle::Hatched => {
+                self.draw_hatching(frame, bounds);
+                self.draw_border(frame);
+            }
+        }
+
+        if let Some(side) = self.hovered_side(cursor, handles) {
+            self.draw_highlight(frame, side, handles.corner_radius as f32);
+        }
+        self.draw_corners(frame, handles.corner_radius as f32);
+        if let Some(anchor) = anchor {
+            self.draw_anchor(frame, anchor, handles.corner_radius as f32);
+        }
+    }
+
+    /// Highlight the `side` which will be resized by dragging it, before it is grabbed
+    ///
+    /// Corners get a ring around their circle with the `radius`, sides get a thicker line
+    pub fn draw_highlight(&self, frame: &mut canvas::Frame, side: SideOrCorner, radius: f32) {
+        let corners = self.corners();
+
+        let (path, width) = match side {
+            SideOrCorner::Corner(corner) => (
+                canvas::Path::circle(corners.get(corner), radius * 2.0),
+                self.theme.frame_width(),
+            ),
+            SideOrCorner::Side(side) => {
+                let (from, to) = match side {
+                    Side::Top => (corners.top_left, corners.top_right),
+                    Side::Right => (corners.top_right, corners.bottom_right),
+                    Side::Bottom => (corners.bottom_left, corners.bottom_right),
+                    Side::Left => (corners.top_left, corners.bottom_left),
+                };
+                (canvas::Path::line(from, to), self.theme.frame_width() * 3.0)
+            }
+        };
+
+        frame.stroke(
+            &path,
+            canvas::Stroke::default()
+                .with_color(self.theme.selection_frame)
+                .with_width(width),
+        );
+    }
+
+    /// Draw diagonal lines over the region outside of the selection
+    pub fn draw_hatching(&self, frame: &mut canvas::Frame, image_bounds: Rectangle) {
+        /// Distance between each diagonal line
+        const HATCH_SPACING: f32 = 12.0;
+
+        let sel = self.rect.norm();
+
+        // lines go from bottom-left to top-right, covering the entire image
+        let hatch = canvas::Path::new(|p| {
+            let mut offset = 0.0;
+            while offset < image_bounds.width + image_bounds.height {
+                p.move_to(Point::new(image_bounds.x + offset, image_bounds.y));
+                p.line_to(Point::new(
+                    image_bounds.x + offset - image_bounds.height,
+                    image_bounds.y + image_bounds.height,
+                ));
+                offset += HATCH_SPACING;
+            }
+        });
+
+        // areas above, below, to the left and to the right of the selection
+        let outside = [
+            Rectangle::new(
+                image_bounds.position(),
+                Size::new(image_bounds.width, sel.y - image_bounds.y),
+            ),
+            Rectangle::new(
+                Point::new(image_bounds.x, sel.y + sel.height),
+                Size::new(
+                    image_bounds.width,
+                    image_bounds.y + image_bounds.height - sel.y - sel.height,
+                ),
+            ),
+            Rectangle::new(
+                Point::new(image_bounds.x, sel.y),
+                Size::new(sel.x - image_bounds.x, sel.height),
+            ),
+            Rectangle::new(
+                Point::new(sel.x + sel.width, sel.y),
+                Size::new(
+                    image_bounds.x + image_bounds.width - sel.x - sel.width,
+                    sel.height,
+                ),
+            ),
+        ];
+
+        for region in outside {
+            if region.width <= 0.0 || region.height <= 0.0 {
+                continue;
+            }
+
+            frame.with_clip(region, |frame| {
+                frame.stroke(
+                    &hatch,
+                    canvas::Stroke::default()
+                        .with_color(self.theme.non_selected_region_pattern)
+                        .with_width(1.0),
+                );
+            });
+        }
+    }
+
+    /// The side or corner which is resized by dragging the `cursor`, or is being resized
+    pub fn hovered_side(&self, cursor: Cursor, handles: Handles) -> Option<SideOrCorner> {
+        match self.status {
+            // if we are already resizing, then this side takes priority
+            // e.g. we are resizing horizontally but we are on the top left
+            // corner = we should have horizontal resize cursor.
+            //
+            // when we started dragging a side, even if we go outside of the bounds of that side (which
+            // happens often when we are dragging the mouse fast), we don't want the side to change
+            SelectionStatus::Resize { resize_side, .. } => Some(resize_side),
+            SelectionStatus::Move { .. } => None,
+            _ => cursor.position().and_then(|cursor| {
+                self.corners()
+                    .side_at(cursor, handles.interaction_area as f32)
+            }),
+        }
     }
 
     /// Type of the mouse cursor
-    pub fn mouse_interaction(&self, cursor: Cursor) -> Interaction {
-        // if we are already resizing, then this cursor takes priority
-        // e.g. we are resizing horizontally but we are on the top left
-        // corner = we should have horizontal resize cursor.
-        (if let SelectionStatus::Resize { resize_side, .. } = self.status {
-            // resize icon corresponding to a specific side
-            Some(resize_side.mouse_icon())
-        } else if self.status.is_move() {
+    pub fn mouse_interaction(&self, cursor: Cursor, handles: Handles) -> Interaction {
+        (if self.status.is_move() {
             Some(Interaction::Grabbing)
         } else {
-            None
-        })
-        .or_else(|| {
-            // when we started dragging a side, even if we go outside of the bounds of that side (which
-            // happens often when we are dragging the mouse fast), we don't want the cursor to change
-            cursor
-                .position()
-                .and_then(|cursor| self.corners().side_at(cursor).map(SideOrCorner::mouse_icon))
+            // resize icon corresponding to a specific side
+            self.hovered_side(cursor, handles)
+                .map(SideOrCorner::mouse_icon)
         })
         .unwrap_or_else(|| {
             if self.cursor_in_selection(cursor).is_some() {
@@ -635,7 +1148,7 @@ impl Selection {
             self.size(),
             canvas::Stroke::default()
                 .with_color(self.theme.drop_shadow)
-                .with_width(FRAME_WIDTH * 2.0),
+                .with_width(self.theme.frame_width() * 2.0),
         );
         // Draw the border around the selection (the sides)
         frame.stroke_rectangle(
@@ -643,15 +1156,26 @@ impl Selection {
             self.size(),
             canvas::Stroke::default()
                 .with_color(self.theme.selection_frame)
-                .with_width(FRAME_WIDTH),
+                .with_width(self.theme.frame_width()),
         );
     }
 
-    /// Render the circles for each side
-    pub fn draw_corners(&self, frame: &mut canvas::Frame) {
-        /// Radius of each of the 4 corner circles in the frame drawn around the selection
-        const FRAME_CIRCLE_RADIUS: f32 = 6.0;
+    /// Render a fading border which grows away from the selection, as the `progress`
+    /// goes from `0.0` to `1.0`
+    pub fn draw_pulse(&self, frame: &mut canvas::Frame, progress: f32) {
+        let rect = self.rect.norm().expand(progress * PULSE_SPREAD);
 
+        frame.stroke_rectangle(
+            rect.position(),
+            rect.size(),
+            canvas::Stroke::default()
+                .with_color(self.theme.selection_frame.scale_alpha(1.0 - progress))
+                .with_width(self.theme.frame_width() * 2.0),
+        );
+    }
+
+    /// Render the circles with the `radius` for each corner
+    pub fn draw_corners(&self, frame: &mut canvas::Frame, radius: f32) {
         let corners = self.corners();
         for circle in [
             corners.top_left,
@@ -659,12 +1183,25 @@ impl Selection {
             corners.bottom_left,
             corners.bottom_right,
         ]
-        .map(|corner| canvas::Path::circle(corner, FRAME_CIRCLE_RADIUS))
+        .map(|corner| canvas::Path::circle(corner, radius))
         {
             frame.fill(&circle, self.theme.selection_frame);
         }
     }
 
+    /// Render a larger circle on the `anchor` corner, than the circles with the `radius`
+    /// on the other corners
+    pub fn draw_anchor(&self, frame: &mut canvas::Frame, anchor: Corner, radius: f32) {
+        let circle = canvas::Path::circle(self.corners().get(anchor), radius * 1.5);
+        frame.fill(&circle, self.theme.selection_frame);
+        frame.stroke(
+            &circle,
+            canvas::Stroke::default()
+                .with_color(self.theme.selection_frame_alt)
+                .with_width(self.theme.frame_width()),
+        );
+    }
+
     /// Set status of the selection
     pub const fn with_status(mut self, status: SelectionStatus) -> Self {
         self.status = status;
@@ -694,6 +1231,8 @@ impl Selection {
         event: &iced::Event,
         _bounds: Rectangle,
         cursor: Cursor,
+        handles: Handles,
+        scroll_resize_step: u32,
     ) -> Option<Action<crate::Message>> {
         use iced::Event::{Keyboard, Mouse, Touch};
         use iced::keyboard::Event::KeyPressed;
@@ -704,6 +1243,8 @@ impl Selection {
         use iced::mouse::Event::ButtonPressed;
         use iced::mouse::Event::ButtonReleased;
         use iced::mouse::Event::CursorMoved;
+        use iced::mouse::Event::WheelScrolled;
+        use iced::mouse::ScrollDelta;
         use iced::touch::Event::{FingerLifted, FingerMoved, FingerPressed};
 
         let message = match event {
@@ -712,7 +1253,7 @@ impl Selection {
 
                 if let Some((cursor, side)) = cursor.position().and_then(|cursor_pos| {
                     self.corners()
-                        .side_at(cursor_pos)
+                        .side_at(cursor_pos, handles.interaction_area as f32)
                         .map(|side| (cursor_pos, side))
                 }) {
                     // Left click on corners = Start resizing selection
@@ -743,22 +1284,19 @@ impl Selection {
             Touch(FingerLifted { .. }) | Mouse(ButtonReleased(Left)) => {
                 state.is_left_down = false;
 
-                self.accept_on_select.map_or_else(
-                    // stop the creating of the initial selection
-                    || crate::Message::Selection(Box::new(Message::EnterIdle)),
-                    |on_select| {
-                        if self.is_first && !state.is_ctrl_down {
-                            // we have created 1 selections in total, (the current one)
-                            crate::Message::Command {
-                                action: on_select.into_key_action(),
-                                count: 1,
-                            }
-                        } else {
-                            // stop the creating of the initial selection
-                            crate::Message::Selection(Box::new(Message::EnterIdle))
-                        }
-                    },
-                )
+                // we have created 1 selections in total, (the current one)
+                let accept = self
+                    .accept_on_select
+                    .filter(|_| self.is_first && !state.is_ctrl_down);
+
+                if self.is_create() {
+                    // the new selection could be too small
+                    crate::Message::Selection(Box::new(Message::FinishCreate { then: accept }))
+                } else if let Some(action) = accept {
+                    crate::Message::AcceptOnSelect(action)
+                } else {
+                    crate::Message::Selection(Box::new(Message::EnterIdle))
+                }
             }
             Keyboard(KeyPressed {
                 key: Named(Control),
@@ -894,6 +1432,42 @@ impl Selection {
             {
                 crate::Message::Selection(Box::new(Message::ExtendNewSelection(*position)))
             }
+            // the highlight of the side under the cursor follows it. Nothing else
+            // changes, so there is no message which would draw it again
+            Mouse(CursorMoved { .. }) if self.is_idle() => {
+                let hovered_side = self.hovered_side(cursor, handles);
+                if hovered_side == state.hovered_side {
+                    return None;
+                }
+                state.hovered_side = hovered_side;
+
+                return Some(Action::request_redraw());
+            }
+            // scrolling over the selection grows or shrinks it around its center
+            Mouse(WheelScrolled { delta })
+                if scroll_resize_step != 0
+                    && self.is_idle()
+                    && self.cursor_in_selection(cursor).is_some() =>
+            {
+                let (x, y) = match delta {
+                    ScrollDelta::Lines { x, y } => (*x, *y),
+                    ScrollDelta::Pixels { x, y } => (*x / PIXELS_PER_LINE, *y / PIXELS_PER_LINE),
+                };
+                // some platforms scroll horizontally while shift is held
+                let lines = if y == 0.0 { x } else { y };
+                let step = lines * scroll_resize_step as f32;
+
+                crate::Message::Selection(Box::new(Message::ScrollResize {
+                    delta: if state.is_ctrl_down {
+                        Size::new(step, 0.0)
+                    } else if state.is_shift_down {
+                        Size::new(0.0, step)
+                    } else {
+                        Size::new(step, step)
+                    },
+                    sel_is_some: SelectionIsSome { _private: () },
+                }))
+            }
             _ => return None,
         };
 
@@ -993,4 +1567,28 @@ pub struct SelectionKeysState {
     pub is_shift_down: bool,
     /// Control key is currently being held down
     pub is_ctrl_down: bool,
+    /// Side or corner which was highlighted under the cursor, when it was last drawn
+    pub hovered_side: Option<SideOrCorner>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn geometry_formats() {
+        let rect = Rectangle {
+            x: 10.0,
+            y: 20.0,
+            width: 300.0,
+            height: 400.0,
+        };
+
+        assert_eq!(GeometryFormat::Region.format(rect), "300x400+10+20");
+        assert_eq!(
+            GeometryFormat::Json.format(rect),
+            r#"{"x":10,"y":20,"width":300,"height":400}"#
+        );
+    }
 }
@@ -1,34 +1,47 @@
 //! Icons around the selection rectangle
 
 use core::f32;
+use std::ops::Range;
 
 use iced::{
     Element,
     Length::{self, Fill},
-    Padding, Radians, Rectangle, Rotation,
-    widget::{Column, Row, Space, row, tooltip},
+    Padding, Radians, Rectangle, Rotation, Size,
+    widget::{Column, Row, Space, container, row, tooltip},
 };
 
-use crate::{icon, message::Message, ui::selection::FRAME_WIDTH};
-use crate::{lazy_rect::LazyRectangle, ui::selection::ICON_BUTTON_SIZE};
-use iced::{Background, Border, Shadow, widget};
+use crate::icon;
+use crate::lazy_rect::LazyRectangle;
+use iced::{Background, Border, Shadow, Task, widget};
 
 use super::app::{self};
+use super::popup::Popup;
+
+/// Selection icons message
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// Open a list of the icons which did not fit around the selection,
+    /// starting with the icon at this index of [`actions`]
+    OpenMore(usize),
+    /// An icon in the list was clicked, so close the list and run its command
+    Run(crate::Command),
+}
+
+impl crate::message::Handler for Message {
+    fn handle(self, app: &mut crate::App) -> Task<crate::Message> {
+        match self {
+            Self::OpenMore(first) => {
+                app.popup = Some(Popup::MoreIcons { first });
+                Task::none()
+            }
+            Self::Run(action) => {
+                app.popup = None;
+                Task::done(crate::Message::Command { action, count: 1 })
+            }
+        }
+    }
+}
 
-// Here is the behaviour that we want
-//
-// We have a list of icons we want to render.
-// We want to render every single one of them.
-// Each icon should not be shrunk, nor should it render in weird positions
-//
-// for each side in [bottom, right, top, left] we render
-// all of the icons that fit on that side.
-//
-// But then we may have a small selection which doesn't manage to render all of the icons,
-// so we deal with that by rendering a couple extra rows on top and bottom
-
-/// Height and width of each icon
-const PX_PER_ICON: f32 = SPACE_BETWEEN_ICONS + ICON_BUTTON_SIZE;
 /// The minimum amount of icons to render at the top
 const MIN_TOP_BOTTOM_ICONS: usize = 3;
 /// The minimum amount of icons to render on the sides
@@ -47,33 +60,45 @@ pub fn icon_tooltip<'a, Message>(
         .style(move |_| widget::container::Style {
             text_color: Some(theme.tooltip_fg),
             background: Some(Background::Color(theme.tooltip_bg)),
-            border: Border::default(),
+            border: theme.tooltip_border(),
             shadow: Shadow::default(),
         })
         .gap(10.0)
 }
 
-/// Styled icon as a button
+/// Styled icon as a button, which is `size` pixels large
 pub fn selection_icon<'a, Message>(
     icon: widget::Svg<'a>,
     theme: &'a crate::Theme,
+    size: f32,
 ) -> widget::Button<'a, Message> {
-    /// Width and height for icons *inside* of buttons
-    const ICON_SIZE: f32 = 32.0;
+    /// Space between the icon and the edges of the button
+    const ICON_PADDING: f32 = 5.0;
 
     widget::button(
         icon.style(move |_, _| widget::svg::Style {
             color: Some(theme.icon_fg),
         })
-        .width(Length::Fixed(ICON_SIZE))
-        .height(Length::Fixed(ICON_SIZE)),
+        .width(Length::Fixed(size - ICON_PADDING))
+        .height(Length::Fixed(size - ICON_PADDING)),
     )
-    .width(Length::Fixed(ICON_BUTTON_SIZE))
-    .height(Length::Fixed(ICON_BUTTON_SIZE))
-    .style(move |_, _| {
-        let mut style = widget::button::Style::default().with_background(theme.icon_bg);
+    .width(Length::Fixed(size))
+    .height(Length::Fixed(size))
+    .style(move |_, status| {
+        // the icon can't be clicked, like copying without a clipboard
+        let is_disabled = status == widget::button::Status::Disabled;
+
+        let mut style = widget::button::Style::default().with_background(if is_disabled {
+            theme.icon_bg.scale_alpha(0.4)
+        } else {
+            theme.icon_bg
+        });
         style.shadow = Shadow {
-            color: theme.drop_shadow,
+            color: if is_disabled {
+                iced::Color::TRANSPARENT
+            } else {
+                theme.drop_shadow
+            },
             blur_radius: 3.0,
             offset: iced::Vector { x: 0.0, y: 0.0 },
         };
@@ -97,410 +122,471 @@ pub struct SelectionIcons<'app> {
     pub selection_rect: Rectangle,
 }
 
-/// Add icons to the side until the amount of them reaches the minimum required
-fn add_icons_until_there_is_at_least_n_of_them<'a, const MIN_ELEMENTS: usize>(
-    mut icons: Vec<Element<'a, Message>>,
-    mut iter: impl Iterator<Item = (Element<'a, Message>, &'static str)>,
-    mut padding: f32,
-    total_icons_positioned: &mut usize,
-    tooltip_position: tooltip::Position,
-    theme: &'a crate::Theme,
-) -> (Vec<Element<'a, Message>>, f32) {
-    while icons.len() < MIN_ELEMENTS {
-        if let Some((next, tooltip_str)) = iter.by_ref().next() {
-            icons.push(icon_tooltip(next, tooltip_str, tooltip_position, theme).into());
-            *total_icons_positioned += 1;
-            padding -= PX_PER_ICON / 2.0;
+/// Where each icon goes, as ranges of their indices. Icons are placed in order
+///
+/// The icons go around the selection, one line on each side in the order bottom,
+/// right, top and left. When there is no room around the selection, they go inside of it.
+/// The icons which fit nowhere overflow into a list, opened with the last icon which fits
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Layout {
+    /// Row below the selection
+    bottom: Range<usize>,
+    /// Column to the right of the selection
+    right: Range<usize>,
+    /// Row above the selection
+    top: Range<usize>,
+    /// Column to the left of the selection
+    left: Range<usize>,
+    /// Row inside of the bottom edge of the selection, when there is no room around it
+    inside: Range<usize>,
+    /// Index of the last icon which fits. It opens the list of the rest instead,
+    /// when some of them do not fit
+    more: Option<usize>,
+    /// Icons which fit nowhere, and go into the list
+    overflow: Range<usize>,
+}
+
+impl Layout {
+    /// Place `icons` many icons of `px_per_icon` pixels around the `selection`,
+    /// which is on a screen of size `screen`
+    fn new(icons: usize, selection: Rectangle, screen: Size, px_per_icon: f32) -> Self {
+        let mut next = 0;
+        let mut take = |capacity: usize| {
+            let start = next;
+            next = next.saturating_add(capacity).min(icons);
+            start..next
+        };
+
+        // how many lines of icons fit into `space`. The last one needs no space after it
+        let lines_fitting =
+            |space: f32| ((space + SPACE_BETWEEN_ICONS) / px_per_icon).max(0.0) as usize;
+
+        let has_top = lines_fitting(selection.y) > 0;
+        let has_bottom = lines_fitting(screen.height - selection.y - selection.height) > 0;
+        let has_left = lines_fitting(selection.x) > 0;
+        let has_right = lines_fitting(screen.width - selection.x - selection.width) > 0;
+
+        // lines may stick out of small selections, so there are enough icons near them
+        let row = ((selection.width / px_per_icon) as usize).max(MIN_TOP_BOTTOM_ICONS);
+        let column = ((selection.height / px_per_icon) as usize).max(MIN_SIDE_ICONS);
+
+        let bottom = take(if has_bottom { row } else { 0 });
+        let right = take(if has_right { column } else { 0 });
+        let top = take(if has_top { row } else { 0 });
+        let left = take(if has_left { column } else { 0 });
+        // like when the selection covers the entire screen
+        let inside = take(if selection.height >= px_per_icon {
+            (selection.width / px_per_icon) as usize
         } else {
-            break;
+            0
+        });
+        let mut overflow = take(usize::MAX);
+
+        // with nothing shown, there is also nowhere to open the list from
+        let more = (!overflow.is_empty())
+            .then(|| overflow.start.checked_sub(1))
+            .flatten();
+        if let Some(more) = more {
+            overflow.start = more;
+        }
+
+        Self {
+            bottom,
+            right,
+            top,
+            left,
+            inside,
+            more,
+            overflow,
         }
     }
-    (icons, padding)
 }
 
-/// Position icons until we reach an adequate amount of them
-fn position_icons_in_line<'a>(
-    space_available: f32,
+/// A line of `icons` with tooltips at `tooltip_position`, and how far to offset it so it is
+/// centered along a side which is `length` pixels long
+fn line<'a>(
+    icons: impl IntoIterator<Item = (Element<'a, crate::Message>, String)>,
+    length: f32,
     tooltip_position: tooltip::Position,
-    total_icons_positioned: &mut usize,
-    mut icons_iter: impl Iterator<Item = (Element<'a, Message>, &'static str)>,
-    icons_len: usize,
     theme: &'a crate::Theme,
-) -> (Vec<Element<'a, Message>>, f32) {
-    let icons_left_to_position = icons_len - *total_icons_positioned;
-    let icons_rendered_here =
-        ((space_available / PX_PER_ICON) as usize).min(icons_left_to_position);
-    *total_icons_positioned += icons_rendered_here;
-
-    // we do this thing because we need to know exactly
-    // how many elems we got. size_hint may be unreliable
-    let mut icons = Vec::with_capacity(icons_rendered_here);
-    for _ in 0..icons_rendered_here {
-        if let Some((icon, tooltip_str)) = icons_iter.by_ref().next() {
-            icons.push(icon_tooltip(icon, tooltip_str, tooltip_position, theme).into());
-        }
-    }
+    px_per_icon: f32,
+) -> (Vec<Element<'a, crate::Message>>, f32) {
+    let icons = icons
+        .into_iter()
+        .map(|(icon, tooltip_str)| {
+            icon_tooltip(icon, widget::text(tooltip_str), tooltip_position, theme).into()
+        })
+        .collect::<Vec<_>>();
 
-    // if there is just 0 element it will take away the icon padding so it can be negative
-    // ensure it is positive
-    let space_used = (icons.len() as f32) * PX_PER_ICON + -SPACE_BETWEEN_ICONS.max(0.0);
+    let space_used = (icons.len() as f32 * px_per_icon - SPACE_BETWEEN_ICONS).max(0.0);
 
-    let padding = (space_available - space_used) / 2.0;
+    (icons, (length - space_used) / 2.0)
+}
 
-    (icons, padding)
+/// One of the icons around the selection
+pub struct IconAction<'app> {
+    /// The icon
+    pub icon: widget::Svg<'app>,
+    /// Command which runs when the icon is clicked. `None` disables the icon
+    pub command: Option<crate::Command>,
+    /// What the icon does
+    pub label: String,
+    /// Keys which do the same, as bound in the config
+    pub keys: Option<String>,
 }
 
-impl<'app> SelectionIcons<'app> {
-    /// Render icons around the selection border
-    // TODO: Currently, this function does not handle the case where the selection has the
-    // same size as the entire screen - so no icons can be rendered at all.
-    //
-    // We should add even more fallbacks so that it can render a little bit inside of the selection.
-    pub fn view(self) -> Element<'app, Message> {
-        let icons = vec![
-            (
-                icon!(Fullscreen),
-                crate::Command::Selection(super::selection::Command::SelectRegion {
-                    selection: LazyRectangle::FULL,
-                }),
-                "Select entire monitor (F11)",
-            ),
-            (
-                icon!(Clipboard),
-                crate::Command::ImageUpload(crate::image::action::Command::CopyToClipboard),
-                "Copy to Clipboard (Enter)",
-            ),
-            (
-                icon!(Save),
-                crate::Command::ImageUpload(crate::image::action::Command::SaveScreenshot),
-                "Save Screenshot (Ctrl + s)",
-            ),
-            (
-                icon!(Close),
-                crate::Command::App(app::Command::Exit),
-                "Exit (esc)",
-            ),
-            if self.app.is_uploading_image {
-                // how many seconds we are into the current spin
-                let current_spin_secs = self.app.time_elapsed.as_secs_f32() % 2.0;
-                // how much % we are through the current spin
-                let current_spin_percent = current_spin_secs / 2.0;
-
-                (
-                    icon!(Spinner).rotation(Rotation::Floating(Radians(
-                        current_spin_percent * f32::consts::TAU,
-                    ))),
-                    // TODO: Clicking this should cancel the image upload
-                    crate::Command::App(app::Command::NoOp),
-                    "Screenshot is being uploaded...",
-                )
+impl IconAction<'_> {
+    /// The label, followed by the keys
+    fn tooltip(&self) -> String {
+        match &self.keys {
+            Some(keys) => format!("{} ({keys})", self.label),
+            None => self.label.clone(),
+        }
+    }
+}
+
+/// Icons shown around the selection, in the order they are placed
+pub fn actions(app: &crate::App) -> Vec<IconAction<'static>> {
+    let keys_of = |command: &crate::Command| app.config.keys.keys_of(command);
+
+    // the primary action also runs with the keys of `accept`, which are shown instead
+    let action_keys = |action: crate::image::action::Command| {
+        (action == app.config.primary_action)
+            .then(|| keys_of(&crate::Command::App(app::Command::Accept)))
+            .flatten()
+            .or_else(|| keys_of(&crate::Command::ImageUpload(action)))
+    };
+
+    let fullscreen = crate::Command::Selection(super::selection::Command::SelectRegion {
+        selection: LazyRectangle::FULL,
+    });
+
+    // the tools of the annotations, which draw on the screenshot
+    let tool = |icon, tool, label: &str| {
+        let command =
+            crate::Command::Annotations(super::annotations::Command::SetAnnotationTool { tool });
+        let is_active = app.mode == super::mode::Mode::Annotate && app.annotations.tool == tool;
+
+        IconAction {
+            icon,
+            keys: keys_of(&command),
+            command: Some(command),
+            label: if is_active {
+                format!("{label} (active)")
             } else {
-                (
-                    icon!(Upload),
-                    crate::Command::ImageUpload(crate::image::action::Command::UploadScreenshot),
-                    "Upload Screenshot (Ctrl + u)",
-                )
+                label.to_string()
             },
-        ]
-        .into_iter()
-        .map(|(icon, action, label)| {
-            (
-                selection_icon(icon, &self.app.config.theme)
-                    .on_press(Message::Command {
-                        action,
-                        // Count does not actually matter at all, since it does not make sense to
-                        // do any of the buttons multiple times.
-                        count: 1,
-                    })
-                    .into(),
-                label,
-            )
-        });
-
-        let is_enough_space_at_bottom = self.image_height
-            - (self.selection_rect.y + self.selection_rect.height)
-            > ICON_BUTTON_SIZE;
-        let is_enough_space_at_right = self.image_width
-            - (self.selection_rect.x + self.selection_rect.width)
-            > ICON_BUTTON_SIZE;
-        let is_enough_space_at_top = self.selection_rect.y > ICON_BUTTON_SIZE;
-        let is_enough_space_at_left = self.selection_rect.x > ICON_BUTTON_SIZE;
-
-        let icons_len = icons.len();
-        let mut icons_iter = icons.into_iter();
-        let mut total_icons_positioned = 0;
-
-        // first position the icons on each side (bottom -> right -> top -> left)
-        // (bottom_icons, mut bottom_padding)
-
-        let bottom_icons = is_enough_space_at_bottom.then(|| {
-            position_icons_in_line(
-                self.selection_rect.width,
-                tooltip::Position::Bottom,
-                &mut total_icons_positioned,
-                &mut icons_iter,
-                icons_len,
-                &self.app.config.theme,
-            )
-        });
-
-        let right_icons = is_enough_space_at_right.then(|| {
-            position_icons_in_line(
-                self.selection_rect.height,
-                tooltip::Position::Right,
-                &mut total_icons_positioned,
-                &mut icons_iter,
-                icons_len,
-                &self.app.config.theme,
-            )
-        });
-
-        let top_icons = is_enough_space_at_top.then(|| {
-            position_icons_in_line(
-                self.selection_rect.width,
-                tooltip::Position::Top,
-                &mut total_icons_positioned,
-                &mut icons_iter,
-                icons_len,
-                &self.app.config.theme,
-            )
-        });
-
-        let left_icons = is_enough_space_at_left.then(|| {
-            position_icons_in_line(
-                self.selection_rect.height,
-                tooltip::Position::Left,
-                &mut total_icons_positioned,
-                &mut icons_iter,
-                icons_len,
-                &self.app.config.theme,
-            )
-        });
+        }
+    };
+
+    vec![
+        IconAction {
+            icon: icon!(Fullscreen),
+            keys: keys_of(&fullscreen),
+            command: Some(fullscreen),
+            label: "Select entire monitor".to_string(),
+        },
+        // disabled without a clipboard, the icon explains why
+        match &app.clipboard_error {
+            Some(err) => {
+                let with_keys = |label: &str, action| match action_keys(action) {
+                    Some(keys) => format!("{label} ({keys})"),
+                    None => label.to_string(),
+                };
+
+                IconAction {
+                    icon: icon!(Clipboard),
+                    command: None,
+                    label: format!(
+                        "Clipboard is unavailable: {err}\n{} or {} instead",
+                        with_keys("Save", crate::image::action::Command::SaveScreenshot),
+                        with_keys("upload", crate::image::action::Command::UploadScreenshot),
+                    ),
+                    keys: None,
+                }
+            }
+            None => IconAction {
+                icon: icon!(Clipboard),
+                command: Some(crate::Command::ImageUpload(
+                    crate::image::action::Command::CopyToClipboard,
+                )),
+                label: "Copy to Clipboard".to_string(),
+                keys: action_keys(crate::image::action::Command::CopyToClipboard),
+            },
+        },
+        IconAction {
+            icon: icon!(Save),
+            command: Some(crate::Command::ImageUpload(
+                crate::image::action::Command::SaveScreenshot,
+            )),
+            label: "Save Screenshot".to_string(),
+            keys: action_keys(crate::image::action::Command::SaveScreenshot),
+        },
+        IconAction {
+            icon: icon!(Close),
+            command: Some(crate::Command::App(app::Command::Exit)),
+            label: "Exit".to_string(),
+            // with nothing else to close, `smart-escape` exits too
+            keys: keys_of(&crate::Command::App(app::Command::Exit))
+                .or_else(|| keys_of(&crate::Command::App(app::Command::SmartEscape))),
+        },
+        if app.is_uploading_image {
+            // how many seconds we are into the current spin
+            let current_spin_secs = app.time_elapsed.as_secs_f32() % 2.0;
+            // how much % we are through the current spin
+            let current_spin_percent = current_spin_secs / 2.0;
+
+            IconAction {
+                icon: icon!(Spinner).rotation(Rotation::Floating(Radians(
+                    current_spin_percent * f32::consts::TAU,
+                ))),
+                // TODO: Clicking this should cancel the image upload
+                command: Some(crate::Command::App(app::Command::NoOp)),
+                label: "Screenshot is being uploaded...".to_string(),
+                keys: None,
+            }
+        } else {
+            IconAction {
+                icon: icon!(Upload),
+                command: Some(crate::Command::ImageUpload(
+                    crate::image::action::Command::UploadScreenshot,
+                )),
+                label: "Upload Screenshot".to_string(),
+                keys: action_keys(crate::image::action::Command::UploadScreenshot),
+            }
+        },
+        tool(
+            icon!(ArrowRight),
+            super::annotations::Tool::Arrow,
+            "Draw arrows",
+        ),
+        tool(
+            icon!(Square),
+            super::annotations::Tool::Rectangle,
+            "Draw rectangles",
+        ),
+        tool(icon!(Pen), super::annotations::Tool::Pen, "Draw freehand"),
+        tool(icon!(Text), super::annotations::Tool::Text, "Write text"),
+    ]
+}
 
-        // if we reach here, our selection is to small to nicely
-        // render all of the icons so we must "stack" them somehow
+impl<'app> SelectionIcons<'app> {
+    /// Render icons around the selection border
+    pub fn view(self) -> Element<'app, crate::Message> {
+        let icon_button = self.app.config.handles.icon_button as f32;
+        // height and width of each icon
+        let px_per_icon = SPACE_BETWEEN_ICONS + icon_button;
+
+        let theme = &self.app.config.theme;
+        let sel = self.selection_rect;
+        let actions = actions(self.app);
+        let layout = Layout::new(
+            actions.len(),
+            sel,
+            Size::new(self.image_width, self.image_height),
+            px_per_icon,
+        );
 
-        // for the 4 sides, combined they will fit at LEAST 8 icons (3 top 3 bottom 1 right 1 left)
+        let mut icons = actions
+            .into_iter()
+            .enumerate()
+            .map(|(index, action)| {
+                let (icon, message, tooltip) = if Some(index) == layout.more {
+                    (
+                        icon!(More),
+                        Some(crate::Message::SelectionIcons(Message::OpenMore(index))),
+                        "More...".to_string(),
+                    )
+                } else {
+                    let tooltip = action.tooltip();
+                    (
+                        action.icon,
+                        action.command.map(|action| crate::Message::Command {
+                            action,
+                            // Count does not actually matter at all, since it does not make sense to
+                            // do any of the buttons multiple times.
+                            count: 1,
+                        }),
+                        tooltip,
+                    )
+                };
+
+                Some((
+                    selection_icon(icon, theme, icon_button)
+                        .on_press_maybe(message)
+                        .into(),
+                    tooltip,
+                ))
+            })
+            .collect::<Vec<_>>();
 
-        let bottom_icons = bottom_icons.map(|(bottom_icons, bottom_padding)| {
-            add_icons_until_there_is_at_least_n_of_them::<MIN_TOP_BOTTOM_ICONS>(
-                bottom_icons,
-                &mut icons_iter,
-                bottom_padding,
-                &mut total_icons_positioned,
-                tooltip::Position::Bottom,
-                &self.app.config.theme,
-            )
-        });
+        // icons of the `range`, each of them is only placed once
+        let mut take = |range: Range<usize>| -> Vec<_> {
+            icons[range].iter_mut().filter_map(Option::take).collect()
+        };
 
-        let top_icons = top_icons.map(|(top_icons, top_padding)| {
-            add_icons_until_there_is_at_least_n_of_them::<MIN_TOP_BOTTOM_ICONS>(
-                top_icons,
-                &mut icons_iter,
-                top_padding,
-                &mut total_icons_positioned,
-                tooltip::Position::Top,
-                &self.app.config.theme,
-            )
-        });
+        let mut row_of = |range: Range<usize>, tooltip_position| {
+            let (icons, padding) =
+                line(take(range), sel.width, tooltip_position, theme, px_per_icon);
+
+            row![
+                Space::with_width(sel.x),
+                Row::from_vec(icons)
+                    .spacing(SPACE_BETWEEN_ICONS)
+                    .height(px_per_icon)
+                    .padding(Padding::default().left(padding))
+            ]
+        };
 
-        let left_icons = left_icons.map(|(left_icons, left_padding)| {
-            add_icons_until_there_is_at_least_n_of_them::<MIN_SIDE_ICONS>(
-                left_icons,
-                &mut icons_iter,
-                left_padding,
-                &mut total_icons_positioned,
-                tooltip::Position::Left,
-                &self.app.config.theme,
-            )
-        });
+        let top_icons =
+            (!layout.top.is_empty()).then(|| row_of(layout.top, tooltip::Position::Top));
+        let bottom_icons = row_of(layout.bottom, tooltip::Position::Bottom);
+
+        let mut column_of = |range: Range<usize>, tooltip_position| {
+            (!range.is_empty()).then(|| {
+                let (icons, padding) = line(
+                    take(range),
+                    sel.height,
+                    tooltip_position,
+                    theme,
+                    px_per_icon,
+                );
+
+                Column::from_vec(icons)
+                    .spacing(SPACE_BETWEEN_ICONS)
+                    .width(px_per_icon)
+                    .padding(Padding::default().top(padding))
+            })
+        };
 
-        let right_icons = right_icons.map(|(right_icons, right_padding)| {
-            add_icons_until_there_is_at_least_n_of_them::<MIN_SIDE_ICONS>(
-                right_icons,
-                &mut icons_iter,
-                right_padding,
-                &mut total_icons_positioned,
-                tooltip::Position::Right,
-                &self.app.config.theme,
-            )
-        });
+        let right_icons = column_of(layout.right, tooltip::Position::Right);
+        let left_icons = column_of(layout.left, tooltip::Position::Left);
 
-        // position two additional rows of icons on top and bottom
-        // if we STILL have extra icons left
-
-        let extra_top_icons = is_enough_space_at_top.then(|| {
-            position_icons_in_line(
-                self.selection_rect.width,
-                tooltip::Position::Top,
-                &mut total_icons_positioned,
-                &mut icons_iter,
-                icons_len,
-                &self.app.config.theme,
-            )
-        });
+        let (inside_icons, _) = line(
+            take(layout.inside),
+            sel.width,
+            tooltip::Position::Top,
+            theme,
+            px_per_icon,
+        );
 
-        let extra_bottom_icons = is_enough_space_at_bottom.then(|| {
-            position_icons_in_line(
-                self.selection_rect.width,
-                tooltip::Position::Bottom,
-                &mut total_icons_positioned,
-                &mut icons_iter,
-                icons_len,
-                &self.app.config.theme,
-            )
-        });
+        // include the frame so the icons do not touch the frame
+        let selection_height = theme.frame_width() * 2.0 + sel.height;
 
-        let extra_bottom_icons =
-            extra_bottom_icons.map(|(extra_bottom_icons, extra_bottom_padding)| {
-                add_icons_until_there_is_at_least_n_of_them::<MIN_TOP_BOTTOM_ICONS>(
-                    extra_bottom_icons,
-                    &mut icons_iter,
-                    extra_bottom_padding,
-                    &mut total_icons_positioned,
-                    tooltip::Position::Bottom,
-                    &self.app.config.theme,
-                )
-            });
-
-        let extra_top_icons = extra_top_icons.map(|(extra_top_icons, extra_top_padding)| {
-            add_icons_until_there_is_at_least_n_of_them::<MIN_TOP_BOTTOM_ICONS>(
-                extra_top_icons,
-                &mut icons_iter,
-                extra_top_padding,
-                &mut total_icons_positioned,
-                tooltip::Position::Top,
-                &self.app.config.theme,
-            )
-        });
+        // the left and right rows should be large enough to have at least 1 icon
+        // always.
+        let height_added = (px_per_icon - selection_height).max(0.0);
 
-        let extra_extra_top_icons = is_enough_space_at_top.then(|| {
-            position_icons_in_line(
-                self.selection_rect.width,
-                tooltip::Position::Top,
-                &mut total_icons_positioned,
-                &mut icons_iter,
-                icons_len,
-                &self.app.config.theme,
-            )
-        });
+        let top_height = if top_icons.is_some() {
+            px_per_icon
+        } else {
+            0.0
+        };
 
-        let extra_extra_bottom_icons = is_enough_space_at_bottom.then(|| {
-            position_icons_in_line(
-                self.selection_rect.width,
-                tooltip::Position::Bottom,
-                &mut total_icons_positioned,
-                &mut icons_iter,
-                icons_len,
-                &self.app.config.theme,
+        iced::widget::column![
+            // just whitespace necessary to align the icons to the selection
+            Space::with_height(Length::Fixed(sel.y - top_height - height_added / 2.0)).width(Fill),
+        ]
+        // top icon row
+        .push_maybe(top_icons)
+        .push(
+            // right icon row + left icon row
+            match left_icons {
+                Some(left_icons) => row![
+                    Space::with_width(sel.x - px_per_icon).height(Fill),
+                    left_icons
+                ],
+                None => row![Space::with_width(sel.x).height(Fill)],
+            }
+            .push(
+                container(Row::from_vec(inside_icons).spacing(SPACE_BETWEEN_ICONS))
+                    .center_x(theme.frame_width() * 2.0 + sel.width)
+                    .align_bottom(Fill)
+                    .padding(theme.frame_width() * 2.0),
             )
-        });
+            .push_maybe(right_icons)
+            .padding(Padding::default().top(height_added / 2.0))
+            .height(selection_height + height_added),
+        )
+        // bottom icon row
+        .push(bottom_icons)
+        .into()
+    }
+}
 
-        let extra_extra_top_icons =
-            extra_extra_top_icons.map(|(extra_extra_top_icons, extra_extra_top_padding)| {
-                add_icons_until_there_is_at_least_n_of_them::<MIN_TOP_BOTTOM_ICONS>(
-                    extra_extra_top_icons,
-                    &mut icons_iter,
-                    extra_extra_top_padding,
-                    &mut total_icons_positioned,
-                    tooltip::Position::Top,
-                    &self.app.config.theme,
-                )
-            });
-
-        let extra_extra_bottom_icons = extra_extra_bottom_icons.map(
-            |(extra_extra_bottom_icons, extra_extra_bottom_padding)| {
-                add_icons_until_there_is_at_least_n_of_them::<MIN_TOP_BOTTOM_ICONS>(
-                    extra_extra_bottom_icons,
-                    &mut icons_iter,
-                    extra_extra_bottom_padding,
-                    &mut total_icons_positioned,
-                    tooltip::Position::Bottom,
-                    &self.app.config.theme,
-                )
-            },
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iced::Point;
+    use pretty_assertions::assert_eq;
+
+    /// Size of each icon, with the space after it
+    const PX: f32 = 40.0;
+    /// Size of the screen
+    const SCREEN: Size = Size::new(1920.0, 1080.0);
+
+    #[test]
+    fn all_icons_fit_below_a_large_selection() {
+        let layout = Layout::new(
+            5,
+            Rectangle::new(Point::new(500.0, 500.0), Size::new(400.0, 300.0)),
+            SCREEN,
+            PX,
         );
 
-        let right_icons = right_icons.map(|(right_icons, right_padding)| {
-            Column::from_vec(right_icons)
-                .spacing(SPACE_BETWEEN_ICONS)
-                .width(PX_PER_ICON)
-                .padding(Padding::default().top(right_padding))
-        });
+        assert_eq!(
+            layout,
+            Layout {
+                bottom: 0..5,
+                right: 5..5,
+                top: 5..5,
+                left: 5..5,
+                inside: 5..5,
+                more: None,
+                overflow: 5..5,
+            }
+        );
+    }
 
-        let left_icons = left_icons.map(|(left_icons, left_padding)| {
-            Column::from_vec(left_icons)
-                .spacing(SPACE_BETWEEN_ICONS)
-                .width(PX_PER_ICON)
-                .padding(Padding::default().top(left_padding))
-        });
+    #[test]
+    fn small_selection_overflows_into_a_list() {
+        let layout = Layout::new(
+            12,
+            Rectangle::new(Point::new(500.0, 500.0), Size::new(10.0, 10.0)),
+            SCREEN,
+            PX,
+        );
 
-        // there is no way to get amount of children
-        // from a Row. that would be prety useful
-        let mut top_icon_rows_count = 0;
-        let top_icons: Column<_> = extra_extra_top_icons
-            .into_iter()
-            .chain(extra_top_icons)
-            .chain(top_icons)
-            .filter_map(|(icons, padding)| {
-                (!icons.is_empty()).then(|| {
-                    top_icon_rows_count += 1;
-                    row![
-                        Space::with_width(self.selection_rect.x),
-                        Row::from_vec(icons)
-                            .spacing(SPACE_BETWEEN_ICONS)
-                            .height(PX_PER_ICON)
-                            .padding(Padding::default().left(padding))
-                    ]
-                    .into()
-                })
-            })
-            .collect();
+        assert_eq!(
+            layout,
+            Layout {
+                bottom: 0..3,
+                right: 3..4,
+                top: 4..7,
+                left: 7..8,
+                inside: 8..8,
+                // the last icon which fits opens the list
+                more: Some(7),
+                overflow: 7..12,
+            }
+        );
+    }
 
-        let bottom_icons: Column<_> = bottom_icons
-            .into_iter()
-            .chain(extra_bottom_icons)
-            .chain(extra_extra_bottom_icons)
-            .filter_map(|(icons, padding)| {
-                (!icons.is_empty()).then(|| {
-                    row![
-                        Space::with_width(self.selection_rect.x),
-                        Row::from_vec(icons)
-                            .spacing(SPACE_BETWEEN_ICONS)
-                            .height(PX_PER_ICON)
-                            .padding(Padding::default().left(padding))
-                    ]
-                    .into()
-                })
-            })
-            .collect();
+    #[test]
+    fn icons_go_inside_of_a_full_screen_selection() {
+        let layout = Layout::new(5, Rectangle::new(Point::ORIGIN, SCREEN), SCREEN, PX);
 
-        // include the frame so the icons do not touch the frame
-        let selection_height = FRAME_WIDTH * 2.0 + self.selection_rect.height;
+        assert_eq!(layout.inside, 0..5);
+        assert_eq!(layout.more, None);
+        assert!(layout.overflow.is_empty());
+    }
 
-        // the left and right rows should be large enough to have at least 1 icon
-        // always.
-        let height_added = (PX_PER_ICON - selection_height).max(0.0);
+    #[test]
+    fn icons_which_fit_nowhere_have_no_list() {
+        let screen = Size::new(100.0, 20.0);
+        let layout = Layout::new(5, Rectangle::new(Point::ORIGIN, screen), screen, PX);
 
-        iced::widget::column![
-            // just whitespace necessary to align the icons to the selection
-            Space::with_height(Length::Fixed(
-                (top_icon_rows_count as f32) * -PX_PER_ICON + self.selection_rect.y
-                    - height_added / 2.0
-            ))
-            .width(Fill),
-            // top icon row
-            top_icons,
-            // right icon row + left icon row
-            row![Space::with_width(self.selection_rect.x - PX_PER_ICON).height(Fill),]
-                .push_maybe(left_icons)
-                .push(Space::with_width(FRAME_WIDTH * 2.0 + self.selection_rect.width).height(Fill))
-                .push_maybe(right_icons)
-                .padding(Padding::default().top(height_added / 2.0))
-                .height(selection_height + height_added),
-            // bottom icon row
-            bottom_icons,
-        ]
-        .into()
+        assert_eq!(layout.more, None);
+        assert_eq!(layout.overflow, 0..5);
     }
 }
@@ -1,12 +1,24 @@
 //! Renders a tiny numeric input which shows a dimension of the rect and allow resizing it
 
-use super::{App, selection::OptionalSelectionExt as _};
+use super::{App, selection::OptionalSelectionExt as _, selection_icons::icon_tooltip};
 use iced::{
-    Background, Element, Length, Rectangle, Task,
+    Background, Element, Length, Point, Rectangle, Renderer, Size, Task, Theme,
+    advanced::{
+        Clipboard, Layout, Shell, Widget, layout, renderer,
+        widget::{Operation, Tree, tree},
+    },
+    alignment::Vertical,
+    keyboard::{self, key::Named},
+    mouse,
     widget::{self, Space, column, row, text::Shaping},
 };
 
-use crate::{geometry::RectangleExt as _, ui::selection::SelectionIsSome};
+use crate::{
+    geometry::RectangleExt as _,
+    icon,
+    message::Handler as _,
+    ui::selection::{Selection, SelectionIsSome},
+};
 
 /// One of the values in the size indicator has changed
 #[derive(Clone, Debug)]
@@ -27,6 +39,61 @@ pub enum Message {
         /// always be there (to bypass the limitation that we cannot pass `&mut Selection` in a `Message`)
         sel_is_some: SelectionIsSome,
     },
+    /// Start or stop keeping the aspect ratio when the width or height changes
+    ToggleAspectRatioLink,
+    /// Text typed into the width or height. Numbers resize the selection immediately,
+    /// anything else is kept as a draft until Enter is pressed
+    Edit {
+        /// The value which was edited
+        dimension: Dimension,
+        /// New text of the value
+        text: String,
+        /// A key to obtain `&mut Selection` from `Option<Selection>` with a guarantee that it will
+        /// always be there (to bypass the limitation that we cannot pass `&mut Selection` in a `Message`)
+        sel_is_some: SelectionIsSome,
+    },
+    /// Evaluate the draft of the width or height, like `1920/2`, and resize the selection to it
+    Submit {
+        /// The value which was submitted
+        dimension: Dimension,
+        /// A key to obtain `&mut Selection` from `Option<Selection>` with a guarantee that it will
+        /// always be there (to bypass the limitation that we cannot pass `&mut Selection` in a `Message`)
+        sel_is_some: SelectionIsSome,
+    },
+}
+
+impl Message {
+    /// Change the `dimension` of the selection to `value`
+    const fn resize(dimension: Dimension, value: u32, sel_is_some: SelectionIsSome) -> Self {
+        match dimension {
+            Dimension::Width => Self::ResizeHorizontally {
+                new_width: value,
+                sel_is_some,
+            },
+            Dimension::Height => Self::ResizeVertically {
+                new_height: value,
+                sel_is_some,
+            },
+        }
+    }
+}
+
+/// Width or height of the selection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dimension {
+    /// Width of the selection
+    Width,
+    /// Height of the selection
+    Height,
+}
+
+/// Text typed into the width or height which is not a number yet, like `1920/2`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Draft {
+    /// The value which is being edited
+    pub dimension: Dimension,
+    /// Text typed so far
+    pub text: String,
 }
 
 impl crate::message::Handler for Message {
@@ -37,35 +104,88 @@ impl crate::message::Handler for Message {
                 sel_is_some,
             } => {
                 let sel = app.selection.unlock(sel_is_some);
+                let rect = sel.norm().rect;
+                let max = max_size(rect);
+
+                let size = match app.linked_aspect_ratio {
+                    Some(aspect_ratio) => {
+                        linked_size(Dimension::Height, new_height, aspect_ratio, max)
+                    }
+                    None => Size::new(rect.width, (new_height as f32).min(max.height)),
+                };
 
-                // what is the minimum value for `new_height` that would make
-                // this overflow vertically?
-                // We want to make sure the selection cannot get bigger than that.
-                let new_height =
-                    new_height.min((sel.norm().rect.y + sel.norm().rect.height) as u32);
-
-                let dy = new_height as f32 - sel.norm().rect.height;
-                *sel = sel
-                    .norm()
-                    .with_height(|_| new_height as f32)
-                    .with_y(|y| y - dy);
+                *sel = resized(*sel, size);
             }
             Self::ResizeHorizontally {
                 new_width,
                 sel_is_some,
             } => {
                 let sel = app.selection.unlock(sel_is_some);
+                let rect = sel.norm().rect;
+                let max = max_size(rect);
 
-                // what is the minimum value for `new_width` that would make
-                // this overflow vertically?
-                // We want to make sure the selection cannot get bigger than that.
-                let new_width = new_width.min((sel.norm().rect.x + sel.norm().rect.width) as u32);
+                let size = match app.linked_aspect_ratio {
+                    Some(aspect_ratio) => {
+                        linked_size(Dimension::Width, new_width, aspect_ratio, max)
+                    }
+                    None => Size::new((new_width as f32).min(max.width), rect.height),
+                };
 
-                let dx = new_width as f32 - sel.norm().rect.width;
-                *sel = sel
-                    .norm()
-                    .with_width(|_| new_width as f32)
-                    .with_x(|x| x - dx);
+                *sel = resized(*sel, size);
+            }
+            Self::ToggleAspectRatioLink => {
+                app.linked_aspect_ratio = match (app.linked_aspect_ratio, app.selection) {
+                    (Some(_), _) => None,
+                    (None, Some(sel)) if sel.rect.width != 0.0 && sel.rect.height != 0.0 => {
+                        Some((sel.rect.width / sel.rect.height).abs())
+                    }
+                    (None, _) => {
+                        app.errors
+                            .warn("An empty selection has no aspect ratio to keep");
+                        None
+                    }
+                };
+            }
+            Self::Edit {
+                dimension,
+                text,
+                sel_is_some,
+            } => {
+                app.size_indicator_draft = None;
+
+                // if we get "" it means user e.g. just deleted everything
+                let value = if text.is_empty() {
+                    Some(0)
+                } else {
+                    text.parse::<u32>().ok()
+                };
+
+                match value {
+                    Some(value) => return Self::resize(dimension, value, sel_is_some).handle(app),
+                    None => app.size_indicator_draft = Some(Draft { dimension, text }),
+                }
+            }
+            Self::Submit {
+                dimension,
+                sel_is_some,
+            } => {
+                let Some(draft) = app
+                    .size_indicator_draft
+                    .take()
+                    .filter(|draft| draft.dimension == dimension)
+                else {
+                    return Task::none();
+                };
+
+                match crate::expression::eval(&draft.text) {
+                    Ok(value) => {
+                        return Self::resize(dimension, value.round().max(0.0) as u32, sel_is_some)
+                            .handle(app);
+                    }
+                    Err(err) => app
+                        .errors
+                        .warn(format!("Invalid size `{}`: {err}", draft.text)),
+                }
             }
         }
 
@@ -73,28 +193,227 @@ impl crate::message::Handler for Message {
     }
 }
 
+/// Largest size the selection `rect` can have. The bottom right corner does not move,
+/// so the top left corner must stay on the screen
+fn max_size(rect: Rectangle) -> Size {
+    Size::new(rect.x + rect.width, rect.y + rect.height)
+}
+
+/// Size of the selection when the `dimension` is changed to `value`, and the other side
+/// follows the `aspect_ratio`, its width divided by its height
+///
+/// If the size would be larger than `max`, both sides shrink so the aspect ratio stays
+fn linked_size(dimension: Dimension, value: u32, aspect_ratio: f32, max: Size) -> Size {
+    let value = value as f32;
+    let size = match dimension {
+        Dimension::Width => Size::new(value, value / aspect_ratio),
+        Dimension::Height => Size::new(value * aspect_ratio, value),
+    };
+
+    let scale = (max.width / size.width)
+        .min(max.height / size.height)
+        .min(1.0);
+
+    Size::new((size.width * scale).round(), (size.height * scale).round())
+}
+
+/// Change the size of the selection, the bottom right corner does not move
+fn resized(sel: Selection, size: Size) -> Selection {
+    sel.norm()
+        .with_pos(|pos| {
+            Point::new(
+                pos.x + sel.norm().rect.width - size.width,
+                pos.y + sel.norm().rect.height - size.height,
+            )
+        })
+        .with_size(|_| size)
+}
+
+/// A numeric `TextInput`, whose value is stepped with the Up and Down arrow keys while it is focused
+struct Stepped<'a> {
+    /// The input
+    input: widget::TextInput<'a, crate::Message>,
+    /// Message which changes the value by this much
+    on_step: Box<dyn Fn(i32) -> crate::Message + 'a>,
+}
+
+/// State of the `TextInput` inside of `Stepped`
+type InputState =
+    widget::text_input::State<<Renderer as iced::advanced::text::Renderer>::Paragraph>;
+
+// Everything is forwarded to the `TextInput`, which also owns the state
+impl Widget<crate::Message, Theme, Renderer> for Stepped<'_> {
+    fn size(&self) -> Size<Length> {
+        Widget::size(&self.input)
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        Widget::layout(&self.input, tree, renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        Widget::draw(
+            &self.input,
+            tree,
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+    }
+
+    fn tag(&self) -> tree::Tag {
+        Widget::tag(&self.input)
+    }
+
+    fn state(&self) -> tree::State {
+        Widget::state(&self.input)
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        Widget::children(&self.input)
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        Widget::diff(&self.input, tree);
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        Widget::operate(&self.input, tree, layout, renderer, operation);
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &iced::Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, crate::Message>,
+        viewport: &Rectangle,
+    ) {
+        // the `TextInput` ignores Up and Down, so they would move the selection instead
+        if let iced::Event::Keyboard(keyboard::Event::KeyPressed {
+            key: keyboard::Key::Named(key @ (Named::ArrowUp | Named::ArrowDown)),
+            modifiers,
+            ..
+        }) = event
+            && tree.state.downcast_ref::<InputState>().is_focused()
+        {
+            let step = if modifiers.shift() { 10 } else { 1 };
+            let step = if *key == Named::ArrowUp { step } else { -step };
+
+            shell.publish((self.on_step)(step));
+            shell.capture_event();
+            return;
+        }
+
+        Widget::update(
+            &mut self.input,
+            tree,
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        Widget::mouse_interaction(&self.input, tree, layout, cursor, viewport, renderer)
+    }
+}
+
+impl<'a> From<Stepped<'a>> for Element<'a, crate::Message> {
+    fn from(widget: Stepped<'a>) -> Self {
+        Self::new(widget)
+    }
+}
+
+/// Small button with the `label`, which sends the `message` to step the value
+fn stepper<'a>(
+    label: &'a str,
+    message: crate::Message,
+    theme: &'a crate::Theme,
+) -> widget::Button<'a, crate::Message> {
+    widget::button(
+        widget::text(label)
+            .color(theme.size_indicator_fg)
+            .font(theme.font),
+    )
+    .on_press(message)
+    .padding([0.0, 4.0])
+    .style(|_, _| widget::button::Style {
+        background: Some(Background::Color(iced::Color::TRANSPARENT)),
+        ..Default::default()
+    })
+}
+
 /// Renders the indicator for a single dimension (e.g. width or height)
+///
+/// The value can be typed, stepped with the Up and Down arrow keys (10 at a time with Shift),
+/// or with the `-` and `+` buttons around it. Arithmetic like `1920/2` is evaluated on Enter
 fn dimension_indicator<'a>(
     value: u32,
-    on_change: impl Fn(u32) -> crate::Message + 'a,
+    dimension: Dimension,
+    draft: Option<&'a Draft>,
+    sel_is_some: SelectionIsSome,
     theme: &'a crate::Theme,
-) -> widget::TextInput<'a, crate::Message> {
-    let content = value.to_string();
+) -> Element<'a, crate::Message> {
+    let on_change =
+        move |value| crate::Message::SizeIndicator(Message::resize(dimension, value, sel_is_some));
+
+    let content = draft
+        .filter(|draft| draft.dimension == dimension)
+        .map_or_else(|| value.to_string(), |draft| draft.text.clone());
     let input = widget::text_input(Default::default(), content.as_str())
         // HACK: iced does not provide a way to mimic `width: min-content` from CSS
         // so we have to "guesstimate" the width that each character will be
         // `Length::Shrink` makes `width = 0` for some reason
         .width(Length::Fixed((12 * content.len()) as f32))
-        .on_input(move |s| {
-            // if we get "" it means user e.g. just deleted everything
-            if s.is_empty() {
-                on_change(0)
-            } else {
-                s.parse::<u32>()
-                    .ok()
-                    .map_or(crate::Message::NoOp, &on_change)
-            }
+        .on_input(move |text| {
+            crate::Message::SizeIndicator(Message::Edit {
+                dimension,
+                text,
+                sel_is_some,
+            })
         })
+        .on_submit(crate::Message::SizeIndicator(Message::Submit {
+            dimension,
+            sel_is_some,
+        }))
         .style(move |_, _| widget::text_input::Style {
             value: theme.size_indicator_fg,
             selection: theme.text_selection,
@@ -110,7 +429,16 @@ fn dimension_indicator<'a>(
         })
         .padding(0.0);
 
-    input
+    row![
+        stepper("-", on_change(value.saturating_sub(1)), theme),
+        Stepped {
+            input,
+            on_step: Box::new(move |step| on_change(value.saturating_add_signed(step))),
+        },
+        stepper("+", on_change(value.saturating_add(1)), theme),
+    ]
+    .align_y(Vertical::Center)
+    .into()
 }
 
 /// Renders a tiny numeric input which shows a dimension of the rect and allow resizing it
@@ -120,7 +448,7 @@ pub fn size_indicator(
     sel_is_some: SelectionIsSome,
 ) -> Element<crate::Message> {
     const SPACING: f32 = 12.0;
-    const ESTIMATED_INDICATOR_WIDTH: u32 = 120;
+    const ESTIMATED_INDICATOR_WIDTH: u32 = 200;
     const ESTIMATED_INDICATOR_HEIGHT: u32 = 26;
 
     let image_height = app.image.height();
@@ -136,35 +464,97 @@ pub fn size_indicator(
 
     let width = dimension_indicator(
         selection_rect.width as u32,
-        move |new_width| {
-            crate::Message::SizeIndicator(Message::ResizeHorizontally {
-                new_width,
-                sel_is_some,
-            })
-        },
+        Dimension::Width,
+        app.size_indicator_draft.as_ref(),
+        sel_is_some,
         &app.config.theme,
     );
     let height = dimension_indicator(
         selection_rect.height as u32,
-        move |new_height| {
-            crate::Message::SizeIndicator(Message::ResizeVertically {
-                new_height,
-                sel_is_some,
-            })
-        },
+        Dimension::Height,
+        app.size_indicator_draft.as_ref(),
+        sel_is_some,
         &app.config.theme,
     );
 
-    let x = widget::text("✕ ")
+    let x = widget::text("✕")
         .color(app.config.theme.size_indicator_fg)
         .shaping(Shaping::Advanced);
+
+    // keeps the aspect ratio of the selection while it is linked
+    let link = icon_tooltip(
+        widget::button(
+            icon!(Link)
+                .style(move |_, _| widget::svg::Style {
+                    color: Some(if app.linked_aspect_ratio.is_some() {
+                        app.config.theme.size_indicator_fg
+                    } else {
+                        app.config.theme.size_indicator_fg.scale_alpha(0.4)
+                    }),
+                })
+                .width(14.0)
+                .height(14.0),
+        )
+        .on_press(crate::Message::SizeIndicator(
+            Message::ToggleAspectRatioLink,
+        ))
+        .padding([0.0, 4.0])
+        .style(|_, _| widget::button::Style {
+            background: Some(Background::Color(iced::Color::TRANSPARENT)),
+            ..Default::default()
+        }),
+        widget::text(if app.linked_aspect_ratio.is_some() {
+            "Unlink width and height"
+        } else {
+            "Keep aspect ratio"
+        }),
+        widget::tooltip::Position::Top,
+        &app.config.theme,
+    );
     let space = widget::text(" ");
-    let c = widget::container(row![space, width, x, height]).style(|_| widget::container::Style {
-        text_color: None,
-        background: Some(Background::Color(app.config.theme.size_indicator_bg)),
-        border: iced::Border::default(),
-        shadow: iced::Shadow::default(),
-    });
+    let c = widget::container(row![space, width, x, link, height].align_y(Vertical::Center)).style(
+        |_| widget::container::Style {
+            text_color: None,
+            background: Some(Background::Color(app.config.theme.size_indicator_bg)),
+            border: iced::Border::default(),
+            shadow: iced::Shadow::default(),
+        },
+    );
 
     column![vertical_space, row![horizontal_space, c]].into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const MAX: Size = Size::new(1000.0, 1000.0);
+
+    #[test]
+    fn linked_size_keeps_the_aspect_ratio() {
+        let aspect_ratio = 16.0 / 9.0;
+
+        assert_eq!(
+            linked_size(Dimension::Width, 160, aspect_ratio, MAX),
+            Size::new(160.0, 90.0)
+        );
+        // going through 0 does not lose the aspect ratio
+        assert_eq!(
+            linked_size(Dimension::Width, 0, aspect_ratio, MAX),
+            Size::new(0.0, 0.0)
+        );
+        assert_eq!(
+            linked_size(Dimension::Height, 9, aspect_ratio, MAX),
+            Size::new(16.0, 9.0)
+        );
+    }
+
+    #[test]
+    fn linked_size_shrinks_both_sides_to_fit() {
+        assert_eq!(
+            linked_size(Dimension::Width, 1600, 2.0, Size::new(1000.0, 400.0)),
+            Size::new(800.0, 400.0)
+        );
+    }
+}
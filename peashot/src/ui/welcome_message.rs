@@ -17,29 +17,65 @@ const FONT_SIZE: f32 = 13.0;
 const SPACING: f32 = 8.0;
 /// Padding of the tips
 const PADDING: f32 = 10.0;
+/// Height of the welcome message box, with this many tips
+const fn height(tips: usize) -> f32 {
+    30.0 + tips as f32 * FONT_SIZE + tips.saturating_sub(1) as f32 * SPACING + (PADDING * 2.0)
+}
+
 /// Tips: The Key, and Action for each Key
-const TIPS: [(&str, &str); 7] = [
-    ("Mouse", "Select screenshot area"),
-    ("Ctrl + S", "Save screenshot to a file"),
-    ("Enter", "Copy screenshot to clipboard"),
-    ("Right Click", "Snap closest corner to mouse"),
-    ("Shift + Mouse", "Slowly resize / move area"),
-    ("?", "Open Keybindings Cheatsheet"),
-    ("Esc", "Exit"),
-];
-/// Height of the welcome message box
-const HEIGHT: f32 =
-    30.0 + TIPS.len() as f32 * FONT_SIZE + (TIPS.len() - 1) as f32 * SPACING + (PADDING * 2.0);
+///
+/// Keys of the commands are looked up in the config, and the tips of commands
+/// without any keys are left out
+fn tips(app: &super::App) -> Vec<(String, &'static str)> {
+    let keys_of = |command| app.config.keys.keys_of(&command);
+
+    [
+        (Some("Mouse".to_string()), "Select screenshot area"),
+        (
+            keys_of(crate::Command::ImageUpload(
+                crate::image::action::Command::SaveScreenshot,
+            )),
+            "Save screenshot to a file",
+        ),
+        (
+            keys_of(crate::Command::App(super::app::Command::Accept)),
+            app.config.primary_action.description(),
+        ),
+        (
+            Some("Right Click".to_string()),
+            "Snap closest corner to mouse",
+        ),
+        (
+            Some("Shift + Mouse".to_string()),
+            "Slowly resize / move area",
+        ),
+        (
+            keys_of(crate::Command::KeybindingsCheatsheet(
+                super::popup::keybindings_cheatsheet::Command::OpenKeybindingsCheatsheet,
+            )),
+            "Open Keybindings Cheatsheet",
+        ),
+        (
+            keys_of(crate::Command::App(super::app::Command::SmartEscape)),
+            "Exit",
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(keys, action)| Some((keys?, action)))
+    .collect()
+}
 
 /// Renders the welcome message that the user sees when they first launch the program
 pub fn welcome_message(app: &super::App) -> Element<Message> {
     let image_width = app.image.width();
     let image_height = app.image.height();
-    let vertical_space = Space::with_height(image_height / 2 - HEIGHT as u32 / 2);
+    let tips = tips(app);
+    let height = height(tips.len());
+    let vertical_space = Space::with_height(image_height / 2 - height as u32 / 2);
     let horizontal_space = Space::with_width(image_width / 2 - WIDTH / 2);
 
     let stuff = iced::widget::container(
-        TIPS.into_iter()
+        tips.into_iter()
             .map(|(key, action)| {
                 row![
                     row![
@@ -61,16 +97,18 @@ pub fn welcome_message(app: &super::App) -> Element<Message> {
             })
             .collect::<Column<_>>()
             .spacing(SPACING)
-            .height(HEIGHT)
+            .height(height)
             .width(WIDTH)
             .padding(PADDING),
     )
     .style(|_| iced::widget::container::Style {
         text_color: Some(app.config.theme.info_box_fg),
         background: Some(Background::Color(app.config.theme.info_box_bg)),
-        border: iced::Border::default()
+        border: app
+            .config
+            .theme
+            .info_box_border()
             .color(app.config.theme.info_box_border)
-            .rounded(6.0)
             .width(1.5),
         shadow: iced::Shadow::default(),
     });
@@ -0,0 +1,173 @@
+//! A small toast shown after saving a screenshot, with a button to undo the save
+//!
+//! The screenshot is saved after the main window has closed, and a process can only
+//! ever create a single window event loop. So the toast runs in its own process,
+//! which lets ferrishot exit and print its output right away.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use iced::{
+    Element,
+    Length::Fill,
+    Point, Size, Task,
+    alignment::Vertical,
+    widget::{button, container, row, text},
+    window,
+};
+
+/// An argument that can be passed into the program to signal that it should show the toast
+/// instead. This can be anything as long as it is unlikely to be passed in by the user by mistake.
+pub const UNDO_SAVE_ID: &str = "__ferrishot_undo_save";
+
+/// Size of the toast
+const SIZE: Size = Size::new(420.0, 60.0);
+
+/// Space between the toast and the edges of the screen
+const MARGIN: f32 = 20.0;
+
+/// Show the toast in a new process, offering to undo saving the file at `path`
+/// for `timeout`
+///
+/// # Errors
+///
+/// Could not spawn the process
+pub fn spawn(path: &Path, timeout: Duration) -> std::io::Result<()> {
+    use std::process;
+
+    process::Command::new(std::env::current_exe()?)
+        .arg(UNDO_SAVE_ID)
+        .arg(path)
+        .arg(timeout.as_millis().to_string())
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::inherit())
+        .spawn()?;
+
+    Ok(())
+}
+
+/// Message of the toast
+#[derive(Debug, Clone)]
+enum Message {
+    /// Move the saved file to the trash
+    Undo,
+    /// Close the toast
+    Close,
+}
+
+/// State of the toast
+#[derive(Debug)]
+struct Toast {
+    /// The saved file
+    path: PathBuf,
+    /// Could not move the file to the trash
+    error: Option<String>,
+}
+
+impl Toast {
+    /// Update the toast
+    fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::Undo => match trash::delete(&self.path) {
+                Ok(()) => {
+                    log::info!("Moved {} to the trash", self.path.display());
+                    iced::exit()
+                }
+                Err(err) => {
+                    log::error!("Failed to move {} to the trash: {err}", self.path.display());
+                    self.error = Some(format!("Could not undo: {err}"));
+                    Task::none()
+                }
+            },
+            Message::Close => iced::exit(),
+        }
+    }
+
+    /// Render the toast
+    fn view(&self) -> Element<Message> {
+        let file_name = self.path.file_name().map_or_else(
+            || self.path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+
+        container(
+            row![
+                text(
+                    self.error
+                        .clone()
+                        .unwrap_or_else(|| format!("Saved {file_name}"))
+                )
+                .width(Fill),
+                button("Undo").on_press(Message::Undo),
+            ]
+            .spacing(10.0)
+            .align_y(Vertical::Center),
+        )
+        .padding(10.0)
+        .center_y(Fill)
+        .into()
+    }
+}
+
+/// Show the toast, until it times out or the save is undone
+///
+/// # Panics
+///
+/// Will panic if the toast was invoked incorrectly. That's fine because
+/// it should only be invoked from this app with [`spawn`], never from the outside.
+///
+/// We expect 3 arguments:
+///
+/// 1. ID of the toast
+/// 2. path to the saved file
+/// 3. how long to show the toast for, in milliseconds
+///
+/// # Errors
+///
+/// Could not create the window
+pub fn run() -> iced::Result {
+    let mut args = std::env::args_os().skip(1);
+
+    assert_eq!(
+        args.next().as_deref(),
+        Some(std::ffi::OsStr::new(UNDO_SAVE_ID)),
+        "this function must be invoked from the toast process"
+    );
+
+    let path = PathBuf::from(args.next().expect("saved file path"));
+    let timeout = args
+        .next()
+        .and_then(|millis| millis.to_str()?.parse().ok())
+        .map(Duration::from_millis)
+        .expect("valid timeout");
+
+    iced::application(
+        move || {
+            (
+                Toast {
+                    path: path.clone(),
+                    error: None,
+                },
+                Task::perform(tokio::time::sleep(timeout), |()| Message::Close),
+            )
+        },
+        Toast::update,
+        Toast::view,
+    )
+    .window(window::Settings {
+        size: SIZE,
+        position: window::Position::SpecificWith(|window, monitor| {
+            Point::new(
+                monitor.width - window.width - MARGIN,
+                monitor.height - window.height - MARGIN,
+            )
+        }),
+        level: window::Level::AlwaysOnTop,
+        decorations: false,
+        resizable: false,
+        ..Default::default()
+    })
+    .title("ferrishot")
+    .run()
+}
@@ -0,0 +1,103 @@
+//! `ferrishot upload <FILE>` uploads an existing image, without taking a screenshot
+//!
+//! The image goes to the same services as the screenshots. Without `--service`,
+//! all of them are tried at once and the first link wins.
+
+use std::path::{Path, PathBuf};
+
+use indoc::formatdoc;
+
+use crate::image::action::JsonOutput;
+use crate::image::upload::{ImageUploadService, ImageUploaded};
+
+/// Error uploading a file
+#[derive(thiserror::Error, miette::Diagnostic, Debug)]
+pub enum Error {
+    /// The file is not an image which can be read
+    #[error("could not read the image {}: {source}", path.display())]
+    Read {
+        /// The file
+        path: PathBuf,
+        /// Why it could not be read
+        source: image::ImageError,
+    },
+    /// None of the services accepted the image
+    #[error("failed to upload the image: {}", .0.join(", "))]
+    #[diagnostic(help("Check your internet connection, or run `ferrishot doctor`"))]
+    Upload(Vec<String>),
+}
+
+/// Upload the image at `path` to the `service`, or to the first service which accepts it
+///
+/// # Returns
+///
+/// What to print: the link and information about the image, in JSON with `is_json`
+pub async fn run(
+    path: &Path,
+    service: Option<ImageUploadService>,
+    is_json: bool,
+) -> Result<String, Error> {
+    let (width, height) = image::image_dimensions(path).map_err(|source| Error::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let ImageUploaded { link, expires_in } = match service {
+        Some(service) => service
+            .upload_image(path)
+            .await
+            .map_err(|err| Error::Upload(vec![err.to_string()]))?,
+        None => crate::image::upload::upload(path)
+            .await
+            .map_err(Error::Upload)?,
+    };
+
+    let file_size_bytes = path.metadata().map(|meta| meta.len()).unwrap_or(0);
+    let file_size = human_bytes::human_bytes(file_size_bytes as f64);
+
+    Ok(if is_json {
+        JsonOutput::Upload {
+            width,
+            height,
+            file_size,
+            file_size_in_bytes: file_size_bytes,
+            link: &link,
+            expires_in,
+        }
+        .to_string()
+    } else {
+        let green = anstyle::AnsiColor::Green
+            .on_default()
+            .effects(anstyle::Effects::BOLD);
+        let reset = anstyle::Reset;
+
+        formatdoc! {
+            "
+                {green}✓{reset} Image uploaded to {link}
+
+                width: {width} px
+                height: {height} px
+                file size: {file_size}
+                expires in: {expires_in}
+            "
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_an_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.png");
+        std::fs::write(&path, "not an image").unwrap();
+
+        let result = tokio::runtime::Runtime::new()
+            .unwrap()
+            .block_on(run(&path, None, false));
+
+        assert!(matches!(result, Err(Error::Read { .. })));
+    }
+}
@@ -0,0 +1,24 @@
+//! The clipboard works without the daemon, on Windows and macOS
+#![cfg(not(target_os = "linux"))]
+#![allow(clippy::unwrap_used, reason = "relaxed rules in tests")]
+
+use std::borrow::Cow;
+
+/// Both are in the same test, because tests run in parallel and share the clipboard
+#[test]
+fn set_text_and_image() {
+    ferrishot::set_text("copied by ferrishot").unwrap();
+    assert_eq!(
+        arboard::Clipboard::new().unwrap().get_text().unwrap(),
+        "copied by ferrishot"
+    );
+
+    ferrishot::set_image(arboard::ImageData {
+        width: 2,
+        height: 1,
+        bytes: Cow::Borrowed(&[255, 0, 0, 255, 0, 0, 255, 255]),
+    })
+    .unwrap();
+    let image = arboard::Clipboard::new().unwrap().get_image().unwrap();
+    assert_eq!((image.width, image.height), (2, 1));
+}